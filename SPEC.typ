@@ -2,7 +2,7 @@
 #set page(numbering: "1")
 
 #align(center, text(25pt)[
-  *Resurgence Virtual Machine Specification, Version 7.0*
+  *Resurgence Virtual Machine Specification, Version 7.9*
 ])
 
 #align(center, text(12pt)[
@@ -152,7 +152,7 @@ The Accumulator SHALL be a 64-bit floating point that can be used in any registe
 #pagebreak(weak: true)
 
 = Instructions
-Resurgence has 25 instructions. This part of the specification defines those instructions. The reference implementation also declares, but does not define, 5 more instructions.
+Resurgence has 50 instructions. This part of the specification defines those instructions. The reference implementation also declares, but does not define, 5 more instructions.
 #footnote[#link("https://github.com/Resurgence-VM-Development/Resurgence/blob/8bfe13f9205b28fcea04e0a527bd05fe451d5a9f/src/objects/instruction.rs#L189", "Additional instructions in the Resurgence reference implementation (link)")]
 Since these are not defined nor formalized, they will not be included in this version of the spec. Developers should simply ignore these instructions.
 
@@ -214,6 +214,38 @@ Adds $n$ to the Instuction Pointer, where $n$ is a signed 64-bit integer. If $n$
 
 It SHALL be undefined behavior if $n$ makes the Instruction Pointer go beyond bounds, or if $n$ makes the Instruction Pointer become negative. For instance, if the instruction pointer is at 9, then `jump -11` is undefined behavior.
 
+=== JumpTo
+```
+jump_to n <u64>
+```
+Sets the Instruction Pointer to $n$, where $n$ is an unsigned 64-bit integer.
+
+Implementations MUST indicate failure if $n$ goes beyond bounds.
+
+=== JumpIfTrue
+```
+jump_if_true src <REG>, n <u64>
+```
+Sets the Instruction Pointer to $n$ if `src` holds _true_, where $n$ is an unsigned 64-bit integer. Otherwise, execution continues with the following instruction.
+
+Implementations MUST indicate failure if `src` does not hold a Bool, or if the jump is taken and $n$ goes beyond bounds.
+
+=== JumpIfFalse
+```
+jump_if_false src <REG>, n <u64>
+```
+Sets the Instruction Pointer to $n$ if `src` holds _false_, where $n$ is an unsigned 64-bit integer. Otherwise, execution continues with the following instruction.
+
+Implementations MUST indicate failure if `src` does not hold a Bool, or if the jump is taken and $n$ goes beyond bounds.
+
+=== JumpTable
+```
+jump_table src <REG>, default <u64>, table <[u64]>
+```
+Sets the Instruction Pointer to the entry of `table` at the index held by `src`. If the index is negative or not less than the length of `table`, the Instruction Pointer is set to `default` instead.
+
+Implementations MUST indicate failure if `src` does not hold an Int, or if the selected instruction goes beyond bounds.
+
 === Call
 ```
 call n <u64>
@@ -326,7 +358,7 @@ In the future, `stack_mov` may be merged with `stack_pop`.
 ```
 add dst <REG>, src_1 <REG>, src_2 <REG>
 ```
-Adds `src_1` and `src_2`, storing the result in `dst`. If `src_1` holds an address and `src_2` holds an integer, then pointer arithmethic can be performed.
+Adds `src_1` and `src_2`, storing the result in `dst`. If `src_1` holds an address and `src_2` holds an integer, then pointer arithmethic can be performed. If both `src_1` and `src_2` hold Strings, then the result SHALL be `src_2` appended to `src_1`.
 
 The type of the result MUST be:
 - A. The type of `src_1` if both `src_1` and `src_2` are integers
@@ -338,7 +370,7 @@ The type of the result MUST be:
 
 It SHALL be undefined behavior for the following:
 - For `src_1` and `src_2` to both hold addresses
-- For `src_1` and/or `src_2` to hold a non-numeric type
+- For `src_1` and/or `src_2` to hold a non-numeric type, unless both hold Strings
 
 === Sub
 ```
@@ -446,7 +478,7 @@ greater src_1 <REG>, src_2 <REG>
 
 Compares checks if `src_1` is greater than `src_2`. If the result is true, then the instruction pointer is incremented by one and the following instruction is skipped.
 
-Both `src_1` and `src_2` MUST be numeric types. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float.
+Both `src_1` and `src_2` MUST be numeric types or both MUST be Strings. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float. Strings SHALL be compared lexicographically by Unicode code point.
 
 === Less
 ```
@@ -455,7 +487,7 @@ less src_1 <REG>, src_2 <REG>
 
 Compares checks if `src_1` is less than `src_2`. If the result is true, then the instruction pointer is incremented by one and the following instruction is skipped.
 
-Both `src_1` and `src_2` MUST be numeric types. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float.
+Both `src_1` and `src_2` MUST be numeric types or both MUST be Strings. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float. Strings SHALL be compared lexicographically by Unicode code point.
 
 === GreaterEqual
 ```
@@ -464,7 +496,7 @@ greater_equal src_1 <REG>, src_2 <REG>
 
 Compares checks if `src_1` is greater than or equal to `src_2`. If the result is true, then the instruction pointer is incremented by one and the following instruction is skipped.
 
-Both `src_1` and `src_2` MUST be numeric types. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float.
+Both `src_1` and `src_2` MUST be numeric types or both MUST be Strings. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float. Strings SHALL be compared lexicographically by Unicode code point.
 
 === LessEqual
 ```
@@ -473,7 +505,155 @@ less_equal src_1 <REG>, src_2 <REG>
 
 Compares checks if `src_1` is less than or equal to `src_2`. If the result is true, then the instruction pointer is incremented by one and the following instruction is skipped.
 
-Both `src_1` and `src_2` MUST be numeric types. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float.
+Both `src_1` and `src_2` MUST be numeric types or both MUST be Strings. It SHALL be undefined behavior otherwise. In addition, if one of the source registers is a float and the other an int, then the int SHALL be interpreted as a float. Strings SHALL be compared lexicographically by Unicode code point.
+
+== Conversion
+=== ToInt
+```
+to_int dst <REG>, src <REG>
+```
+Converts the value in `src` to an Int, storing the result in `dst`.
+- A Float SHALL be truncated towards zero. Implementations MUST indicate failure if the Float is NaN or can not be represented as an Int.
+- A String SHALL be parsed as a base 10 Int. Implementations MUST indicate failure if the String is not a valid Int.
+
+Implementations MUST indicate failure if `src` holds any other non-Int type.
+
+=== ToDouble
+```
+to_double dst <REG>, src <REG>
+```
+Converts the value in `src` to a Float, storing the result in `dst`.
+- An Int SHALL be converted exactly. Implementations MUST indicate failure if the Int can not be represented as a Float without losing precision.
+- A String SHALL be parsed as a Float. Implementations MUST indicate failure if the String is not a valid Float.
+
+Implementations MUST indicate failure if `src` holds a non-numeric type other than String.
+
+=== ToString
+```
+to_string dst <REG>, src <REG>
+```
+Converts the value in `src` to a String, storing the result in `dst`. `src` MUST hold an Int, Float, String, or Bool. Implementations MUST indicate failure otherwise.
+
+=== ToBool
+```
+to_bool dst <REG>, src <REG>
+```
+Converts the value in `src` to a Bool based on its truthiness, storing the result in `dst`. The result SHALL be _false_ if `src` holds `0`, `0.0`, NaN, an empty String, or _false_, and _true_ otherwise.
+
+=== TypeOf
+```
+type_of dst <REG>, src <REG>
+```
+Stores an Int describing the type of the value in `src` in `dst`. The Int SHALL be the same value used to describe the type in the #link(<constants_table>, "Constants Table").
+
+== Strings
+All indices and lengths used by String instructions SHALL count Unicode scalar values (characters), not bytes.
+
+=== StrLen
+```
+str_len dst <REG>, src <REG>
+```
+Stores the number of characters in the String held by `src` in `dst` as an Int.
+
+=== Substr
+```
+substr dst <REG>, src <REG>, start <REG>, length <REG>
+```
+Stores the String of `length` characters beginning at character `start` of the String held by `src` in `dst`. Both `start` and `length` MUST hold Ints.
+
+Implementations MUST indicate failure if `start` or `length` are negative, or if the substring extends beyond the end of the String.
+
+=== CharAt
+```
+char_at dst <REG>, src <REG>, index <REG>
+```
+Stores the character at `index` of the String held by `src` in `dst` as a String. `index` MUST hold an Int.
+
+Implementations MUST indicate failure if `index` is negative or not less than the number of characters in the String.
+
+== Vectors
+A Vector SHALL be an ordered list of values of any type. All indices used by Vector instructions MUST be held as Ints, and implementations MUST indicate failure if an index is negative or not less than the length of the Vector.
+
+=== VecNew
+```
+vec_new dst <REG>
+```
+Stores a new, empty Vector in `dst`.
+
+=== VecGet
+```
+vec_get dst <REG>, vec <REG>, index <REG>
+```
+Stores a copy of the element at `index` of the Vector held by `vec` in `dst`.
+
+=== VecSet
+```
+vec_set vec <REG>, index <REG>, src <REG>
+```
+Replaces the element at `index` of the Vector held by `vec` with a copy of the value in `src`. `vec` MUST be a `GLOBAL` or `LOCAL` register.
+
+=== VecPush
+```
+vec_push vec <REG>, src <REG>
+```
+Appends a copy of the value in `src` to the end of the Vector held by `vec`. `vec` MUST be a `GLOBAL` or `LOCAL` register.
+
+=== VecPop
+```
+vec_pop dst <REG>, vec <REG>
+```
+Removes the last element of the Vector held by `vec` and stores it in `dst`. `vec` MUST be a `GLOBAL` or `LOCAL` register, and implementations MUST indicate failure if the Vector is empty.
+
+=== VecLen
+```
+vec_len dst <REG>, vec <REG>
+```
+Stores the number of elements in the Vector held by `vec` in `dst` as an Int.
+
+== Maps
+A Map SHALL be an associative array of values of any type, stored under keys which MUST be either Ints or Strings. Implementations MUST indicate failure if a key of any other type is used.
+
+=== MapNew
+```
+map_new dst <REG>
+```
+Stores a new, empty Map in `dst`.
+
+=== MapGet
+```
+map_get dst <REG>, map <REG>, key <REG>
+```
+Stores a copy of the value stored under `key` of the Map held by `map` in `dst`. Implementations MUST indicate failure if the Map does not contain `key`.
+
+=== MapSet
+```
+map_set map <REG>, key <REG>, src <REG>
+```
+Stores a copy of the value in `src` under `key` of the Map held by `map`, replacing the previous value if there was one. `map` MUST be a `GLOBAL` or `LOCAL` register.
+
+=== MapRemove
+```
+map_remove map <REG>, key <REG>
+```
+Removes `key` and its value from the Map held by `map` if the Map contains `key`. `map` MUST be a `GLOBAL` or `LOCAL` register.
+
+=== MapContains
+```
+map_contains dst <REG>, map <REG>, key <REG>
+```
+Stores _true_ in `dst` if the Map held by `map` contains `key`, and _false_ otherwise.
+
+=== MapLen
+```
+map_len dst <REG>, map <REG>
+```
+Stores the number of entries in the Map held by `map` in `dst` as an Int.
+
+=== MapKeys
+```
+map_keys dst <REG>, map <REG>
+```
+Stores the keys of the Map held by `map` in `dst` as a Vector. Int keys SHALL be ordered before String keys, and keys of the same type SHALL be in ascending order.
 
 #pagebreak(weak: true)
 
@@ -546,14 +726,21 @@ Constants are an enumeration type that describes a hard-coded value in the bytec
   [String], [03],
   [Boolean], [04],
   [Register], [05],
+  [Vector], [06],
+  [Map], [07],
 )
 
 To describe a Constant containing a String, for example, the constant type field would be set to `03` and would be followed by the String's `u64` length field and its textual contents.
 
+A Vector is described by a `u64` length field indicating the number of elements, followed by each element as a Constant. Bytecode with a major version of 7 and a minor version below 6 instead uses a `u8` length field; implementations SHOULD accept this layout when reading such bytecode, but MUST NOT write it.
+
+A Map is described by a `u64` length field indicating the number of entries, followed by each entry as a key Constant (which MUST be an Integer or String) and a value Constant.
+
 == Bytecode Header
 The Bytecode contains a header before instructions are listed. This header contains the following:
 - A magic number, to identify that this is valid Bytecode information
 - Major and minor specification version that the Bytecode is compliant with
+- Header flags, describing how the rest of the Bytecode is stored
 - Constants table, defining all constant values used in the program
 - Imports table, listing runtime features required by the program
 - Exports table, listing functions that the program implements, as well as the position in the instructions that the function starts at
@@ -566,6 +753,60 @@ The magic number is a fixed 32-bit value at the beginning of all Bytecode instan
 
 The version information is expressed as a `u16` major version, followed by a `u16` minor version. This version number is explained in #link(<versioning>,"the Versioning section"). To ensure stability, implementations MUST also check this value to ensure runtime compatibility and prevent undefined behavior from occurring.
 
+Within major version 7, instructions and constants were added in the following minor versions. Implementations MUST indicate failure when reading Bytecode that uses an instruction or constant newer than its minor version.
+- 7.1: `JumpTo`, `JumpIfTrue`, `JumpIfFalse` and `JumpTable`
+- 7.2: `ToInt`, `ToDouble`, `ToString`, `ToBool` and `TypeOf`
+- 7.3: `StrLen`, `Substr` and `CharAt`
+- 7.4: the vector instructions
+- 7.5: Map constants and the map instructions
+
+=== Header Flags
+`flags <u8>`
+
+Beginning with version 7.8, the version information is followed by a `u8` flags field. Implementations MUST indicate failure if a flag they do not recognize is set. The following flags are defined:
+#table(
+  columns: 2,
+  [*Flag*], [*Value*],
+  [Compressed], [01],
+)
+
+If the Compressed flag is set, everything following the flags field is compressed as a single raw DEFLATE stream (RFC 1951). Implementations MUST decompress it before reading the sections it contains, and MUST indicate failure if it can not be decompressed. Checksum and Signature sections cover the uncompressed Bytecode.
+
+=== Sections <sections>
+`(tag <u8>, flags <u8>, length <u64>, contents), ...`
+
+Beginning with version 7.7, everything following the version information (and header flags) is stored in sections. Each section is expressed as a `u8` tag describing its type, a `u8` flags field, a `u64` length value indicating the size of its contents in bytes, and then its contents. Sections are read until the end of the Bytecode. Bytecode of earlier versions instead stores the constants table, imports table, exports table and instructions section directly after each other, in that order, without section headers.
+
+The following table lists the values (formatted as hexadecimals) that MUST be used to describe the section types:
+#table(
+  columns: 2,
+  [*Section*], [*Value*],
+  [Constants Table], [01],
+  [Imports Table], [02],
+  [Exports Table], [03],
+  [Instructions], [04],
+  [Custom], [05],
+  [Metadata], [06],
+  [Checksum], [07],
+  [Signature], [08],
+)
+
+Bit `01` of the flags field marks a section as required. Implementations MUST indicate failure when encountering a required section with a tag they do not recognize, and MUST skip sections with unrecognized tags that are not marked as required. Implementations MUST indicate failure if the contents of a recognized section do not exactly fill its length. Writers SHOULD mark the constants table, imports table, exports table and instructions sections as required.
+
+A Custom section contains a String holding its name, followed by arbitrary bytes filling the rest of the section. Custom sections let applications store their own data alongside the code; implementations MUST NOT change the meaning of the Bytecode based on their contents.
+
+A Metadata section describes the module contained in the Bytecode, allowing applications to show it and to check its requirements before running any code. It SHOULD NOT be marked as required, and MUST NOT appear more than once. Its contents are expressed as follows:
+
+`name <String>, version <Version>, author <String>, description <String>, min_host_api <Version>, length <u64>, (key <String>, value <String>), ...`
+
+Each `Version` is expressed as three `u64` values holding the major, minor and patch numbers of a semantic version. `min_host_api` is the lowest version of the application's API that the module works with; an application providing version `X.Y.Z` of its API SHOULD refuse to run the module unless `X` equals the major number of `min_host_api` and `X.Y.Z` is not older than `min_host_api`. The `u64` length value indicates the number of arbitrary key/value String pairs that follow.
+
+A Checksum section allows implementations to detect corrupted Bytecode. It is expressed as a `u8` algorithm value followed by the checksum of every byte of the Bytecode before the section, beginning with the magic number. The only algorithm currently defined is CRC-32 (IEEE 802.3), using the value `01` and a `u32` checksum. Implementations MUST indicate failure if a Checksum section does not match, or uses an algorithm they do not recognize.
+
+A Signature section allows implementations to verify who published the Bytecode. It is expressed as a String holding the identifier of the signing key, followed by the signature of every byte of the Bytecode before the section, beginning with the magic number, filling the rest of the section. The signature algorithm is agreed upon by the application signing the Bytecode and the application running it. Implementations that verify signatures MUST indicate failure if the signature is not valid for a key they trust.
+
+Checksum and Signature sections SHOULD NOT be marked as required. A Checksum section MUST only be followed by a Signature section, and a Signature section MUST be the last section of the Bytecode.
+
 === Constants Table <constants_table>
 `length <u32>, constant <Constant>, ...`
 
@@ -603,6 +844,10 @@ The instructions type values are listed in the following table. Values are expre
   [Free], [02],
   [FrameFree], [16],
   [Jump], [03],
+  [JumpTo], [1A],
+  [JumpIfTrue], [1B],
+  [JumpIfFalse], [1C],
+  [JumpTable], [1D],
   [Call], [04],
   [ExtCall], [05],
   [Ret], [19],
@@ -623,7 +868,50 @@ The instructions type values are listed in the following table. Values are expre
   [Less], [12],
   [GreaterEqual], [13],
   [LessEqual], [14],
+  [ToInt], [1E],
+  [ToDouble], [1F],
+  [ToString], [20],
+  [ToBool], [21],
+  [TypeOf], [22],
+  [StrLen], [23],
+  [Substr], [24],
+  [CharAt], [25],
+  [VecNew], [26],
+  [VecGet], [27],
+  [VecSet], [28],
+  [VecPush], [29],
+  [VecPop], [2A],
+  [VecLen], [2B],
+  [MapNew], [2C],
+  [MapGet], [2D],
+  [MapSet], [2E],
+  [MapRemove], [2F],
+  [MapContains], [30],
+  [MapLen], [31],
+  [MapKeys], [32],
+)
+
+
+The `JumpTable` instruction is followed by its `REG` and `default` arguments, then a `u64` length value indicating the number of entries in its table, followed by each entry as a `u64`.
+
+=== Compact Encoding
+Beginning with version 7.9, instruction parameters use a compact encoding built on LEB128 variable-length integers, in which each byte holds 7 bits of the value (least significant group first) and sets its high bit if another byte follows. Parameters are encoded as follows:
+- `u32` and `u64` parameters, including the length of a `JumpTable` table, are unsigned LEB128 integers
+- `i64` parameters are signed LEB128 integers
+- A `REG` is a single unsigned LEB128 integer holding the position shifted left by 2 bits, with the location in the low 2 bits
+- A `REG` directly followed by its `REG_REF` is a single unsigned LEB128 integer holding the position shifted left by 3 bits, with bit 2 set if the register is de-referenced and the location in the low 2 bits
+- A `LOC` is unchanged
+
+The following table lists the values of the packed location bits:
+#table(
+  columns: 2,
+  [*Location*], [*Value*],
+  [Constant], [0],
+  [Accumulator], [1],
+  [Global Register], [2],
+  [Local Register], [3],
 )
 
+Implementations MUST indicate failure if a LEB128 integer does not fit in 64 bits, or if a value does not fit in the parameter it encodes. The Constants Table, Imports Table and Exports Table are not affected by the compact encoding.
 
-*NOTE:* Unlike other sections of the bytecode, this section does NOT specify a length field. Implementations MUST read instructions until the read cursor reaches the end of the bytecode. If a given bytecode instance does not have the appropriate length given its instructions, implementations MUST indicate failure in some way.
+*NOTE:* The instructions themselves do NOT specify a length field. Implementations MUST read instructions until the read cursor reaches the end of the Instructions section, or the end of the bytecode for versions before 7.7. If a given bytecode instance does not have the appropriate length given its instructions, implementations MUST indicate failure in some way.
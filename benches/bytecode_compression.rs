@@ -0,0 +1,60 @@
+//! Compares the size and load time of raw and compressed bytecode.
+//!
+//! Run with `cargo bench --bench bytecode_compression`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use resurgence::bytecode::{read_bytecode, write_bytecode, write_bytecode_with_options, WriteOptions};
+use resurgence::{codegen, CodeHolder};
+
+const ITERATIONS: u32 = 200;
+
+/// Builds a program resembling a generated script: many similar strings and register heavy code
+fn generated_script(lines: u32) -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    for i in 0..lines {
+        let string = codegen::generate_string_constant(
+            &mut holder,
+            format!("dialogue.chapter_{}.speaker_{}.line_{}", i / 100, i % 7, i),
+        );
+        let len = codegen::generate_int_constant(&mut holder, 0);
+        codegen::generate_str_len(&mut holder, len, string);
+    }
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+/// Returns the average time it takes to load `bytes`
+fn load_time(bytes: &[u8]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(read_bytecode(black_box(bytes)).unwrap());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let options = WriteOptions {
+        compress: true,
+        ..Default::default()
+    };
+
+    println!("{:>8} {:>12} {:>12} {:>7} {:>12} {:>12}", "lines", "raw", "compressed", "ratio", "raw load", "comp. load");
+    for lines in [100, 1_000, 10_000] {
+        let holder = generated_script(lines);
+        let raw = write_bytecode(&holder).unwrap();
+        let compressed = write_bytecode_with_options(&holder, &options).unwrap();
+
+        println!(
+            "{:>8} {:>10} B {:>10} B {:>6.1}% {:>12?} {:>12?}",
+            lines,
+            raw.len(),
+            compressed.len(),
+            compressed.len() as f64 / raw.len() as f64 * 100.0,
+            load_time(&raw),
+            load_time(&compressed),
+        );
+    }
+}
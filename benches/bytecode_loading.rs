@@ -0,0 +1,64 @@
+//! Compares loading bytecode with `read_bytecode` against borrowing it with `BytecodeView` and
+//! running it with `Interpreter::from_bytes`.
+//!
+//! Run with `cargo bench --bench bytecode_loading`.
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use resurgence::bytecode::{read_bytecode, write_bytecode, BytecodeView};
+use resurgence::{codegen, CodeHolder, Interpreter};
+
+const ITERATIONS: u32 = 200;
+
+/// Builds a script bundle with many long string constants, such as localized dialogue
+fn script_bundle(lines: u32) -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    for i in 0..lines {
+        let string = codegen::generate_string_constant(
+            &mut holder,
+            format!("chapter {} line {}: {}", i / 100, i, "lorem ipsum dolor sit amet ".repeat(4)),
+        );
+        let len = codegen::generate_int_constant(&mut holder, 0);
+        codegen::generate_str_len(&mut holder, len, string);
+    }
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+/// Returns the average time `load` takes
+fn measure<F: FnMut()>(mut load: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        load();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>14} {:>14} {:>14}", "lines", "size", "read_bytecode", "view", "from_bytes");
+    for lines in [100, 1_000, 10_000] {
+        let bytes: Arc<[u8]> = write_bytecode(&script_bundle(lines)).unwrap().into();
+
+        let read = measure(|| {
+            black_box(read_bytecode(black_box(&bytes)).unwrap());
+        });
+        let view = measure(|| {
+            black_box(BytecodeView::new(black_box(&bytes)).unwrap());
+        });
+        let interpreter = measure(|| {
+            black_box(Interpreter::from_bytes(black_box(bytes.clone())).unwrap());
+        });
+
+        println!(
+            "{:>8} {:>10} B {:>14?} {:>14?} {:>14?}",
+            lines,
+            bytes.len(),
+            read,
+            view,
+            interpreter
+        );
+    }
+}
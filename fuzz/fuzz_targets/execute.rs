@@ -0,0 +1,33 @@
+//! Runs any bytecode that passes verification with fuel, recursion and memory limits. Bad programs
+//! must stop with an error instead of panicking, running out of fuel must stop infinite loops and
+//! the memory limits must keep the fuzzer from running out of memory.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resurgence::bytecode;
+use resurgence::{ExecutionEngine, Interpreter, MemoryLimits};
+
+fuzz_target!(|data: &[u8]| {
+    let holder = match bytecode::read_bytecode(data) {
+        Ok(holder) => holder,
+        Err(_) => return,
+    };
+    if holder.verify().is_err() || holder.instructions.is_empty() {
+        return;
+    }
+
+    let mut interpreter = Interpreter::from(holder);
+    interpreter.set_fuel(Some(10_000));
+    interpreter.set_max_depth(64);
+    interpreter.set_memory_limits(MemoryLimits {
+        max_registers: 1 << 12,
+        max_globals: 1 << 12,
+        max_stack: 1 << 12,
+        max_string_length: 1 << 12,
+        max_vector_size: 1 << 12,
+    });
+    if interpreter.resolve_imports().is_err() {
+        return;
+    }
+    let _ = interpreter.execute_instruction(0);
+});
@@ -0,0 +1,21 @@
+//! Feeds arbitrary bytes to the bytecode reader, which must reject bad input with an error
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resurgence::bytecode::{self, BytecodeView};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = bytecode::read_bytecode(data);
+
+    // The borrowed view parses lazily, so walk everything it exposes
+    if let Ok(view) = BytecodeView::new(data) {
+        for i in 0..view.constant_count() {
+            let _ = view.constant(i);
+        }
+        for ins in view.instructions() {
+            if ins.is_err() {
+                break;
+            }
+        }
+    }
+});
@@ -0,0 +1,19 @@
+//! Checks that any bytecode the reader accepts is written back and read again unchanged
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resurgence::bytecode;
+
+fuzz_target!(|data: &[u8]| {
+    let holder = match bytecode::read_bytecode(data) {
+        Ok(holder) => holder,
+        Err(_) => return,
+    };
+    let written = bytecode::write_bytecode(&holder).expect("accepted bytecode must be writable");
+    let reread = bytecode::read_bytecode(&written).expect("written bytecode must be readable");
+    assert_eq!(
+        bytecode::write_bytecode(&reread).unwrap(),
+        written,
+        "bytecode changed after a round trip"
+    );
+});
@@ -51,6 +51,8 @@ extern "C" {
 struct RVMInterpreter;
 struct RVMCodeHolder;
 struct RVMState;
+struct RVMMap;
+struct RVMInterruptHandle;
 
 /**
  * Creates an instance of an Interpreter. If successful, returns a pointer to an
@@ -61,8 +63,9 @@ struct RVMInterpreter* rvm_interpreter_new(struct RVMCodeHolder* ch);
 
 /**
  * Registers a function to be used for ExtCalls. Must be called before invoking
- * rvm_interpreter_resolve_imports. If successful, returns 0; If this fails, it
- * returns 1.
+ * rvm_interpreter_resolve_imports. Interpreters may run on any thread, so the
+ * callback must be safe to call from other threads. If successful, returns 0;
+ * If this fails, it returns 1.
  */
 uint8_t rvm_interpreter_register_function(
   struct RVMInterpreter* inter,
@@ -70,6 +73,65 @@ uint8_t rvm_interpreter_register_function(
   const char* name_char
 );
 
+/**
+ * Registers a function that belongs to a capability, such as "fs". Code may
+ * only import it once the capability has been granted with
+ * rvm_interpreter_grant_capability. If successful, returns 0; If this fails,
+ * it returns 1.
+ */
+uint8_t rvm_interpreter_register_function_with_capability(
+  struct RVMInterpreter* inter,
+  uint8_t (*callback)(struct RVMState*),
+  const char* name_char,
+  const char* capability_char
+);
+
+/**
+ * Allows the code to import the functions belonging to a capability. If
+ * successful, returns 0; If this fails, it returns 1.
+ */
+uint8_t rvm_interpreter_grant_capability(
+  struct RVMInterpreter* inter,
+  const char* capability_char
+);
+
+/**
+ * Sets the version of the API the host provides. Once set, resolving imports
+ * fails for modules whose metadata requires an incompatible host API. If
+ * successful, returns 0; If this fails, it returns 1.
+ */
+uint8_t rvm_interpreter_set_host_api_version(
+  struct RVMInterpreter* inter,
+  uint64_t major,
+  uint64_t minor,
+  uint64_t patch
+);
+
+/**
+ * Creates a handle that can stop the code running in the Interpreter from
+ * another thread. If successful, returns a pointer to the handle. If this
+ * fails, it returns a null pointer.
+ */
+struct RVMInterruptHandle* rvm_interpreter_interrupt_handle(struct RVMInterpreter* inter);
+
+/**
+ * Asks the Interpreter the handle belongs to to stop. May be called from any
+ * thread. If successful, returns 0; If this fails, it returns 1.
+ */
+uint8_t rvm_interrupt_handle_interrupt(const struct RVMInterruptHandle* handle);
+
+/**
+ * Free and destroy an interrupt handle. The Interpreter is not affected.
+ */
+void rvm_interrupt_handle_destroy(struct RVMInterruptHandle* handle);
+
+/**
+ * Stops execution once the given amount of milliseconds have passed. A timeout
+ * of 0 removes the deadline. If successful, returns 0; If this fails, it
+ * returns 1.
+ */
+uint8_t rvm_interpreter_set_timeout(struct RVMInterpreter* inter, uint64_t milliseconds);
+
 /**
  * Attempts to resolve all imports requested by the CodeHolder. If this
  * succeeds, returns 0; If this fails, it returns 1.
@@ -85,6 +147,13 @@ uint8_t rvm_interpreter_execute_function(
   const char* name_char
 );
 
+/**
+ * Clears the registers, stack frames and stack so the code can run again,
+ * keeping the code and registered functions. If successful, returns 0; If this
+ * fails, it returns 1.
+ */
+uint8_t rvm_interpreter_reset(struct RVMInterpreter* inter);
+
 /**
  * Free and destroy an Interpreter instance. Consumes the Interpreter.
  */
@@ -156,6 +225,95 @@ uint8_t rvm_state_push_string(struct RVMState* state, const char* value);
  */
 uint8_t rvm_state_push_bool(struct RVMState* state, uint8_t value);
 
+/**
+ * Creates an empty Map instance. Maps hold values under either string keys or
+ * integer keys. Functions ending in "_at" use integer keys.
+ */
+struct RVMMap* rvm_map_new();
+
+/**
+ * Free and destroy a Map instance. Consumes the Map.
+ */
+void rvm_map_destroy(struct RVMMap* map);
+
+/**
+ * Retrieve the amount of entries in a Map.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_map_len(struct RVMMap* map, uint64_t* out_value);
+
+/**
+ * List the keys of a Map by their position, from 0 up to its length. Integer
+ * keys come first in ascending order, followed by string keys in ascending
+ * order. Positions change when the Map is modified.
+ * rvm_map_key_is_integer outputs 1 if the key at a position is an integer key
+ * and 0 if it is a string key.
+ * NOTE: Strings output by rvm_map_get_key_string MUST be freed using
+ * rvm_string_free!
+ * Returns 0 if successful. Returns 1 if the position is outside of the Map or
+ * holds a key of a different type.
+ */
+uint8_t rvm_map_key_is_integer(struct RVMMap* map, uint64_t index, uint8_t* out_value);
+uint8_t rvm_map_get_key_integer(struct RVMMap* map, uint64_t index, int64_t* out_key);
+uint8_t rvm_map_get_key_string(struct RVMMap* map, uint64_t index, char** out_key);
+
+/**
+ * Store a value under a key of a Map, replacing the previous value if there
+ * was one. Strings are not consumed and must be manually freed through normal
+ * means.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_map_set_integer(struct RVMMap* map, const char* key, int64_t value);
+uint8_t rvm_map_set_float(struct RVMMap* map, const char* key, double value);
+uint8_t rvm_map_set_string(struct RVMMap* map, const char* key, const char* value);
+uint8_t rvm_map_set_bool(struct RVMMap* map, const char* key, uint8_t value);
+uint8_t rvm_map_set_integer_at(struct RVMMap* map, int64_t key, int64_t value);
+uint8_t rvm_map_set_float_at(struct RVMMap* map, int64_t key, double value);
+uint8_t rvm_map_set_string_at(struct RVMMap* map, int64_t key, const char* value);
+uint8_t rvm_map_set_bool_at(struct RVMMap* map, int64_t key, uint8_t value);
+
+/**
+ * Retrieve the value stored under a key of a Map.
+ * NOTE: Strings output by these functions MUST be freed using rvm_string_free!
+ * Returns 0 if successful. Returns 1 if the key does not exist or holds a
+ * value of a different type.
+ */
+uint8_t rvm_map_get_integer(struct RVMMap* map, const char* key, int64_t* out_value);
+uint8_t rvm_map_get_float(struct RVMMap* map, const char* key, double* out_value);
+uint8_t rvm_map_get_string(struct RVMMap* map, const char* key, char** out_value);
+uint8_t rvm_map_get_bool(struct RVMMap* map, const char* key, uint8_t* out_value);
+uint8_t rvm_map_get_integer_at(struct RVMMap* map, int64_t key, int64_t* out_value);
+uint8_t rvm_map_get_float_at(struct RVMMap* map, int64_t key, double* out_value);
+uint8_t rvm_map_get_string_at(struct RVMMap* map, int64_t key, char** out_value);
+uint8_t rvm_map_get_bool_at(struct RVMMap* map, int64_t key, uint8_t* out_value);
+
+/**
+ * Check if a Map contains a key. Outputs 1 if it does and 0 if it doesn't.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_map_contains(struct RVMMap* map, const char* key, uint8_t* out_value);
+uint8_t rvm_map_contains_at(struct RVMMap* map, int64_t key, uint8_t* out_value);
+
+/**
+ * Remove a key and its value from a Map if it exists.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_map_remove(struct RVMMap* map, const char* key);
+uint8_t rvm_map_remove_at(struct RVMMap* map, int64_t key);
+
+/**
+ * Retrieve a Map from an RVMState. The Map output by this function MUST be
+ * freed using rvm_map_destroy.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_state_get_map(struct RVMState* state, struct RVMMap** out_value);
+
+/**
+ * Push a Map onto the stack of an RVMState instance. Consumes the Map.
+ * Returns 0 if successful. Returns 1 if it fails.
+ */
+uint8_t rvm_state_push_map(struct RVMState* state, struct RVMMap* map);
+
 /**
  * Frees a string (char*) that was allocated by another Resurgence function.
  */
@@ -0,0 +1,100 @@
+/*!
+# Resurgence command line tool
+Provides utilities for working with Resurgence bytecode files.
+
+```text
+resurgence convert <input.rvm> <output.rvm> [--target MAJOR.MINOR]
+resurgence to-json <input.rvm> <output.json>      (requires the `serde` feature)
+resurgence from-json <input.json> <output.rvm>    (requires the `serde` feature)
+```
+*/
+
+use std::env;
+#[cfg(feature = "serde")]
+use std::fs;
+use std::process::ExitCode;
+
+use resurgence::bytecode;
+
+const USAGE: &str = "Usage:
+    resurgence convert <input.rvm> <output.rvm> [--target MAJOR.MINOR]
+        Rewrites a bytecode file as another version of the format (defaults to the latest)
+    resurgence to-json <input.rvm> <output.json>
+        Writes a bytecode file as JSON (requires the serde feature)
+    resurgence from-json <input.json> <output.rvm>
+        Writes JSON created by to-json as a bytecode file (requires the serde feature)";
+
+/// Parses a `MAJOR.MINOR` version string
+fn parse_version(version: &str) -> Option<(u16, u16)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Handles the `convert` subcommand
+fn convert(args: &[String]) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut target = bytecode::LATEST_VERSION;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--target" {
+            let version = iter.next().ok_or("--target requires a version")?;
+            target = parse_version(version).ok_or(format!("Invalid version \"{}\"", version))?;
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    if paths.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+
+    bytecode::convert_bytecode_file(paths[0], paths[1], target.0, target.1)
+        .map_err(|err| format!("Can not convert {}: {}", paths[0], err))
+}
+
+/// Handles the `to-json` subcommand
+#[cfg(feature = "serde")]
+fn to_json(args: &[String]) -> Result<(), String> {
+    if args.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    let data = fs::read(&args[0]).map_err(|err| format!("Can not read {}: {}", args[0], err))?;
+    let json = bytecode::bytecode_to_json(&data)
+        .map_err(|err| format!("Can not convert {}: {}", args[0], err))?;
+    fs::write(&args[1], json).map_err(|err| format!("Can not write {}: {}", args[1], err))
+}
+
+/// Handles the `from-json` subcommand
+#[cfg(feature = "serde")]
+fn from_json(args: &[String]) -> Result<(), String> {
+    if args.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    let json =
+        fs::read_to_string(&args[0]).map_err(|err| format!("Can not read {}: {}", args[0], err))?;
+    let data = bytecode::json_to_bytecode(&json)
+        .map_err(|err| format!("Can not convert {}: {}", args[0], err))?;
+    fs::write(&args[1], data).map_err(|err| format!("Can not write {}: {}", args[1], err))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let res = match args.first().map(String::as_str) {
+        Some("convert") => convert(&args[1..]),
+        #[cfg(feature = "serde")]
+        Some("to-json") => to_json(&args[1..]),
+        #[cfg(feature = "serde")]
+        Some("from-json") => from_json(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -5,22 +5,161 @@ instance.
 */
 
 use byteorder::{BigEndian, ReadBytesExt};
+use miniz_oxide::inflate::TINFLStatus;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{Cursor, Read};
+use std::io::{self, BufReader, Read};
 use std::io::{Error, ErrorKind};
+use std::path::Path;
 use std::result::Result;
 
+use super::integrity::{Crc32, ReadOptions};
+use super::leb128;
 use super::parser_constants as pc;
 use crate::objects::codeholder::CodeHolder;
-use crate::objects::constant::Constant;
+use crate::objects::constant::{Constant, MapKey};
 use crate::objects::instruction::Instruction;
+use crate::objects::metadata::{ModuleMetadata, SemanticVersion};
 use crate::objects::register::{Register, RegisterLocation, RegisterReference};
 
-/// Reads a string from a cursor
-fn read_string(cur: &mut Cursor<&Vec<u8>>) -> Result<String, Error> {
-    let length = cur.read_u64::<BigEndian>()? as usize;
-    let mut data = vec![0u8; length];
-    cur.read_exact(&mut data)?;
+/// Wraps a reader and keeps track of how many bytes were read, so errors can report positions.
+/// It can also checksum and record everything read, so integrity checks can be done while
+/// streaming.
+pub(super) struct PositionReader<R: Read> {
+    inner: R,
+    position: u64,
+    /// Position where the input ends, if it is known
+    end: Option<u64>,
+    checksum: Option<Crc32>,
+    recorded: Option<Vec<u8>>,
+}
+
+impl<R: Read> PositionReader<R> {
+    /// Creates a reader whose position starts at `position` instead of 0
+    pub(super) fn with_position(inner: R, position: u64) -> PositionReader<R> {
+        PositionReader {
+            inner,
+            position,
+            end: None,
+            checksum: None,
+            recorded: None,
+        }
+    }
+
+    /// Creates a reader whose position starts at `position`, for input that is `length` bytes long
+    pub(super) fn with_length(inner: R, position: u64, length: u64) -> PositionReader<R> {
+        PositionReader {
+            end: position.checked_add(length),
+            ..PositionReader::with_position(inner, position)
+        }
+    }
+
+    /// Creates a reader that checksums everything it reads, and records it if `record` is set
+    fn with_integrity(inner: R, record: bool) -> PositionReader<R> {
+        PositionReader {
+            inner,
+            position: 0,
+            end: None,
+            checksum: Some(Crc32::new()),
+            recorded: match record {
+                true => Some(Vec::new()),
+                false => None,
+            },
+        }
+    }
+
+    /// Returns the amount of bytes read so far
+    pub(super) fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Fails if the rest of the input is known to be too short for `count` elements, each of
+    /// which takes at least one byte
+    fn check_count(&self, count: u64, kind: &str, position: u64) -> Result<(), Error> {
+        match self.end {
+            Some(end) if count > end.saturating_sub(self.position) => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} constant at position {} has {} elements, more than the bytecode can hold",
+                    kind, position, count
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Continues reading from `inner` instead, keeping the position and integrity state
+    fn switch_to<S: Read>(self, inner: S) -> PositionReader<S> {
+        PositionReader {
+            inner,
+            position: self.position,
+            end: None,
+            checksum: self.checksum,
+            recorded: self.recorded,
+        }
+    }
+}
+
+impl<R: Read> Read for PositionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let amount = self.inner.read(buf)?;
+        self.position += amount as u64;
+        if let Some(checksum) = &mut self.checksum {
+            checksum.update(&buf[..amount]);
+        }
+        if let Some(recorded) = &mut self.recorded {
+            recorded.extend_from_slice(&buf[..amount]);
+        }
+        Ok(amount)
+    }
+}
+
+/// Tracks the integrity sections found while reading sections
+#[derive(Default)]
+pub(super) struct IntegrityState {
+    pub(super) has_checksum: bool,
+    pub(super) has_signature: bool,
+    /// Set once a signature was checked by the verifier from the read options
+    pub(super) verified: bool,
+}
+
+impl IntegrityState {
+    /// Fails if the integrity information required by `options` was not found
+    pub(super) fn check_required(&self, options: &ReadOptions) -> Result<(), Error> {
+        if options.require_checksum && !self.has_checksum {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Bytecode does not contain a checksum",
+            ));
+        }
+        if options.verifier.is_some() && !self.verified {
+            return Err(Error::new(ErrorKind::InvalidData, "Bytecode is not signed"));
+        }
+        Ok(())
+    }
+}
+
+/// Reads a single byte, returning `None` if the reader has reached its end
+pub(super) fn read_u8_or_end<R: Read>(cur: &mut PositionReader<R>) -> Result<Option<u8>, Error> {
+    let mut op = [0u8; 1];
+    loop {
+        match cur.read(&mut op) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(op[0])),
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Reads a string from a reader
+fn read_string<R: Read>(cur: &mut PositionReader<R>) -> Result<String, Error> {
+    let length = cur.read_u64::<BigEndian>()?;
+    // grow the buffer as data arrives, since a corrupted length could be huge
+    let mut data = Vec::new();
+    if Read::by_ref(cur).take(length).read_to_end(&mut data)? as u64 != length {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+    }
     match String::from_utf8(data) {
         Ok(d) => Ok(d),
         Err(error) => Err(Error::new(
@@ -35,7 +174,7 @@ fn read_string(cur: &mut Cursor<&Vec<u8>>) -> Result<String, Error> {
 }
 
 /// Creates a register instance from 5 bytes
-fn read_register(cur: &mut Cursor<&Vec<u8>>) -> Result<Register, Error> {
+fn read_register<R: Read>(cur: &mut PositionReader<R>) -> Result<Register, Error> {
     let reg = cur.read_u32::<BigEndian>()?;
 
     let regloc = read_reg_loc(cur)?;
@@ -44,7 +183,7 @@ fn read_register(cur: &mut Cursor<&Vec<u8>>) -> Result<Register, Error> {
 }
 
 // Creates a register location value from a single byte
-fn read_reg_loc(cur: &mut Cursor<&Vec<u8>>) -> Result<RegisterLocation, Error> {
+fn read_reg_loc<R: Read>(cur: &mut PositionReader<R>) -> Result<RegisterLocation, Error> {
     let locval = cur.read_u8()?;
 
     let regloc = match locval {
@@ -66,8 +205,80 @@ fn read_reg_loc(cur: &mut Cursor<&Vec<u8>>) -> Result<RegisterLocation, Error> {
     Ok(regloc)
 }
 
+/// Reads a `u32` from the compact encoding, failing if it does not fit
+fn read_compact_u32<R: Read>(cur: &mut PositionReader<R>, val: u64) -> Result<u32, Error> {
+    u32::try_from(val).map_err(|_| {
+        Error::other(format!("Operand {} before position {} does not fit in 32 bits", val, cur.position()))
+    })
+}
+
+/// Unpacks the register packed in `val` by the compact encoding, starting at bit `shift`
+fn unpack_register<R: Read>(cur: &mut PositionReader<R>, val: u64, shift: u32) -> Result<Register, Error> {
+    let regloc = match val & 0b11 {
+        pc::PACKED_LOC_CONSTANT => RegisterLocation::ConstantPool,
+        pc::PACKED_LOC_ACCUMULATOR => RegisterLocation::Accumulator,
+        pc::PACKED_LOC_GLOBAL => RegisterLocation::Global,
+        _ => RegisterLocation::Local,
+    };
+    Ok(Register(read_compact_u32(cur, val >> shift)?, regloc))
+}
+
+/// Reads a register operand of an instruction
+fn read_register_operand<R: Read>(cur: &mut PositionReader<R>, compact: bool) -> Result<Register, Error> {
+    match compact {
+        true => {
+            let val = leb128::read_unsigned(cur)?;
+            unpack_register(cur, val, 2)
+        }
+        false => read_register(cur),
+    }
+}
+
+/// Reads a register operand of an instruction together with its reference type
+fn read_register_ref_operand<R: Read>(
+    cur: &mut PositionReader<R>,
+    compact: bool,
+) -> Result<(Register, RegisterReference), Error> {
+    if !compact {
+        return Ok((read_register(cur)?, read_reg_ref(cur)?));
+    }
+    let val = leb128::read_unsigned(cur)?;
+    let rref = match val & pc::PACKED_REF_DEREFERENCE {
+        0 => RegisterReference::AsIs,
+        _ => RegisterReference::Dereference,
+    };
+    Ok((unpack_register(cur, val, 3)?, rref))
+}
+
+/// Reads a size operand of an instruction
+fn read_size_operand<R: Read>(cur: &mut PositionReader<R>, compact: bool) -> Result<u32, Error> {
+    match compact {
+        true => {
+            let val = leb128::read_unsigned(cur)?;
+            read_compact_u32(cur, val)
+        }
+        false => cur.read_u32::<BigEndian>(),
+    }
+}
+
+/// Reads an index operand of an instruction
+fn read_index_operand<R: Read>(cur: &mut PositionReader<R>, compact: bool) -> Result<u64, Error> {
+    match compact {
+        true => leb128::read_unsigned(cur),
+        false => cur.read_u64::<BigEndian>(),
+    }
+}
+
+/// Reads a relative offset operand of an instruction
+fn read_offset_operand<R: Read>(cur: &mut PositionReader<R>, compact: bool) -> Result<i64, Error> {
+    match compact {
+        true => leb128::read_signed(cur),
+        false => cur.read_i64::<BigEndian>(),
+    }
+}
+
 /// Creates a register reference
-fn read_reg_ref(cur: &mut Cursor<&Vec<u8>>) -> Result<RegisterReference, Error> {
+fn read_reg_ref<R: Read>(cur: &mut PositionReader<R>) -> Result<RegisterReference, Error> {
     let v = cur.read_u8()?;
 
     let rref = match v {
@@ -88,8 +299,32 @@ fn read_reg_ref(cur: &mut Cursor<&Vec<u8>>) -> Result<RegisterReference, Error>
     Ok(rref)
 }
 
-fn read_constant(cur: &mut Cursor<&Vec<u8>>) -> Result<Constant, Error> {
+/// Reads a constant from a cursor
+///
+/// `vminor` is the minor version of the bytecode, which decides the layout of vector constants
+pub(super) fn read_constant<R: Read>(cur: &mut PositionReader<R>, vminor: u16) -> Result<Constant, Error> {
+    read_nested_constant(cur, vminor, 0)
+}
+
+/// Creates the error returned for vector and map constants nested deeper than
+/// [`pc::MAX_CONSTANT_DEPTH`]
+pub(super) fn too_deep(position: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Constant at position {} is nested deeper than {} levels",
+            position,
+            pc::MAX_CONSTANT_DEPTH
+        ),
+    )
+}
+
+/// Reads a constant that is nested in `depth` vector and map constants
+fn read_nested_constant<R: Read>(cur: &mut PositionReader<R>, vminor: u16, depth: u32) -> Result<Constant, Error> {
     let ctype = cur.read_u8()?;
+    if matches!(ctype, pc::CONST_VEC | pc::CONST_MAP) && depth >= pc::MAX_CONSTANT_DEPTH {
+        return Err(too_deep(cur.position() - 1));
+    }
     match ctype {
         pc::CONST_INT => {
             // integer
@@ -117,13 +352,49 @@ fn read_constant(cur: &mut Cursor<&Vec<u8>>) -> Result<Constant, Error> {
             return Ok(Constant::Address(val));
         }
         pc::CONST_VEC => {
-            let size = cur.read_u8()?;
+            // older minor versions only used a single byte for the element count
+            let position = cur.position() - 1;
+            let size = match vminor < pc::VER_MINOR_VEC_U64_LEN {
+                true => cur.read_u8()? as u64,
+                false => cur.read_u64::<BigEndian>()?,
+            };
+            cur.check_count(size, "Vector", position)?;
             let mut vi = Vec::new();
             for _ in 0..size {
-                vi.push(read_constant(cur)?);
+                vi.push(read_nested_constant(cur, vminor, depth + 1)?);
             }
             return Ok(Constant::Vec(vi));
         }
+        pc::CONST_MAP => {
+            if vminor < pc::VER_MINOR_MAPS {
+                return Err(Error::other(format!(
+                    "Map constant at position {} requires bytecode version {}.{}, found {}.{}",
+                    cur.position() - 1,
+                    pc::VER_MAJOR,
+                    pc::VER_MINOR_MAPS,
+                    pc::VER_MAJOR,
+                    vminor
+                )));
+            }
+            let position = cur.position() - 1;
+            let size = cur.read_u64::<BigEndian>()?;
+            cur.check_count(size, "Map", position)?;
+            let mut map = BTreeMap::new();
+            for _ in 0..size {
+                let key = match read_nested_constant(cur, vminor, depth + 1)? {
+                    Constant::Int(val) => MapKey::Int(val),
+                    Constant::String(val) => MapKey::String(val),
+                    _ => {
+                        return Err(Error::other(format!(
+                            "Invalid map key type at position {}",
+                            cur.position() - 1
+                        )));
+                    }
+                };
+                map.insert(key, read_nested_constant(cur, vminor, depth + 1)?);
+            }
+            Ok(Constant::Map(map))
+        }
         _ => {
             return Err(Error::new(
                 ErrorKind::Other,
@@ -139,23 +410,36 @@ fn read_constant(cur: &mut Cursor<&Vec<u8>>) -> Result<Constant, Error> {
 
 /// Opens and reads bytecode from a file and parses it into a usable
 /// CodeHolder.
-pub fn read_bytecode_file(path: &str) -> Result<CodeHolder, Error> {
-    let mut file = File::open(path)?;
-    let mut buf = Vec::new(); // has subtype u8
-    file.read_to_end(&mut buf)?;
+pub fn read_bytecode_file<P: AsRef<Path>>(path: P) -> Result<CodeHolder, Error> {
+    let file = File::open(path)?;
 
-    let holder = read_bytecode(&buf)?;
+    let holder = read_bytecode_from(BufReader::new(file))?;
 
     Ok(holder)
 }
 
-/// Parses bytecode contained in a Vec<u8> and returns a usable CodeHolder.
-pub fn read_bytecode(buf: &Vec<u8>) -> Result<CodeHolder, Error> {
-    let len = buf.len();
+/// Parses bytecode contained in a byte slice and returns a usable CodeHolder.
+pub fn read_bytecode(buf: &[u8]) -> Result<CodeHolder, Error> {
+    read_bytecode_from(buf)
+}
 
-    let mut cur = Cursor::new(buf);
-    let mut holder = CodeHolder::new();
+/// Parses bytecode from any [`Read`] implementation and returns a usable CodeHolder. The reader
+/// is consumed until it reaches the end of its data.
+///
+/// Reads are not buffered, so wrapping unbuffered readers (such as a [`File`]) in a
+/// [`BufReader`] is recommended.
+pub fn read_bytecode_from<R: Read>(reader: R) -> Result<CodeHolder, Error> {
+    read_bytecode_from_with_options(reader, &ReadOptions::default())
+}
+
+/// Parses bytecode contained in a byte slice like [`read_bytecode`], failing with an
+/// [`ErrorKind::InvalidData`] error if the integrity checks required by `options` fail.
+pub fn read_bytecode_with_options(buf: &[u8], options: &ReadOptions) -> Result<CodeHolder, Error> {
+    read_bytecode_from_with_options(buf, options)
+}
 
+/// Checks the magic number and version, and returns the minor version and header flags
+pub(super) fn read_header<R: Read>(cur: &mut PositionReader<R>) -> Result<(u16, u8), Error> {
     // check if this is a rvm bytecode file
     // 52564D88
     if cur.read_u32::<BigEndian>()? != pc::MAGIC_NUMBER {
@@ -185,223 +469,627 @@ pub fn read_bytecode(buf: &Vec<u8>) -> Result<CodeHolder, Error> {
         ));
     }
 
-    // constants table
+    let flags = match vminor >= pc::VER_MINOR_HEADER_FLAGS {
+        true => cur.read_u8()?,
+        false => 0x00,
+    };
+    if flags & !pc::HEADER_FLAG_COMPRESSED != 0 {
+        return Err(Error::other(format!("Unrecognized header flags {:#04x}", flags)));
+    }
+
+    Ok((vminor, flags))
+}
+
+/// Parses bytecode from any [`Read`] implementation like [`read_bytecode_from`], failing with an
+/// [`ErrorKind::InvalidData`] error if the integrity checks required by `options` fail.
+pub fn read_bytecode_from_with_options<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<CodeHolder, Error> {
+    let mut cur = PositionReader::with_integrity(reader, options.verifier.is_some());
+    let mut holder = CodeHolder::new();
+
+    let (vminor, flags) = read_header(&mut cur)?;
+
+    let mut integrity = IntegrityState::default();
+    if flags & pc::HEADER_FLAG_COMPRESSED != 0 {
+        // the body is decompressed as a whole, positions in errors refer to the decompressed body
+        let mut compressed = Vec::new();
+        cur.inner.read_to_end(&mut compressed)?;
+        let body = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, options.max_decompressed_size)
+            .map_err(|err| match err.status {
+                TINFLStatus::HasMoreOutput => Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Compressed bytecode exceeds the limit of {} bytes",
+                        options.max_decompressed_size
+                    ),
+                ),
+                _ => Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Can not decompress bytecode: {}", err),
+                ),
+            })?;
+        let mut cur = cur.switch_to(&body[..]);
+        read_body(&mut cur, &mut holder, vminor, options, &mut integrity)?;
+    } else {
+        read_body(&mut cur, &mut holder, vminor, options, &mut integrity)?;
+    }
+
+    integrity.check_required(options)?;
+
+    Ok(holder)
+}
+
+/// Reads everything following the header
+fn read_body<R: Read>(
+    cur: &mut PositionReader<R>,
+    holder: &mut CodeHolder,
+    vminor: u16,
+    options: &ReadOptions,
+    integrity: &mut IntegrityState,
+) -> Result<(), Error> {
+    if vminor < pc::VER_MINOR_SECTIONS {
+        // older minor versions store the tables and instructions in a fixed order
+        read_constants_table(cur, holder, vminor)?;
+        read_imports_table(cur, holder)?;
+        read_exports_table(cur, holder)?;
+        read_instructions(cur, holder, vminor)
+    } else {
+        read_sections(cur, holder, vminor, options, integrity)
+    }
+}
+
+/// Reads sections until the end of the bytecode, skipping unknown optional sections
+fn read_sections<R: Read>(
+    cur: &mut PositionReader<R>,
+    holder: &mut CodeHolder,
+    vminor: u16,
+    options: &ReadOptions,
+    integrity: &mut IntegrityState,
+) -> Result<(), Error> {
+    loop {
+        let section_pos = cur.position();
+        // integrity sections cover everything before them, so remember what was read until here
+        let checksum_before = cur.checksum.map(|checksum| checksum.value());
+        let recorded_before = cur.recorded.as_ref().map_or(0, Vec::len);
+
+        let tag = match read_u8_or_end(cur)? {
+            Some(tag) => tag,
+            None => break,
+        };
+        if integrity.has_signature || (integrity.has_checksum && tag != pc::SECTION_SIGNATURE) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Section {} at position {} follows an integrity section",
+                    tag, section_pos
+                ),
+            ));
+        }
+        let flags = cur.read_u8()?;
+        let length = cur.read_u64::<BigEndian>()?;
+        let start = cur.position();
+
+        let mut signature = None;
+
+        // limit the section to its length so it can not read into the next one
+        let mut section = PositionReader::with_length(Read::by_ref(cur).take(length), start, length);
+        match tag {
+            pc::SECTION_CONSTANTS => read_constants_table(&mut section, holder, vminor)?,
+            pc::SECTION_IMPORTS => read_imports_table(&mut section, holder)?,
+            pc::SECTION_EXPORTS => read_exports_table(&mut section, holder)?,
+            pc::SECTION_CODE => read_instructions(&mut section, holder, vminor)?,
+            pc::SECTION_CUSTOM => {
+                let name = read_string(&mut section)?;
+                let mut data = Vec::new();
+                section.read_to_end(&mut data)?;
+                holder.custom_sections.insert(name, data);
+            }
+            pc::SECTION_METADATA => holder.metadata = Some(read_metadata(&mut section)?),
+            pc::SECTION_CHECKSUM => {
+                let algorithm = section.read_u8()?;
+                if algorithm != pc::CHECKSUM_CRC32 {
+                    return Err(Error::other(format!(
+                        "Unrecognized checksum algorithm {} at position {}",
+                        algorithm, section_pos
+                    )));
+                }
+                let expected = section.read_u32::<BigEndian>()?;
+                if checksum_before != Some(expected) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Bytecode checksum does not match, the file is corrupted",
+                    ));
+                }
+                integrity.has_checksum = true;
+            }
+            pc::SECTION_SIGNATURE => {
+                let key_id = read_string(&mut section)?;
+                let mut data = Vec::new();
+                section.read_to_end(&mut data)?;
+                signature = Some((key_id, data));
+                integrity.has_signature = true;
+            }
+            _ => {
+                if flags & pc::SECTION_FLAG_REQUIRED != 0 {
+                    return Err(Error::other(format!(
+                        "Unrecognized required section {} at position {}",
+                        tag, section_pos
+                    )));
+                }
+                io::copy(&mut section, &mut io::sink())?;
+            }
+        }
+
+        if section.position() - start != length {
+            return Err(Error::other(format!(
+                "Section {} at position {} does not match its length of {} bytes",
+                tag, section_pos, length
+            )));
+        }
+
+        // the signature can only be checked once the section no longer borrows the reader
+        if let (Some((key_id, data)), Some(verifier), Some(recorded)) =
+            (signature, options.verifier, &cur.recorded)
+        {
+            if !verifier.verify(&key_id, &recorded[..recorded_before], &data) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Bytecode signature by key \"{}\" is not valid", key_id),
+                ));
+            }
+            integrity.verified = true;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a semantic version
+fn read_version<R: Read>(cur: &mut PositionReader<R>) -> Result<SemanticVersion, Error> {
+    let major = cur.read_u64::<BigEndian>()?;
+    let minor = cur.read_u64::<BigEndian>()?;
+    let patch = cur.read_u64::<BigEndian>()?;
+    Ok(SemanticVersion::new(major, minor, patch))
+}
+
+/// Reads the contents of a metadata section
+pub(super) fn read_metadata<R: Read>(cur: &mut PositionReader<R>) -> Result<ModuleMetadata, Error> {
+    let mut metadata = ModuleMetadata {
+        name: read_string(cur)?,
+        version: read_version(cur)?,
+        author: read_string(cur)?,
+        description: read_string(cur)?,
+        min_host_api: read_version(cur)?,
+        ..Default::default()
+    };
+
+    let plen = cur.read_u64::<BigEndian>()?;
+    for _ in 0..plen {
+        let key = read_string(cur)?;
+        let value = read_string(cur)?;
+        metadata.properties.insert(key, value);
+    }
+
+    Ok(metadata)
+}
+
+/// Reads the constants table into the constant pool
+fn read_constants_table<R: Read>(cur: &mut PositionReader<R>, holder: &mut CodeHolder, vminor: u16) -> Result<(), Error> {
     let clen = cur.read_u32::<BigEndian>()?;
     for _ in 0..clen {
-        holder.constant_pool.push(read_constant(&mut cur)?);
+        holder.constant_pool.push(read_constant(cur, vminor)?);
     }
 
-    // read imports table
+    Ok(())
+}
+
+/// Reads the imports table
+fn read_imports_table<R: Read>(cur: &mut PositionReader<R>, holder: &mut CodeHolder) -> Result<(), Error> {
     let ilen = cur.read_u64::<BigEndian>()?;
     for _ in 0..ilen {
-        let import_func = read_string(&mut cur)?;
+        let import_func = read_string(cur)?;
         holder.imports.push(import_func);
     }
 
-    // read exports table
+    Ok(())
+}
+
+/// Reads the exports table
+fn read_exports_table<R: Read>(cur: &mut PositionReader<R>, holder: &mut CodeHolder) -> Result<(), Error> {
     let elen = cur.read_u64::<BigEndian>()?;
     for _ in 0..elen {
-        let export_name = read_string(&mut cur)?;
+        let export_name = read_string(cur)?;
         let export_pos = cur.read_u64::<BigEndian>()?;
         holder.exports.insert(export_name, export_pos);
     }
 
-    // read bytecode into vector
-    loop {
-        if cur.position() == (len as u64) {
-            break;
+    Ok(())
+}
+
+/// Reads instructions until the end of the reader
+fn read_instructions<R: Read>(cur: &mut PositionReader<R>, holder: &mut CodeHolder, vminor: u16) -> Result<(), Error> {
+    let compact = vminor >= pc::VER_MINOR_COMPACT;
+    while let Some(op) = read_u8_or_end(cur)? {
+        let position = cur.position() - 1;
+        if let Some(ins) = read_instruction(cur, op, compact)? {
+            check_instruction_minor(&ins, vminor, position)?;
+            holder.instructions.push(Some(ins));
         }
+    }
 
-        let op = cur.read_u8()?; // opcode
+    Ok(())
+}
 
-        match op {
-            pc::INST_NOOP => {
-                // NOOP
-                continue;
-            }
-            pc::INST_ALLOC => {
-                // Alloc
-                let size = cur.read_u32::<BigEndian>()?;
-                holder.instructions.push(Some(Instruction::Alloc(size)));
-            }
-            pc::INST_FRAME_ALLOC => {
-                // FrameAlloc
-                let size = cur.read_u32::<BigEndian>()?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::FrameAlloc(size, read_reg_loc(&mut cur)?)));
-            }
-            pc::INST_FREE => {
-                // Free
-                let size = cur.read_u32::<BigEndian>()?;
-                holder.instructions.push(Some(Instruction::Free(size)));
-            }
-            pc::INST_FRAME_FREE => {
-                // FrameFree
-                let size = cur.read_u32::<BigEndian>()?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::FrameFree(size, read_reg_loc(&mut cur)?)));
-            }
-            pc::INST_JUMP => {
-                // Jump
-                let addr = cur.read_i64::<BigEndian>()?;
-                holder.instructions.push(Some(Instruction::Jump(addr)));
-            }
-            pc::INST_CALL => {
-                // Call
-                let addr = cur.read_u64::<BigEndian>()?;
-                holder.instructions.push(Some(Instruction::Call(addr)));
-            }
-            pc::INST_EXTCALL => {
-                // ExtCall
-                let id = cur.read_u64::<BigEndian>()?;
-                holder.instructions.push(Some(Instruction::ExtCall(id)));
-            }
-            pc::INST_RET => {
-                // Ret
-                holder.instructions.push(Some(Instruction::Ret));
-            }
-            pc::INST_MOV => {
-                // Mov
-                let ra = read_register(&mut cur)?;
-                let aref = read_reg_ref(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let bref = read_reg_ref(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::Mov(ra, aref, rb, bref)));
-            }
-            pc::INST_CPY => {
-                // Cpy
-                let ra = read_register(&mut cur)?;
-                let aref = read_reg_ref(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let bref = read_reg_ref(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::Cpy(ra, aref, rb, bref)));
-            }
-            pc::INST_REF => {
-                // Ref
-                let ra = read_register(&mut cur)?;
-                let aref = read_reg_ref(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let bref = read_reg_ref(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::Ref(ra, aref, rb, bref)));
-            }
-            pc::INST_STACK_PUSH => {
-                // StackPush
-                let reg = read_register(&mut cur)?;
-                let rref = read_reg_ref(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::StackPush(reg, rref)));
-            }
-            pc::INST_STACK_MOV => {
-                // StackMov
-                let reg = read_register(&mut cur)?;
-                let rref = read_reg_ref(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::StackMov(reg, rref)));
-            }
-            pc::INST_STACK_POP => {
-                // StackPop
-                holder.instructions.push(Some(Instruction::StackPop));
-            }
-            pc::INST_ADD => {
-                // Add
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let rc = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Add(ra, rb, rc)));
-            }
-            pc::INST_SUB => {
-                // Sub
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let rc = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Sub(ra, rb, rc)));
-            }
-            pc::INST_MUL => {
-                // Mul
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let rc = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Mul(ra, rb, rc)));
-            }
-            pc::INST_DIV => {
-                // Div
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let rc = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Div(ra, rb, rc)));
-            }
-            pc::INST_MOD => {
-                // Mod
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                let rc = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Mod(ra, rb, rc)));
-            }
-            pc::INST_EQUAL => {
-                // Equal
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Equal(ra, rb)));
-            }
-            pc::INST_NOT_EQUAL => {
-                // NotEqual
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::NotEqual(ra, rb)));
-            }
-            pc::INST_GREATER => {
-                // Greater
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Greater(ra, rb)));
-            }
-            pc::INST_LESS => {
-                // Less
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder.instructions.push(Some(Instruction::Less(ra, rb)));
-            }
-            pc::INST_GREATER_EQUAL => {
-                // GreaterEqual
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::GreaterEqual(ra, rb)));
-            }
-            pc::INST_LESS_EQUAL => {
-                // LessEqual
-                let ra = read_register(&mut cur)?;
-                let rb = read_register(&mut cur)?;
-                holder
-                    .instructions
-                    .push(Some(Instruction::LessEqual(ra, rb)));
-            }
-            _ => {
-                // catch-all for invalid instructions
-                return Err(Error::new(
-                    ErrorKind::Other,
-                    format!(
-                        "Unrecognized instruction {} at position {}",
-                        op,
-                        cur.position() - 1
-                    ),
-                ));
+/// Returns the minor version of `VER_MAJOR` that introduced an instruction
+pub(super) fn instruction_minor(ins: &Instruction) -> u16 {
+    match ins {
+        Instruction::JumpTo(..)
+        | Instruction::JumpIfTrue(..)
+        | Instruction::JumpIfFalse(..)
+        | Instruction::JumpTable(..) => pc::VER_MINOR_JUMPS,
+        Instruction::ToInt(..)
+        | Instruction::ToDouble(..)
+        | Instruction::ToString(..)
+        | Instruction::ToBool(..)
+        | Instruction::TypeOf(..) => pc::VER_MINOR_CASTS,
+        Instruction::StrLen(..) | Instruction::Substr(..) | Instruction::CharAt(..) => pc::VER_MINOR_STRINGS,
+        Instruction::VecNew(..)
+        | Instruction::VecGet(..)
+        | Instruction::VecSet(..)
+        | Instruction::VecPush(..)
+        | Instruction::VecPop(..)
+        | Instruction::VecLen(..) => pc::VER_MINOR_VECTORS,
+        Instruction::MapNew(..)
+        | Instruction::MapGet(..)
+        | Instruction::MapSet(..)
+        | Instruction::MapRemove(..)
+        | Instruction::MapContains(..)
+        | Instruction::MapLen(..)
+        | Instruction::MapKeys(..) => pc::VER_MINOR_MAPS,
+        _ => 0,
+    }
+}
+
+/// Fails if the instruction `ins` is newer than minor version `vminor`. `position` is the
+/// position of the instruction, used in the error message.
+pub(super) fn check_instruction_minor(ins: &Instruction, vminor: u16, position: u64) -> Result<(), Error> {
+    let required = instruction_minor(ins);
+    if required > vminor {
+        return Err(Error::other(format!(
+            "Instruction {:?} at position {} requires bytecode version {}.{}, found {}.{}",
+            ins,
+            position,
+            pc::VER_MAJOR,
+            required,
+            pc::VER_MAJOR,
+            vminor
+        )));
+    }
+    Ok(())
+}
+
+/// Reads the operands of the instruction with opcode `op`. Returns `None` for no-ops.
+pub(super) fn read_instruction<R: Read>(
+    cur: &mut PositionReader<R>,
+    op: u8,
+    compact: bool,
+) -> Result<Option<Instruction>, Error> {
+    let ins = match op {
+        pc::INST_NOOP => return Ok(None),
+        pc::INST_ALLOC => {
+            // Alloc
+            let size = read_size_operand(cur, compact)?;
+            Instruction::Alloc(size)
+        }
+        pc::INST_FRAME_ALLOC => {
+            // FrameAlloc
+            let size = read_size_operand(cur, compact)?;
+            Instruction::FrameAlloc(size, read_reg_loc(cur)?)
+        }
+        pc::INST_FREE => {
+            // Free
+            let size = read_size_operand(cur, compact)?;
+            Instruction::Free(size)
+        }
+        pc::INST_FRAME_FREE => {
+            // FrameFree
+            let size = read_size_operand(cur, compact)?;
+            Instruction::FrameFree(size, read_reg_loc(cur)?)
+        }
+        pc::INST_JUMP => {
+            // Jump
+            let addr = read_offset_operand(cur, compact)?;
+            Instruction::Jump(addr)
+        }
+        pc::INST_JUMP_TO => {
+            // JumpTo
+            let addr = read_index_operand(cur, compact)?;
+            Instruction::JumpTo(addr)
+        }
+        pc::INST_JUMP_IF_TRUE => {
+            // JumpIfTrue
+            let reg = read_register_operand(cur, compact)?;
+            let addr = read_index_operand(cur, compact)?;
+            Instruction::JumpIfTrue(reg, addr)
+        }
+        pc::INST_JUMP_IF_FALSE => {
+            // JumpIfFalse
+            let reg = read_register_operand(cur, compact)?;
+            let addr = read_index_operand(cur, compact)?;
+            Instruction::JumpIfFalse(reg, addr)
+        }
+        pc::INST_JUMP_TABLE => {
+            // JumpTable
+            let reg = read_register_operand(cur, compact)?;
+            let default = read_index_operand(cur, compact)?;
+            let tlen = read_index_operand(cur, compact)?;
+            let mut table = Vec::new();
+            for _ in 0..tlen {
+                table.push(read_index_operand(cur, compact)?);
             }
+            Instruction::JumpTable(reg, default, table)
         }
-    }
+        pc::INST_CALL => {
+            // Call
+            let addr = read_index_operand(cur, compact)?;
+            Instruction::Call(addr)
+        }
+        pc::INST_EXTCALL => {
+            // ExtCall
+            let id = read_index_operand(cur, compact)?;
+            Instruction::ExtCall(id)
+        }
+        pc::INST_RET => {
+            // Ret
+            Instruction::Ret
+        }
+        pc::INST_MOV => {
+            // Mov
+            let (ra, aref) = read_register_ref_operand(cur, compact)?;
+            let (rb, bref) = read_register_ref_operand(cur, compact)?;
+            Instruction::Mov(ra, aref, rb, bref)
+        }
+        pc::INST_CPY => {
+            // Cpy
+            let (ra, aref) = read_register_ref_operand(cur, compact)?;
+            let (rb, bref) = read_register_ref_operand(cur, compact)?;
+            Instruction::Cpy(ra, aref, rb, bref)
+        }
+        pc::INST_REF => {
+            // Ref
+            let (ra, aref) = read_register_ref_operand(cur, compact)?;
+            let (rb, bref) = read_register_ref_operand(cur, compact)?;
+            Instruction::Ref(ra, aref, rb, bref)
+        }
+        pc::INST_STACK_PUSH => {
+            // StackPush
+            let (reg, rref) = read_register_ref_operand(cur, compact)?;
+            Instruction::StackPush(reg, rref)
+        }
+        pc::INST_STACK_MOV => {
+            // StackMov
+            let (reg, rref) = read_register_ref_operand(cur, compact)?;
+            Instruction::StackMov(reg, rref)
+        }
+        pc::INST_STACK_POP => {
+            // StackPop
+            Instruction::StackPop
+        }
+        pc::INST_ADD => {
+            // Add
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::Add(ra, rb, rc)
+        }
+        pc::INST_SUB => {
+            // Sub
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::Sub(ra, rb, rc)
+        }
+        pc::INST_MUL => {
+            // Mul
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::Mul(ra, rb, rc)
+        }
+        pc::INST_DIV => {
+            // Div
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::Div(ra, rb, rc)
+        }
+        pc::INST_MOD => {
+            // Mod
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::Mod(ra, rb, rc)
+        }
+        pc::INST_EQUAL => {
+            // Equal
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::Equal(ra, rb)
+        }
+        pc::INST_NOT_EQUAL => {
+            // NotEqual
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::NotEqual(ra, rb)
+        }
+        pc::INST_GREATER => {
+            // Greater
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::Greater(ra, rb)
+        }
+        pc::INST_LESS => {
+            // Less
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::Less(ra, rb)
+        }
+        pc::INST_GREATER_EQUAL => {
+            // GreaterEqual
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::GreaterEqual(ra, rb)
+        }
+        pc::INST_LESS_EQUAL => {
+            // LessEqual
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::LessEqual(ra, rb)
+        }
+        pc::INST_TO_INT => {
+            // ToInt
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::ToInt(ra, rb)
+        }
+        pc::INST_TO_DOUBLE => {
+            // ToDouble
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::ToDouble(ra, rb)
+        }
+        pc::INST_TO_STRING => {
+            // ToString
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::ToString(ra, rb)
+        }
+        pc::INST_TO_BOOL => {
+            // ToBool
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::ToBool(ra, rb)
+        }
+        pc::INST_TYPE_OF => {
+            // TypeOf
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::TypeOf(ra, rb)
+        }
+        pc::INST_STR_LEN => {
+            // StrLen
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::StrLen(ra, rb)
+        }
+        pc::INST_SUBSTR => {
+            // Substr
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            let rd = read_register_operand(cur, compact)?;
+            Instruction::Substr(ra, rb, rc, rd)
+        }
+        pc::INST_CHAR_AT => {
+            // CharAt
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::CharAt(ra, rb, rc)
+        }
+        pc::INST_VEC_NEW => {
+            // VecNew
+            let ra = read_register_operand(cur, compact)?;
+            Instruction::VecNew(ra)
+        }
+        pc::INST_VEC_GET => {
+            // VecGet
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::VecGet(ra, rb, rc)
+        }
+        pc::INST_VEC_SET => {
+            // VecSet
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::VecSet(ra, rb, rc)
+        }
+        pc::INST_VEC_PUSH => {
+            // VecPush
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::VecPush(ra, rb)
+        }
+        pc::INST_VEC_POP => {
+            // VecPop
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::VecPop(ra, rb)
+        }
+        pc::INST_VEC_LEN => {
+            // VecLen
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::VecLen(ra, rb)
+        }
+        pc::INST_MAP_NEW => {
+            // MapNew
+            let ra = read_register_operand(cur, compact)?;
+            Instruction::MapNew(ra)
+        }
+        pc::INST_MAP_GET => {
+            // MapGet
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::MapGet(ra, rb, rc)
+        }
+        pc::INST_MAP_SET => {
+            // MapSet
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::MapSet(ra, rb, rc)
+        }
+        pc::INST_MAP_REMOVE => {
+            // MapRemove
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::MapRemove(ra, rb)
+        }
+        pc::INST_MAP_CONTAINS => {
+            // MapContains
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            let rc = read_register_operand(cur, compact)?;
+            Instruction::MapContains(ra, rb, rc)
+        }
+        pc::INST_MAP_LEN => {
+            // MapLen
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::MapLen(ra, rb)
+        }
+        pc::INST_MAP_KEYS => {
+            // MapKeys
+            let ra = read_register_operand(cur, compact)?;
+            let rb = read_register_operand(cur, compact)?;
+            Instruction::MapKeys(ra, rb)
+        }
+        _ => {
+            // catch-all for invalid instructions
+            return Err(Error::other(format!(
+                "Unrecognized instruction {} at position {}",
+                op,
+                cur.position() - 1
+            )));
+        }
+    };
 
-    Ok(holder)
+    Ok(Some(ins))
 }
+
@@ -5,48 +5,160 @@ particularly useful for a pure virtual machine, but is useful for compilers and
 */
 
 use byteorder::{BigEndian, WriteBytesExt};
+use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::BufWriter;
 use std::io::prelude::*;
-use std::io::Error;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
 use std::result::Result;
 
+use super::codereader::instruction_minor;
+use super::integrity::{Crc32, WriteOptions};
+use super::leb128;
 use super::parser_constants as pc;
 use crate::objects::codeholder::CodeHolder;
 use crate::objects::constant::Constant;
 use crate::objects::instruction::Instruction;
+use crate::objects::metadata::{ModuleMetadata, SemanticVersion};
 use crate::objects::register::{Register, RegisterLocation, RegisterReference};
 
-fn write_string(buf: &mut Vec<u8>, val: &str) -> Result<(), Error> {
+fn write_string<W: Write>(buf: &mut W, val: &str) -> Result<(), Error> {
     let bytes = val.to_owned().into_bytes();
     buf.write_u64::<BigEndian>(bytes.len() as u64)?;
     buf.write_all(&bytes)?;
     Ok(())
 }
 
-fn write_register(buf: &mut Vec<u8>, r: &Register) -> Result<(), Error> {
+fn write_register<W: Write>(buf: &mut W, r: &Register) -> Result<(), Error> {
     buf.write_u32::<BigEndian>(r.0)?;
     write_reg_loc(buf, &r.1)?;
     Ok(())
 }
 
-fn write_reg_loc(buf: &mut Vec<u8>, rl: &RegisterLocation) -> Result<(), Error> {
-    buf.push(match *rl {
+fn write_reg_loc<W: Write>(buf: &mut W, rl: &RegisterLocation) -> Result<(), Error> {
+    buf.write_u8(match *rl {
         RegisterLocation::ConstantPool => pc::LOC_CONSTANT,
         RegisterLocation::Accumulator => pc::LOC_ACCUMULATOR,
         RegisterLocation::Global => pc::LOC_GLOBAL,
         RegisterLocation::Local => pc::LOC_LOCAL,
-    });
+    })?;
     Ok(())
 }
 
-fn write_reg_ref(buf: &mut Vec<u8>, rref: &RegisterReference) {
-    buf.push(match rref {
+fn write_reg_ref<W: Write>(buf: &mut W, rref: &RegisterReference) -> Result<(), Error> {
+    buf.write_u8(match rref {
         RegisterReference::AsIs => pc::REF_AS_IS,
         RegisterReference::Dereference => pc::REF_DEREFERENCE,
-    });
+    })?;
+    Ok(())
+}
+
+/// Returns the packed location bits of a register location for the compact encoding
+fn packed_loc(rl: &RegisterLocation) -> u64 {
+    match *rl {
+        RegisterLocation::ConstantPool => pc::PACKED_LOC_CONSTANT,
+        RegisterLocation::Accumulator => pc::PACKED_LOC_ACCUMULATOR,
+        RegisterLocation::Global => pc::PACKED_LOC_GLOBAL,
+        RegisterLocation::Local => pc::PACKED_LOC_LOCAL,
+    }
+}
+
+/// Writes a register operand. The compact encoding packs the location into the low 2 bits of a
+/// LEB128 integer holding the index.
+fn write_register_operand<W: Write>(buf: &mut W, r: &Register, compact: bool) -> Result<(), Error> {
+    match compact {
+        true => leb128::write_unsigned(buf, (r.0 as u64) << 2 | packed_loc(&r.1)),
+        false => write_register(buf, r),
+    }
+}
+
+/// Writes a register operand with a reference type. The compact encoding packs the reference
+/// type into bit 2, above the location.
+fn write_register_ref_operand<W: Write>(
+    buf: &mut W,
+    r: &Register,
+    rref: &RegisterReference,
+    compact: bool,
+) -> Result<(), Error> {
+    if !compact {
+        write_register(buf, r)?;
+        return write_reg_ref(buf, rref);
+    }
+    let deref = match rref {
+        RegisterReference::AsIs => 0,
+        RegisterReference::Dereference => pc::PACKED_REF_DEREFERENCE,
+    };
+    leb128::write_unsigned(buf, (r.0 as u64) << 3 | deref | packed_loc(&r.1))
+}
+
+/// Writes a size operand, such as the amount of registers to allocate
+fn write_size_operand<W: Write>(buf: &mut W, size: u32, compact: bool) -> Result<(), Error> {
+    match compact {
+        true => leb128::write_unsigned(buf, size as u64),
+        false => buf.write_u32::<BigEndian>(size),
+    }
+}
+
+/// Writes an index operand, such as an instruction address or an import index
+fn write_index_operand<W: Write>(buf: &mut W, index: u64, compact: bool) -> Result<(), Error> {
+    match compact {
+        true => leb128::write_unsigned(buf, index),
+        false => buf.write_u64::<BigEndian>(index),
+    }
+}
+
+/// Writes a relative offset operand
+fn write_offset_operand<W: Write>(buf: &mut W, offset: i64, compact: bool) -> Result<(), Error> {
+    match compact {
+        true => leb128::write_signed(buf, offset),
+        false => buf.write_i64::<BigEndian>(offset),
+    }
+}
+
+/// Creates the error returned when something can not be represented in the target version
+fn unrepresentable(what: &str, required: u16, minor: u16) -> Error {
+    Error::new(
+        ErrorKind::Unsupported,
+        format!(
+            "{} requires bytecode version {}.{}, can not write version {}.{}",
+            what,
+            pc::VER_MAJOR,
+            required,
+            pc::VER_MAJOR,
+            minor
+        ),
+    )
 }
 
-fn write_constant(buf: &mut Vec<u8>, constant: &Constant) -> Result<(), Error> {
+/// How instruction operands are encoded by [`write_bytecode_with_options`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstructionEncoding {
+    /// Variable-length LEB128 operands, written as the current version of the format
+    #[default]
+    Compact,
+    /// Fixed-size operands, written as version 7.8 of the format so readers that do not know the
+    /// compact encoding can load it
+    Fixed,
+}
+
+/// Writes a constant using the layout of minor version `minor`
+pub(super) fn write_constant<W: Write>(buf: &mut W, constant: &Constant, minor: u16) -> Result<(), Error> {
+    write_nested_constant(buf, constant, minor, 0)
+}
+
+/// Writes a constant that is nested in `depth` vector and map constants, failing if readers
+/// would reject it for being nested too deep
+fn write_nested_constant<W: Write>(buf: &mut W, constant: &Constant, minor: u16, depth: u32) -> Result<(), Error> {
+    if matches!(constant, Constant::Vec(_) | Constant::Map(_)) && depth >= pc::MAX_CONSTANT_DEPTH {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Constants nested deeper than {} levels can not be written",
+                pc::MAX_CONSTANT_DEPTH
+            ),
+        ));
+    }
     match constant {
         Constant::Int(val) => {
             buf.write_u8(pc::CONST_INT)?;
@@ -73,188 +185,531 @@ fn write_constant(buf: &mut Vec<u8>, constant: &Constant) -> Result<(), Error> {
         }
         Constant::Vec(val) => {
             buf.write_u8(pc::CONST_VEC)?;
+            if minor >= pc::VER_MINOR_VEC_U64_LEN {
+                buf.write_u64::<BigEndian>(val.len() as u64)?;
+            } else if val.len() <= u8::MAX as usize {
+                buf.write_u8(val.len() as u8)?;
+            } else {
+                return Err(unrepresentable(
+                    "A vector constant with more than 255 elements",
+                    pc::VER_MINOR_VEC_U64_LEN,
+                    minor,
+                ));
+            }
             for obj in val {
-                write_constant(buf, obj)?;
+                write_nested_constant(buf, obj, minor, depth + 1)?;
+            }
+        }
+        Constant::Map(val) => {
+            if minor < pc::VER_MINOR_MAPS {
+                return Err(unrepresentable("A map constant", pc::VER_MINOR_MAPS, minor));
+            }
+            buf.write_u8(pc::CONST_MAP)?;
+            buf.write_u64::<BigEndian>(val.len() as u64)?;
+            for (key, obj) in val {
+                write_nested_constant(buf, &key.as_constant(), minor, depth + 1)?;
+                write_nested_constant(buf, obj, minor, depth + 1)?;
             }
         }
     }
     Ok(())
 }
 /// Takes a CodeHolder and generates and writes bytecode to a file.
-pub fn write_bytecode_file(code: &CodeHolder, path: &str) -> Result<(), Error> {
-    let mut f = File::create(path)?;
+pub fn write_bytecode_file<P: AsRef<Path>>(code: &CodeHolder, path: P) -> Result<(), Error> {
+    let mut f = BufWriter::new(File::create(path)?);
 
-    let data = write_bytecode(code)?;
-    f.write_all(&data)?;
+    write_bytecode_to(code, &mut f)?;
+    f.flush()?;
 
     Ok(())
 }
 
 /// Takes a CodeHolder and outputs a vec containing bytecode in binary form.
 pub fn write_bytecode(code: &CodeHolder) -> Result<Vec<u8>, Error> {
+    write_bytecode_version(code, pc::VER_MAJOR, pc::VER_MINOR)
+}
+
+/// Takes a CodeHolder and outputs a vec containing bytecode in binary form, followed by the
+/// checksum and signature requested in `options`, and compressed if requested. Instructions use
+/// the encoding requested in `options`.
+pub fn write_bytecode_with_options(code: &CodeHolder, options: &WriteOptions) -> Result<Vec<u8>, Error> {
+    let flags = match options.compress {
+        true => pc::HEADER_FLAG_COMPRESSED,
+        false => 0x00,
+    };
+    let minor = match options.encoding {
+        InstructionEncoding::Compact => pc::VER_MINOR,
+        InstructionEncoding::Fixed => pc::VER_MINOR_COMPACT - 1,
+    };
+    let mut buf = Vec::new();
+    write_version_to(code, pc::VER_MAJOR, minor, flags, &mut buf)?;
+    let mut section = Vec::new();
+
+    // each integrity section covers all bytes written before it
+    if options.checksum {
+        let mut checksum = Crc32::new();
+        checksum.update(&buf);
+        section.write_u8(pc::CHECKSUM_CRC32)?;
+        section.write_u32::<BigEndian>(checksum.value())?;
+        write_section(&mut buf, pc::SECTION_CHECKSUM, false, &mut section)?;
+    }
+    if let Some(signer) = options.signer {
+        let signature = signer.sign(&buf);
+        write_string(&mut section, signer.key_id())?;
+        section.write_all(&signature)?;
+        write_section(&mut buf, pc::SECTION_SIGNATURE, false, &mut section)?;
+    }
+
+    if options.compress {
+        let body = buf.split_off(pc::HEADER_LEN);
+        buf.extend_from_slice(&miniz_oxide::deflate::compress_to_vec(&body, pc::COMPRESSION_LEVEL));
+    }
+
+    Ok(buf)
+}
+
+/// Takes a CodeHolder and writes bytecode in binary form to any [`Write`] implementation.
+///
+/// Writes are not buffered, so wrapping unbuffered writers (such as a [`File`]) in a
+/// [`BufWriter`] is recommended.
+pub fn write_bytecode_to<W: Write>(code: &CodeHolder, mut writer: W) -> Result<(), Error> {
+    write_version_to(code, pc::VER_MAJOR, pc::VER_MINOR, 0x00, &mut writer)
+}
+
+/// Takes a CodeHolder and outputs a vec containing bytecode in the binary form of an older (or
+/// the current) version of the format.
+///
+/// Fails with an [`ErrorKind::Unsupported`] error if the version can not be written, or if the
+/// CodeHolder uses instructions or constants that the version can not represent.
+pub fn write_bytecode_version(code: &CodeHolder, major: u16, minor: u16) -> Result<Vec<u8>, Error> {
     let mut buf: Vec<u8> = Vec::new();
+    write_version_to(code, major, minor, 0x00, &mut buf)?;
+    Ok(buf)
+}
+
+/// Writes bytecode of version `major.minor` to `buf`. `flags` is only written by versions with
+/// header flags, the body is never compressed.
+fn write_version_to<W: Write>(code: &CodeHolder, major: u16, minor: u16, flags: u8, buf: &mut W) -> Result<(), Error> {
+    if major != pc::VER_MAJOR || minor > pc::VER_MINOR {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Can not write bytecode version {}.{} ({}.0 to {}.{} supported)",
+                major,
+                minor,
+                pc::VER_MAJOR,
+                pc::VER_MAJOR,
+                pc::VER_MINOR
+            ),
+        ));
+    }
 
     // write magic number
     buf.write_u32::<BigEndian>(pc::MAGIC_NUMBER)?;
     // write version number
-    buf.write_u16::<BigEndian>(pc::VER_MAJOR)?;
-    buf.write_u16::<BigEndian>(pc::VER_MINOR)?;
+    buf.write_u16::<BigEndian>(major)?;
+    buf.write_u16::<BigEndian>(minor)?;
+    if minor >= pc::VER_MINOR_HEADER_FLAGS {
+        buf.write_u8(flags)?;
+    }
+
+    if minor < pc::VER_MINOR_SECTIONS {
+        if !code.custom_sections.is_empty() {
+            return Err(unrepresentable("Custom sections", pc::VER_MINOR_SECTIONS, minor));
+        }
+        if code.metadata.is_some() {
+            return Err(unrepresentable("Module metadata", pc::VER_MINOR_SECTIONS, minor));
+        }
+
+        // older minor versions store the tables and instructions in a fixed order
+        write_constants_table(buf, code, minor)?;
+        write_imports_table(buf, code)?;
+        write_exports_table(buf, code)?;
+        write_instructions(buf, code, minor)?;
+        return Ok(());
+    }
+
+    let mut section = Vec::new();
+    write_constants_table(&mut section, code, minor)?;
+    write_section(buf, pc::SECTION_CONSTANTS, true, &mut section)?;
+    write_imports_table(&mut section, code)?;
+    write_section(buf, pc::SECTION_IMPORTS, true, &mut section)?;
+    write_exports_table(&mut section, code)?;
+    write_section(buf, pc::SECTION_EXPORTS, true, &mut section)?;
+    write_instructions(&mut section, code, minor)?;
+    write_section(buf, pc::SECTION_CODE, true, &mut section)?;
+
+    if let Some(metadata) = &code.metadata {
+        write_metadata(&mut section, metadata)?;
+        write_section(buf, pc::SECTION_METADATA, false, &mut section)?;
+    }
 
-    // constants pool
+    for (name, data) in &(code.custom_sections) {
+        write_string(&mut section, name)?;
+        section.write_all(data)?;
+        write_section(buf, pc::SECTION_CUSTOM, false, &mut section)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a section header followed by the contents of `section`, leaving `section` empty so it
+/// can be reused
+fn write_section<W: Write>(buf: &mut W, tag: u8, required: bool, section: &mut Vec<u8>) -> Result<(), Error> {
+    buf.write_u8(tag)?;
+    buf.write_u8(match required {
+        true => pc::SECTION_FLAG_REQUIRED,
+        false => 0x00,
+    })?;
+    buf.write_u64::<BigEndian>(section.len() as u64)?;
+    buf.write_all(section)?;
+    section.clear();
+    Ok(())
+}
+
+fn write_version<W: Write>(buf: &mut W, version: &SemanticVersion) -> Result<(), Error> {
+    buf.write_u64::<BigEndian>(version.major)?;
+    buf.write_u64::<BigEndian>(version.minor)?;
+    buf.write_u64::<BigEndian>(version.patch)?;
+    Ok(())
+}
+
+/// Writes the contents of a metadata section
+fn write_metadata<W: Write>(buf: &mut W, metadata: &ModuleMetadata) -> Result<(), Error> {
+    write_string(buf, &metadata.name)?;
+    write_version(buf, &metadata.version)?;
+    write_string(buf, &metadata.author)?;
+    write_string(buf, &metadata.description)?;
+    write_version(buf, &metadata.min_host_api)?;
+
+    buf.write_u64::<BigEndian>(metadata.properties.len() as u64)?;
+    for (key, value) in &(metadata.properties) {
+        write_string(buf, key)?;
+        write_string(buf, value)?;
+    }
+    Ok(())
+}
+
+/// Writes everything about `code` that decides how it runs, in the layout of the latest version:
+/// the constants, imports, exports and instructions. `constants` is written in place of the
+/// constant pool of `code`, which is empty when the constants are loaded lazily.
+pub(super) fn write_code_contents<W: Write>(buf: &mut W, code: &CodeHolder, constants: &[&Constant]) -> Result<(), Error> {
+    buf.write_u32::<BigEndian>(constants.len() as u32)?;
+    for constant in constants {
+        write_constant(buf, constant, pc::VER_MINOR)?;
+    }
+    write_imports_table(buf, code)?;
+    write_exports_table(buf, code)?;
+    write_instructions(buf, code, pc::VER_MINOR)?;
+    Ok(())
+}
+
+/// Writes the constants table
+fn write_constants_table<W: Write>(buf: &mut W, code: &CodeHolder, minor: u16) -> Result<(), Error> {
     buf.write_u32::<BigEndian>(code.constant_pool.len() as u32)?;
     for i in &(code.constant_pool) {
-        write_constant(&mut buf, i)?;
+        write_constant(buf, i, minor)?;
     }
+    Ok(())
+}
 
-    // imports table
+/// Writes the imports table
+fn write_imports_table<W: Write>(buf: &mut W, code: &CodeHolder) -> Result<(), Error> {
     buf.write_u64::<BigEndian>(code.imports.len() as u64)?;
     for i in &(code.imports) {
-        write_string(&mut buf, i)?;
+        write_string(buf, i)?;
     }
+    Ok(())
+}
 
-    // exports table
-    buf.write_u64::<BigEndian>(code.exports.len() as u64)?;
-    for (export_name, export_pos) in &(code.exports) {
-        write_string(&mut buf, export_name)?;
+/// Writes the exports table, sorted by name so the same code always produces the same bytes
+fn write_exports_table<W: Write>(buf: &mut W, code: &CodeHolder) -> Result<(), Error> {
+    let exports: BTreeMap<_, _> = code.exports.iter().collect();
+    buf.write_u64::<BigEndian>(exports.len() as u64)?;
+    for (export_name, export_pos) in exports {
+        write_string(buf, export_name)?;
         buf.write_u64::<BigEndian>(*export_pos)?;
     }
+    Ok(())
+}
 
-    // instructions
-    for i in &(code.instructions) {
-        let unwrapped_i = i.as_ref();
-        match unwrapped_i.unwrap() {
+/// Writes every instruction, failing if one is not available in minor version `minor`
+fn write_instructions<W: Write>(buf: &mut W, code: &CodeHolder, minor: u16) -> Result<(), Error> {
+    let compact = minor >= pc::VER_MINOR_COMPACT;
+    for (index, i) in code.instructions.iter().enumerate() {
+        let unwrapped_i = match i {
+            Some(instruction) => instruction,
+            None => {
+                return Err(Error::other(format!("Instruction {} is missing", index)))
+            }
+        };
+        let required = instruction_minor(unwrapped_i);
+        if required > minor {
+            return Err(unrepresentable(
+                &format!("Instruction {:?}", unwrapped_i),
+                required,
+                minor,
+            ));
+        }
+        match unwrapped_i {
             Instruction::Alloc(size) => {
-                buf.push(pc::INST_ALLOC);
-                buf.write_u32::<BigEndian>(*size)?;
+                buf.write_u8(pc::INST_ALLOC)?;
+                write_size_operand(buf, *size, compact)?;
             }
             Instruction::FrameAlloc(size, location) => {
-                buf.push(pc::INST_FRAME_ALLOC);
-                buf.write_u32::<BigEndian>(*size)?;
-                write_reg_loc(&mut buf, location)?;
+                buf.write_u8(pc::INST_FRAME_ALLOC)?;
+                write_size_operand(buf, *size, compact)?;
+                write_reg_loc(buf, location)?;
             }
             Instruction::Free(size) => {
-                buf.push(pc::INST_FREE);
-                buf.write_u32::<BigEndian>(*size)?;
+                buf.write_u8(pc::INST_FREE)?;
+                write_size_operand(buf, *size, compact)?;
             }
             Instruction::FrameFree(size, location) => {
-                buf.push(pc::INST_FRAME_FREE);
-                buf.write_u32::<BigEndian>(*size)?;
-                write_reg_loc(&mut buf, location)?;
+                buf.write_u8(pc::INST_FRAME_FREE)?;
+                write_size_operand(buf, *size, compact)?;
+                write_reg_loc(buf, location)?;
             }
             Instruction::Jump(addr) => {
-                buf.push(pc::INST_JUMP);
-                buf.write_i64::<BigEndian>(*addr)?;
+                buf.write_u8(pc::INST_JUMP)?;
+                write_offset_operand(buf, *addr, compact)?;
+            }
+            Instruction::JumpTo(addr) => {
+                buf.write_u8(pc::INST_JUMP_TO)?;
+                write_index_operand(buf, *addr, compact)?;
+            }
+            Instruction::JumpIfTrue(reg, addr) => {
+                buf.write_u8(pc::INST_JUMP_IF_TRUE)?;
+                write_register_operand(buf, reg, compact)?;
+                write_index_operand(buf, *addr, compact)?;
+            }
+            Instruction::JumpIfFalse(reg, addr) => {
+                buf.write_u8(pc::INST_JUMP_IF_FALSE)?;
+                write_register_operand(buf, reg, compact)?;
+                write_index_operand(buf, *addr, compact)?;
+            }
+            Instruction::JumpTable(reg, default, table) => {
+                buf.write_u8(pc::INST_JUMP_TABLE)?;
+                write_register_operand(buf, reg, compact)?;
+                write_index_operand(buf, *default, compact)?;
+                write_index_operand(buf, table.len() as u64, compact)?;
+                for addr in table {
+                    write_index_operand(buf, *addr, compact)?;
+                }
             }
             Instruction::Call(addr) => {
-                buf.push(pc::INST_CALL);
-                buf.write_u64::<BigEndian>(*addr)?;
+                buf.write_u8(pc::INST_CALL)?;
+                write_index_operand(buf, *addr, compact)?;
             }
             Instruction::ExtCall(id) => {
-                buf.push(pc::INST_EXTCALL);
-                buf.write_u64::<BigEndian>(*id)?;
+                buf.write_u8(pc::INST_EXTCALL)?;
+                write_index_operand(buf, *id, compact)?;
             }
             Instruction::Ret => {
-                buf.push(pc::INST_RET);
+                buf.write_u8(pc::INST_RET)?;
             }
             Instruction::Mov(ra, aref, rb, bref) => {
-                buf.push(pc::INST_MOV);
-                write_register(&mut buf, ra)?;
-                write_reg_ref(&mut buf, aref);
-                write_register(&mut buf, rb)?;
-                write_reg_ref(&mut buf, bref);
+                buf.write_u8(pc::INST_MOV)?;
+                write_register_ref_operand(buf, ra, aref, compact)?;
+                write_register_ref_operand(buf, rb, bref, compact)?;
             }
             Instruction::Cpy(ra, aref, rb, bref) => {
-                buf.push(pc::INST_CPY);
-                write_register(&mut buf, ra)?;
-                write_reg_ref(&mut buf, aref);
-                write_register(&mut buf, rb)?;
-                write_reg_ref(&mut buf, bref);
+                buf.write_u8(pc::INST_CPY)?;
+                write_register_ref_operand(buf, ra, aref, compact)?;
+                write_register_ref_operand(buf, rb, bref, compact)?;
             }
             Instruction::Ref(ra, aref, rb, bref) => {
-                buf.push(pc::INST_REF);
-                write_register(&mut buf, ra)?;
-                write_reg_ref(&mut buf, aref);
-                write_register(&mut buf, rb)?;
-                write_reg_ref(&mut buf, bref);
+                buf.write_u8(pc::INST_REF)?;
+                write_register_ref_operand(buf, ra, aref, compact)?;
+                write_register_ref_operand(buf, rb, bref, compact)?;
             }
             Instruction::StackPush(reg, rref) => {
-                buf.push(pc::INST_STACK_PUSH);
-                write_register(&mut buf, reg)?;
-                write_reg_ref(&mut buf, rref);
+                buf.write_u8(pc::INST_STACK_PUSH)?;
+                write_register_ref_operand(buf, reg, rref, compact)?;
             }
             Instruction::StackMov(ra, aref) => {
-                buf.push(pc::INST_STACK_MOV);
-                write_register(&mut buf, ra)?;
-                write_reg_ref(&mut buf, aref);
+                buf.write_u8(pc::INST_STACK_MOV)?;
+                write_register_ref_operand(buf, ra, aref, compact)?;
             }
             Instruction::StackPop => {
-                buf.push(pc::INST_STACK_POP);
+                buf.write_u8(pc::INST_STACK_POP)?;
             }
             Instruction::Add(ra, rb, rc) => {
-                buf.push(pc::INST_ADD);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
-                write_register(&mut buf, rc)?;
+                buf.write_u8(pc::INST_ADD)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
             }
             Instruction::Sub(ra, rb, rc) => {
-                buf.push(pc::INST_SUB);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
-                write_register(&mut buf, rc)?;
+                buf.write_u8(pc::INST_SUB)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
             }
             Instruction::Mul(ra, rb, rc) => {
-                buf.push(pc::INST_MUL);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
-                write_register(&mut buf, rc)?;
+                buf.write_u8(pc::INST_MUL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
             }
             Instruction::Div(ra, rb, rc) => {
-                buf.push(pc::INST_DIV);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
-                write_register(&mut buf, rc)?;
+                buf.write_u8(pc::INST_DIV)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
             }
             Instruction::Mod(ra, rb, rc) => {
-                buf.push(pc::INST_MOD);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
-                write_register(&mut buf, rc)?;
+                buf.write_u8(pc::INST_MOD)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
             }
             Instruction::Equal(ra, rb) => {
-                buf.push(pc::INST_EQUAL);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_EQUAL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             Instruction::NotEqual(ra, rb) => {
-                buf.push(pc::INST_NOT_EQUAL);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_NOT_EQUAL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             Instruction::Greater(ra, rb) => {
-                buf.push(pc::INST_GREATER);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_GREATER)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             Instruction::Less(ra, rb) => {
-                buf.push(pc::INST_LESS);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_LESS)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             Instruction::GreaterEqual(ra, rb) => {
-                buf.push(pc::INST_GREATER_EQUAL);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_GREATER_EQUAL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             Instruction::LessEqual(ra, rb) => {
-                buf.push(pc::INST_LESS_EQUAL);
-                write_register(&mut buf, ra)?;
-                write_register(&mut buf, rb)?;
+                buf.write_u8(pc::INST_LESS_EQUAL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::ToInt(ra, rb) => {
+                buf.write_u8(pc::INST_TO_INT)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::ToDouble(ra, rb) => {
+                buf.write_u8(pc::INST_TO_DOUBLE)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::ToString(ra, rb) => {
+                buf.write_u8(pc::INST_TO_STRING)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::ToBool(ra, rb) => {
+                buf.write_u8(pc::INST_TO_BOOL)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::TypeOf(ra, rb) => {
+                buf.write_u8(pc::INST_TYPE_OF)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::StrLen(ra, rb) => {
+                buf.write_u8(pc::INST_STR_LEN)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::Substr(ra, rb, rc, rd) => {
+                buf.write_u8(pc::INST_SUBSTR)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+                write_register_operand(buf, rd, compact)?;
+            }
+            Instruction::CharAt(ra, rb, rc) => {
+                buf.write_u8(pc::INST_CHAR_AT)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::VecNew(ra) => {
+                buf.write_u8(pc::INST_VEC_NEW)?;
+                write_register_operand(buf, ra, compact)?;
+            }
+            Instruction::VecGet(ra, rb, rc) => {
+                buf.write_u8(pc::INST_VEC_GET)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::VecSet(ra, rb, rc) => {
+                buf.write_u8(pc::INST_VEC_SET)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::VecPush(ra, rb) => {
+                buf.write_u8(pc::INST_VEC_PUSH)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::VecPop(ra, rb) => {
+                buf.write_u8(pc::INST_VEC_POP)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::VecLen(ra, rb) => {
+                buf.write_u8(pc::INST_VEC_LEN)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::MapNew(ra) => {
+                buf.write_u8(pc::INST_MAP_NEW)?;
+                write_register_operand(buf, ra, compact)?;
+            }
+            Instruction::MapGet(ra, rb, rc) => {
+                buf.write_u8(pc::INST_MAP_GET)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::MapSet(ra, rb, rc) => {
+                buf.write_u8(pc::INST_MAP_SET)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::MapRemove(ra, rb) => {
+                buf.write_u8(pc::INST_MAP_REMOVE)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::MapContains(ra, rb, rc) => {
+                buf.write_u8(pc::INST_MAP_CONTAINS)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+                write_register_operand(buf, rc, compact)?;
+            }
+            Instruction::MapLen(ra, rb) => {
+                buf.write_u8(pc::INST_MAP_LEN)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
+            }
+            Instruction::MapKeys(ra, rb) => {
+                buf.write_u8(pc::INST_MAP_KEYS)?;
+                write_register_operand(buf, ra, compact)?;
+                write_register_operand(buf, rb, compact)?;
             }
             _ => {
-                panic!(".__. I don't reconize this instruction");
+                return Err(Error::other(format!("Instruction {:?} can not be written to bytecode", unwrapped_i)))
             }
         }
     }
 
-    Ok(buf)
+    Ok(())
 }
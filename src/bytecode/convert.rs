@@ -0,0 +1,47 @@
+/*!
+# Bytecode Conversion API
+This module provides functions for migrating bytecode between versions of the format, such as when
+the format version is bumped and stored bytecode needs to be upgraded, or when bytecode has to run
+on an older runtime.
+*/
+
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::Error;
+use std::path::Path;
+use std::result::Result;
+
+use super::codereader::read_bytecode;
+use super::codewriter::write_bytecode_version;
+use super::parser_constants as pc;
+
+/// The newest bytecode version as `(major, minor)`. This is the version used by
+/// [`crate::bytecode::write_bytecode`].
+pub const LATEST_VERSION: (u16, u16) = (pc::VER_MAJOR, pc::VER_MINOR);
+
+/// Reads bytecode of any supported version and rewrites it as bytecode of version
+/// `major.minor`.
+///
+/// Fails if the bytecode can not be read, or if the program uses instructions or constants that
+/// the target version can not represent.
+pub fn convert_bytecode(buf: &[u8], major: u16, minor: u16) -> Result<Vec<u8>, Error> {
+    let holder = read_bytecode(buf)?;
+    write_bytecode_version(&holder, major, minor)
+}
+
+/// Reads a bytecode file of any supported version and writes it to `dst` as bytecode of version
+/// `major.minor`. `dst` is left untouched if the conversion fails.
+pub fn convert_bytecode_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    src: P,
+    dst: Q,
+    major: u16,
+    minor: u16,
+) -> Result<(), Error> {
+    let mut buf = Vec::new();
+    File::open(src)?.read_to_end(&mut buf)?;
+
+    let data = convert_bytecode(&buf, major, minor)?;
+    File::create(dst)?.write_all(&data)?;
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+/*!
+# Bytecode Integrity API
+This module provides checksums for detecting corrupted bytecode and a pluggable signing API for
+verifying who published it.
+
+Signing and verification are done by implementations of [`Signer`] and [`SignatureVerifier`], so
+applications can use whichever algorithm and key storage they want. With the `ed25519` feature
+enabled, [`Ed25519Signer`] and [`Ed25519Verifier`] are provided.
+
+# Examples
+Write bytecode with a checksum and refuse to read it back without one:
+```no_run
+use resurgence::bytecode::{self, ReadOptions, WriteOptions};
+use resurgence::CodeHolder;
+
+let options = WriteOptions { checksum: true, ..Default::default() };
+let data = bytecode::write_bytecode_with_options(&CodeHolder::new(), &options).unwrap();
+
+let options = ReadOptions { require_checksum: true, ..Default::default() };
+let holder = bytecode::read_bytecode_with_options(&data, &options).unwrap();
+```
+
+Signatures can also be kept out of the bytecode with [`sign_bytecode`] and [`verify_bytecode`], for
+example to sign bytecode that was already published, or to ship the signature as its own file:
+```no_run
+use resurgence::bytecode::integrity::{sign_bytecode, verify_bytecode, SignatureVerifier, Signer};
+
+fn check(data: &[u8], signer: &dyn Signer, verifier: &dyn SignatureVerifier) {
+    let signature = sign_bytecode(data, signer);
+    verify_bytecode(data, &signature, verifier).unwrap();
+}
+```
+*/
+
+use std::io::{Error, ErrorKind};
+
+use super::codewriter::InstructionEncoding;
+
+/// Signs bytecode so its publisher can be verified by a [`SignatureVerifier`]
+pub trait Signer {
+    /// Returns the identifier of the key used for signing, which is stored next to the signature
+    /// so verifiers can look up the matching public key
+    fn key_id(&self) -> &str;
+
+    /// Signs `data` and returns the signature
+    fn sign(&self, data: &[u8]) -> Vec<u8>;
+}
+
+/// Verifies signatures made by a [`Signer`]. This acts as the key provider as well: only keys
+/// known to the verifier are trusted.
+pub trait SignatureVerifier {
+    /// Returns true if `signature` is a valid signature of `data` made with the key `key_id`.
+    /// Implementations must return false for keys they do not know.
+    fn verify(&self, key_id: &str, data: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Options for writing bytecode with integrity information, a compressed body or a particular
+/// instruction encoding
+#[derive(Default)]
+pub struct WriteOptions<'a> {
+    /// Append a checksum that readers validate to detect corruption
+    pub checksum: bool,
+    /// Sign the bytecode with this signer
+    pub signer: Option<&'a dyn Signer>,
+    /// Compress everything following the header. Checksums and signatures are computed over the
+    /// uncompressed bytecode.
+    pub compress: bool,
+    /// Encoding of instruction operands, compact unless set otherwise
+    pub encoding: InstructionEncoding,
+}
+
+/// Default for [`ReadOptions::max_decompressed_size`], 64 MiB
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Options for which integrity information reading bytecode requires, and how large a compressed
+/// body may get. Checksums that are present are always validated, even when they are not required.
+pub struct ReadOptions<'a> {
+    /// Refuse bytecode that does not contain a checksum
+    pub require_checksum: bool,
+    /// Refuse bytecode that is not signed with a key this verifier accepts
+    pub verifier: Option<&'a dyn SignatureVerifier>,
+    /// Refuse compressed bytecode whose body decompresses to more than this many bytes, so a
+    /// small malicious file can not exhaust memory
+    pub max_decompressed_size: usize,
+}
+
+impl Default for ReadOptions<'_> {
+    fn default() -> Self {
+        ReadOptions {
+            require_checksum: false,
+            verifier: None,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+/// Signs all of `data` and returns a detached signature, holding the key identifier of `signer`
+/// followed by the signature. `data` is left unchanged, unlike signing with [`WriteOptions`].
+pub fn sign_bytecode(data: &[u8], signer: &dyn Signer) -> Vec<u8> {
+    // same layout as the contents of a signature section
+    let key_id = signer.key_id().as_bytes();
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(key_id.len() as u64).to_be_bytes());
+    buf.extend_from_slice(key_id);
+    buf.extend_from_slice(&signer.sign(data));
+    buf
+}
+
+/// Checks a detached signature made by [`sign_bytecode`] over all of `data`. Fails if the
+/// signature is malformed, or not valid for a key `verifier` trusts.
+pub fn verify_bytecode(data: &[u8], signature: &[u8], verifier: &dyn SignatureVerifier) -> Result<(), Error> {
+    let malformed = || Error::new(ErrorKind::InvalidData, "Malformed detached signature");
+    let (len, rest) = signature.split_first_chunk::<8>().ok_or_else(malformed)?;
+    let len = usize::try_from(u64::from_be_bytes(*len)).map_err(|_| malformed())?;
+    if len > rest.len() {
+        return Err(malformed());
+    }
+    let (key_id, signature) = rest.split_at(len);
+    let key_id = std::str::from_utf8(key_id).map_err(|_| malformed())?;
+
+    if !verifier.verify(key_id, data, signature) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Bytecode signature by key \"{}\" is not valid", key_id),
+        ));
+    }
+    Ok(())
+}
+
+/// Lookup table for the CRC-32 (IEEE 802.3) polynomial
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB88320,
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Running CRC-32 checksum
+#[derive(Clone, Copy)]
+pub(crate) struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    pub(crate) fn new() -> Crc32 {
+        Crc32 { state: 0xFFFFFFFF }
+    }
+
+    /// Adds `data` to the checksum
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state = CRC32_TABLE[((self.state ^ *byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Returns the checksum of all data added so far
+    pub(crate) fn value(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[cfg(feature = "ed25519")]
+pub use self::ed25519::{Ed25519Signer, Ed25519Verifier};
+
+#[cfg(feature = "ed25519")]
+mod ed25519 {
+    use std::collections::HashMap;
+    use std::io::{Error, ErrorKind};
+
+    use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+
+    use super::{SignatureVerifier, Signer};
+
+    /// Signs bytecode with an Ed25519 secret key
+    pub struct Ed25519Signer {
+        key_id: String,
+        key: SigningKey,
+    }
+
+    impl Ed25519Signer {
+        /// Creates a signer from a 32 byte secret key
+        ///
+        /// `key_id` (`String`): identifier verifiers use to look up the matching public key
+        /// `secret_key` (`&[u8; 32]`): the secret key
+        pub fn new(key_id: String, secret_key: &[u8; 32]) -> Ed25519Signer {
+            Ed25519Signer {
+                key_id,
+                key: SigningKey::from_bytes(secret_key),
+            }
+        }
+
+        /// Returns the public key matching the secret key of this signer
+        pub fn public_key(&self) -> [u8; 32] {
+            self.key.verifying_key().to_bytes()
+        }
+    }
+
+    impl Signer for Ed25519Signer {
+        fn key_id(&self) -> &str {
+            &self.key_id
+        }
+
+        fn sign(&self, data: &[u8]) -> Vec<u8> {
+            self.key.sign(data).to_bytes().to_vec()
+        }
+    }
+
+    /// Verifies Ed25519 signatures against a set of trusted public keys
+    #[derive(Default)]
+    pub struct Ed25519Verifier {
+        keys: HashMap<String, VerifyingKey>,
+    }
+
+    impl Ed25519Verifier {
+        /// Creates a verifier that does not trust any keys yet
+        pub fn new() -> Ed25519Verifier {
+            Ed25519Verifier {
+                keys: HashMap::new(),
+            }
+        }
+
+        /// Trusts a public key. Fails if the bytes are not a valid Ed25519 public key.
+        ///
+        /// `key_id` (`String`): identifier the signer stores next to its signatures
+        /// `public_key` (`&[u8; 32]`): the public key
+        pub fn add_key(&mut self, key_id: String, public_key: &[u8; 32]) -> Result<(), Error> {
+            let key = VerifyingKey::from_bytes(public_key)
+                .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+            self.keys.insert(key_id, key);
+            Ok(())
+        }
+    }
+
+    impl SignatureVerifier for Ed25519Verifier {
+        fn verify(&self, key_id: &str, data: &[u8], signature: &[u8]) -> bool {
+            let key = match self.keys.get(key_id) {
+                Some(key) => key,
+                None => return false,
+            };
+            match Signature::from_slice(signature) {
+                Ok(signature) => key.verify(data, &signature).is_ok(),
+                Err(_) => false,
+            }
+        }
+    }
+}
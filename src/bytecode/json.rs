@@ -0,0 +1,44 @@
+/*!
+# JSON API
+This module converts between bytecode and a JSON representation of [`CodeHolder`], which is easier
+to generate, inspect and diff. The binary format stays canonical: JSON is not a bytecode version,
+and converting JSON to bytecode always writes the latest version of the binary format.
+
+Requires the `serde` feature.
+
+# Examples
+```no_run
+use resurgence::bytecode;
+
+let data = std::fs::read("path/to/bytecode.rvm").unwrap();
+let json = bytecode::bytecode_to_json(&data).unwrap();
+let data = bytecode::json_to_bytecode(&json).unwrap();
+```
+*/
+
+use std::io::Error;
+use std::result::Result;
+
+use super::codereader::read_bytecode;
+use super::codewriter::write_bytecode;
+use crate::objects::codeholder::CodeHolder;
+
+/// Converts a CodeHolder into pretty-printed JSON
+pub fn code_holder_to_json(code: &CodeHolder) -> Result<String, Error> {
+    Ok(serde_json::to_string_pretty(code)?)
+}
+
+/// Parses a CodeHolder from JSON created by [`code_holder_to_json`]
+pub fn code_holder_from_json(json: &str) -> Result<CodeHolder, Error> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Reads bytecode of any supported version and converts it into pretty-printed JSON
+pub fn bytecode_to_json(buf: &[u8]) -> Result<String, Error> {
+    code_holder_to_json(&read_bytecode(buf)?)
+}
+
+/// Parses JSON created by [`bytecode_to_json`] and converts it into bytecode
+pub fn json_to_bytecode(json: &str) -> Result<Vec<u8>, Error> {
+    write_bytecode(&code_holder_from_json(json)?)
+}
@@ -0,0 +1,85 @@
+/*!
+# LEB128
+Reading and writing of variable-length LEB128 integers, used by the compact instruction encoding.
+*/
+
+use std::io::prelude::*;
+use std::io::Error;
+
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+/// Writes an unsigned LEB128 integer
+pub(crate) fn write_unsigned<W: Write>(buf: &mut W, mut val: u64) -> Result<(), Error> {
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if val == 0 {
+            return buf.write_u8(byte);
+        }
+        buf.write_u8(byte | 0x80)?;
+    }
+}
+
+/// Writes a signed LEB128 integer
+pub(crate) fn write_signed<W: Write>(buf: &mut W, mut val: i64) -> Result<(), Error> {
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        // stop once the remaining bits only repeat the sign bit of the last byte
+        if (val == 0 && byte & 0x40 == 0) || (val == -1 && byte & 0x40 != 0) {
+            return buf.write_u8(byte);
+        }
+        buf.write_u8(byte | 0x80)?;
+    }
+}
+
+/// Creates the error returned for integers that do not fit in 64 bits
+fn overflow() -> Error {
+    Error::other("LEB128 integer does not fit in 64 bits")
+}
+
+/// Reads an unsigned LEB128 integer
+pub(crate) fn read_unsigned<R: Read>(cur: &mut R) -> Result<u64, Error> {
+    let mut val = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = cur.read_u8()?;
+        let bits = (byte & 0x7F) as u64;
+        if shift == 63 && bits > 1 {
+            return Err(overflow());
+        }
+        val |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(val);
+        }
+        shift += 7;
+        if shift > 63 {
+            return Err(overflow());
+        }
+    }
+}
+
+/// Reads a signed LEB128 integer
+pub(crate) fn read_signed<R: Read>(cur: &mut R) -> Result<i64, Error> {
+    let mut val = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = cur.read_u8()?;
+        let bits = (byte & 0x7F) as i64;
+        // the last byte may only hold the sign bit
+        if shift == 63 && bits != 0 && bits != 0x7F {
+            return Err(overflow());
+        }
+        val |= bits << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                val |= -1 << shift;
+            }
+            return Ok(val);
+        }
+        if shift > 63 {
+            return Err(overflow());
+        }
+    }
+}
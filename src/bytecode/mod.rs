@@ -18,11 +18,70 @@ use resurgence::{bytecode, CodeHolder};
 let holder = CodeHolder::new();
 bytecode::write_bytecode_file(&holder, "path/to/destination.rvm").unwrap();
 ```
+
+Read bytecode from any [`std::io::Read`] implementation, such as an in-memory slice:
+```no_run
+use resurgence::bytecode;
+
+let data: &[u8] = &[];
+let holder = bytecode::read_bytecode_from(data).unwrap();
+```
+
+Write bytecode with a compressed body, which all readers decompress transparently:
+```no_run
+use resurgence::bytecode::{self, WriteOptions};
+use resurgence::CodeHolder;
+
+let options = WriteOptions { compress: true, ..Default::default() };
+let data = bytecode::write_bytecode_with_options(&CodeHolder::new(), &options).unwrap();
+let holder = bytecode::read_bytecode(&data).unwrap();
+```
+
+Large bytecode can be loaded without copying it out of its buffer with [`BytecodeView`], or run
+directly with [`crate::Interpreter::from_bytes`].
+
+With the `serde` feature enabled, bytecode can be converted to and from JSON:
+```no_run
+use resurgence::bytecode;
+
+let data = std::fs::read("path/to/bytecode.rvm").unwrap();
+let json = bytecode::bytecode_to_json(&data).unwrap();
+```
+
+Checksums and signatures are covered in the [`integrity`] module.
+
+Rewrite a bytecode file as version 7.4 of the format:
+```no_run
+use resurgence::bytecode;
+
+bytecode::convert_bytecode_file("path/to/bytecode.rvm", "path/to/destination.rvm", 7, 4).unwrap();
+```
 */
 
 pub(crate) mod codereader;
 pub(crate) mod codewriter;
+pub(crate) mod convert;
+pub mod integrity;
+#[cfg(feature = "serde")]
+pub(crate) mod json;
+mod leb128;
 mod parser_constants;
+pub(crate) mod snapshot;
+pub(crate) mod view;
 
-pub use codereader::{read_bytecode, read_bytecode_file};
-pub use codewriter::{write_bytecode, write_bytecode_file};
+pub use codereader::{
+    read_bytecode, read_bytecode_file, read_bytecode_from, read_bytecode_from_with_options,
+    read_bytecode_with_options,
+};
+pub use codewriter::{
+    write_bytecode, write_bytecode_file, write_bytecode_to, write_bytecode_version,
+    write_bytecode_with_options, InstructionEncoding,
+};
+pub use convert::{convert_bytecode, convert_bytecode_file, LATEST_VERSION};
+pub use integrity::{
+    sign_bytecode, verify_bytecode, ReadOptions, SignatureVerifier, Signer, WriteOptions,
+    DEFAULT_MAX_DECOMPRESSED_SIZE,
+};
+#[cfg(feature = "serde")]
+pub use json::{bytecode_to_json, code_holder_from_json, code_holder_to_json, json_to_bytecode};
+pub use view::{BytecodeView, Instructions};
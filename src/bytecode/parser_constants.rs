@@ -8,7 +8,69 @@ pub(crate) const MAGIC_NUMBER: u32 = 0x52564D88;
 
 /// Format version number
 pub(crate) const VER_MAJOR: u16 = 7;
-pub(crate) const VER_MINOR: u16 = 0;
+pub(crate) const VER_MINOR: u16 = 9;
+
+/// First minor version of `VER_MAJOR` with the `JumpTo`, `JumpIfTrue`, `JumpIfFalse` and
+/// `JumpTable` instructions
+pub(crate) const VER_MINOR_JUMPS: u16 = 1;
+
+/// First minor version of `VER_MAJOR` with the conversion instructions and `TypeOf`
+pub(crate) const VER_MINOR_CASTS: u16 = 2;
+
+/// First minor version of `VER_MAJOR` with the string instructions
+pub(crate) const VER_MINOR_STRINGS: u16 = 3;
+
+/// First minor version of `VER_MAJOR` with the vector instructions
+pub(crate) const VER_MINOR_VECTORS: u16 = 4;
+
+/// First minor version of `VER_MAJOR` with map constants and the map instructions
+pub(crate) const VER_MINOR_MAPS: u16 = 5;
+
+/// First minor version of `VER_MAJOR` that writes a `u64` element count for vector constants.
+/// Older minor versions use a `u8` element count.
+pub(crate) const VER_MINOR_VEC_U64_LEN: u16 = 6;
+
+/// First minor version of `VER_MAJOR` that stores its contents in sections
+pub(crate) const VER_MINOR_SECTIONS: u16 = 7;
+
+/// First minor version of `VER_MAJOR` that has a header flags byte after the version number
+pub(crate) const VER_MINOR_HEADER_FLAGS: u16 = 8;
+
+/// First minor version of `VER_MAJOR` that uses the compact instruction encoding
+pub(crate) const VER_MINOR_COMPACT: u16 = 9;
+
+/// Size of the header in versions with header flags (magic number, version number and flags)
+pub(crate) const HEADER_LEN: usize = 9;
+
+/// Header flag marking a body compressed with DEFLATE
+pub(crate) const HEADER_FLAG_COMPRESSED: u8 = 0x01;
+
+/// Deepest nesting of vector and map constants that is read or written. Deeper constants would
+/// overflow the stack of the recursive reader and writer.
+pub(crate) const MAX_CONSTANT_DEPTH: u32 = 256;
+
+/// DEFLATE level used when compressing bodies
+pub(crate) const COMPRESSION_LEVEL: u8 = 9;
+
+/*
+ * Section types
+ */
+pub(crate) const SECTION_CONSTANTS: u8 = 0x01;
+pub(crate) const SECTION_IMPORTS: u8 = 0x02;
+pub(crate) const SECTION_EXPORTS: u8 = 0x03;
+pub(crate) const SECTION_CODE: u8 = 0x04;
+pub(crate) const SECTION_CUSTOM: u8 = 0x05;
+pub(crate) const SECTION_METADATA: u8 = 0x06;
+pub(crate) const SECTION_CHECKSUM: u8 = 0x07;
+pub(crate) const SECTION_SIGNATURE: u8 = 0x08;
+
+/*
+ * Checksum algorithms
+ */
+pub(crate) const CHECKSUM_CRC32: u8 = 0x01;
+
+/// Section flag marking sections that readers must understand to load the bytecode
+pub(crate) const SECTION_FLAG_REQUIRED: u8 = 0x01;
 
 /*
  * Constant types
@@ -20,6 +82,7 @@ pub(crate) const CONST_STRING: u8 = 0x03;
 pub(crate) const CONST_BOOLEAN: u8 = 0x04;
 pub(crate) const CONST_ADDRESS: u8 = 0x05;
 pub(crate) const CONST_VEC: u8 = 0x06;
+pub(crate) const CONST_MAP: u8 = 0x07;
 
 /*
  * Register locations
@@ -35,6 +98,15 @@ pub(crate) const LOC_LOCAL: u8 = 0x04;
 pub(crate) const REF_AS_IS: u8 = 0x01;
 pub(crate) const REF_DEREFERENCE: u8 = 0x02;
 
+/*
+ * Packed register locations and reference types of the compact instruction encoding
+ */
+pub(crate) const PACKED_LOC_CONSTANT: u64 = 0b00;
+pub(crate) const PACKED_LOC_ACCUMULATOR: u64 = 0b01;
+pub(crate) const PACKED_LOC_GLOBAL: u64 = 0b10;
+pub(crate) const PACKED_LOC_LOCAL: u64 = 0b11;
+pub(crate) const PACKED_REF_DEREFERENCE: u64 = 0b100;
+
 /*
  * Instruction numbers
  */
@@ -116,3 +188,78 @@ pub(crate) const INST_MOD: u8 = 0x18;
 
 // Ret
 pub(crate) const INST_RET: u8 = 0x19;
+
+// JumpTo
+pub(crate) const INST_JUMP_TO: u8 = 0x1A;
+
+// JumpIfTrue
+pub(crate) const INST_JUMP_IF_TRUE: u8 = 0x1B;
+
+// JumpIfFalse
+pub(crate) const INST_JUMP_IF_FALSE: u8 = 0x1C;
+
+// JumpTable
+pub(crate) const INST_JUMP_TABLE: u8 = 0x1D;
+
+// ToInt
+pub(crate) const INST_TO_INT: u8 = 0x1E;
+
+// ToDouble
+pub(crate) const INST_TO_DOUBLE: u8 = 0x1F;
+
+// ToString
+pub(crate) const INST_TO_STRING: u8 = 0x20;
+
+// ToBool
+pub(crate) const INST_TO_BOOL: u8 = 0x21;
+
+// TypeOf
+pub(crate) const INST_TYPE_OF: u8 = 0x22;
+
+// StrLen
+pub(crate) const INST_STR_LEN: u8 = 0x23;
+
+// Substr
+pub(crate) const INST_SUBSTR: u8 = 0x24;
+
+// CharAt
+pub(crate) const INST_CHAR_AT: u8 = 0x25;
+
+// VecNew
+pub(crate) const INST_VEC_NEW: u8 = 0x26;
+
+// VecGet
+pub(crate) const INST_VEC_GET: u8 = 0x27;
+
+// VecSet
+pub(crate) const INST_VEC_SET: u8 = 0x28;
+
+// VecPush
+pub(crate) const INST_VEC_PUSH: u8 = 0x29;
+
+// VecPop
+pub(crate) const INST_VEC_POP: u8 = 0x2A;
+
+// VecLen
+pub(crate) const INST_VEC_LEN: u8 = 0x2B;
+
+// MapNew
+pub(crate) const INST_MAP_NEW: u8 = 0x2C;
+
+// MapGet
+pub(crate) const INST_MAP_GET: u8 = 0x2D;
+
+// MapSet
+pub(crate) const INST_MAP_SET: u8 = 0x2E;
+
+// MapRemove
+pub(crate) const INST_MAP_REMOVE: u8 = 0x2F;
+
+// MapContains
+pub(crate) const INST_MAP_CONTAINS: u8 = 0x30;
+
+// MapLen
+pub(crate) const INST_MAP_LEN: u8 = 0x31;
+
+// MapKeys
+pub(crate) const INST_MAP_KEYS: u8 = 0x32;
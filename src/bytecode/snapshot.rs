@@ -0,0 +1,225 @@
+/*!
+# Snapshot Format
+This module reads and writes snapshots of the runtime state of an [`crate::Interpreter`], made
+with [`crate::Interpreter::snapshot`].
+
+A snapshot starts with its own magic number and version, followed by a hash of the code it was
+taken from, so it is only restored onto the same code. It ends with a CRC-32 checksum of
+everything before it.
+*/
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::prelude::*;
+use std::io::{Error, ErrorKind};
+
+use super::codereader::{read_constant, read_u8_or_end, PositionReader};
+use super::codewriter::{write_code_contents, write_constant};
+use super::integrity::Crc32;
+use super::parser_constants as pc;
+use crate::internal::interpreter::execution_engine::Suspension;
+use crate::objects::codeholder::CodeHolder;
+use crate::objects::constant::Constant;
+use crate::objects::stackframe::StackFrame;
+
+/// Magic number of snapshots ("RVMS")
+const SNAPSHOT_MAGIC: u32 = 0x52564D53;
+/// Version of the snapshot format
+const SNAPSHOT_VERSION: u16 = 1;
+
+/// The runtime state of an interpreter stored in a snapshot
+pub(crate) struct VmState {
+    pub(crate) accumulator: f64,
+    pub(crate) global: Vec<Option<Constant>>,
+    pub(crate) call_stack: Vec<StackFrame>,
+    pub(crate) stack: Vec<Constant>,
+    pub(crate) suspended: Option<Suspension>,
+}
+
+/// Running 64 bit FNV-1a hash. Unlike the hashers of the standard library, its output never
+/// changes, so snapshots stay valid across builds.
+struct CodeHasher {
+    state: u64,
+}
+
+impl Write for CodeHasher {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        for byte in buf {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(0x100000001B3);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Hashes everything about `code` that decides how it runs, so a snapshot can tell whether it is
+/// restored onto the code it was taken from
+///
+/// `constants` is hashed in place of the constant pool of `code`, which is empty when the
+/// constants are loaded lazily
+pub(crate) fn code_hash(code: &CodeHolder, constants: &[&Constant]) -> Result<u64, Error> {
+    let mut hasher = CodeHasher {
+        state: 0xCBF29CE484222325,
+    };
+    write_code_contents(&mut hasher, code, constants)?;
+    Ok(hasher.state)
+}
+
+/// Writes a snapshot of the runtime state of an interpreter running code with the hash
+/// `code_hash`
+pub(crate) fn write_snapshot(
+    code_hash: u64,
+    accumulator: f64,
+    global: &[Option<Constant>],
+    call_stack: &[StackFrame],
+    stack: &[Constant],
+    suspended: Option<&Suspension>,
+) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    buf.write_u32::<BigEndian>(SNAPSHOT_MAGIC)?;
+    buf.write_u16::<BigEndian>(SNAPSHOT_VERSION)?;
+    buf.write_u64::<BigEndian>(code_hash)?;
+    buf.write_f64::<BigEndian>(accumulator)?;
+
+    match suspended {
+        Some(suspension) => {
+            buf.write_u8(0x01)?;
+            buf.write_u64::<BigEndian>(suspension.index as u64)?;
+            buf.write_u64::<BigEndian>(suspension.returns.len() as u64)?;
+            for ret in &suspension.returns {
+                buf.write_u64::<BigEndian>(*ret as u64)?;
+            }
+        }
+        None => buf.write_u8(0x00)?,
+    }
+
+    write_registers(&mut buf, global)?;
+    buf.write_u64::<BigEndian>(call_stack.len() as u64)?;
+    for frame in call_stack {
+        write_registers(&mut buf, &frame.registers)?;
+    }
+    buf.write_u64::<BigEndian>(stack.len() as u64)?;
+    for constant in stack {
+        write_constant(&mut buf, constant, pc::VER_MINOR)?;
+    }
+
+    let mut checksum = Crc32::new();
+    checksum.update(&buf);
+    buf.write_u32::<BigEndian>(checksum.value())?;
+    Ok(buf)
+}
+
+/// Reads a snapshot, failing if it is corrupted or was not taken from code with the hash
+/// `code_hash`
+pub(crate) fn read_snapshot(data: &[u8], code_hash: u64) -> Result<VmState, Error> {
+    if data.len() < 4 {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Snapshot is too short"));
+    }
+    let (body, stored) = data.split_at(data.len() - 4);
+    let mut checksum = Crc32::new();
+    checksum.update(body);
+    if checksum.value().to_be_bytes() != stored {
+        return Err(Error::other("Snapshot checksum does not match; the snapshot is corrupted"));
+    }
+
+    let mut cur = PositionReader::with_position(body, 0);
+    if cur.read_u32::<BigEndian>()? != SNAPSHOT_MAGIC {
+        return Err(Error::other("Data is not a snapshot"));
+    }
+    let version = cur.read_u16::<BigEndian>()?;
+    if version != SNAPSHOT_VERSION {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!("Can not read snapshot version {} (only {} supported)", version, SNAPSHOT_VERSION),
+        ));
+    }
+    if cur.read_u64::<BigEndian>()? != code_hash {
+        return Err(Error::other("Snapshot was taken from different code"));
+    }
+    let accumulator = cur.read_f64::<BigEndian>()?;
+
+    let suspended = match cur.read_u8()? {
+        0x00 => None,
+        0x01 => {
+            let index = read_index(&mut cur)?;
+            let count = cur.read_u64::<BigEndian>()?;
+            let mut returns = Vec::new();
+            for _ in 0..count {
+                returns.push(read_index(&mut cur)?);
+            }
+            Some(Suspension { index, returns })
+        }
+        flag => {
+            return Err(Error::other(format!("Invalid suspension flag {} at position {}", flag, cur.position() - 1)))
+        }
+    };
+
+    let global = read_registers(&mut cur)?;
+    let frame_count = cur.read_u64::<BigEndian>()?;
+    let mut call_stack = Vec::new();
+    for _ in 0..frame_count {
+        call_stack.push(StackFrame {
+            registers: read_registers(&mut cur)?,
+        });
+    }
+    let stack_size = cur.read_u64::<BigEndian>()?;
+    let mut stack = Vec::new();
+    for _ in 0..stack_size {
+        stack.push(read_constant(&mut cur, pc::VER_MINOR)?);
+    }
+
+    if read_u8_or_end(&mut cur)?.is_some() {
+        return Err(Error::other(format!("Unexpected data after the snapshot at position {}", cur.position() - 1)));
+    }
+
+    Ok(VmState {
+        accumulator,
+        global,
+        call_stack,
+        stack,
+        suspended,
+    })
+}
+
+/// Writes registers, each preceded by a byte telling whether it holds a value
+fn write_registers<W: Write>(buf: &mut W, registers: &[Option<Constant>]) -> Result<(), Error> {
+    buf.write_u64::<BigEndian>(registers.len() as u64)?;
+    for register in registers {
+        match register {
+            Some(constant) => {
+                buf.write_u8(0x01)?;
+                write_constant(buf, constant, pc::VER_MINOR)?;
+            }
+            None => buf.write_u8(0x00)?,
+        }
+    }
+    Ok(())
+}
+
+/// Reads registers written by [`write_registers`]
+fn read_registers<R: Read>(cur: &mut PositionReader<R>) -> Result<Vec<Option<Constant>>, Error> {
+    let count = cur.read_u64::<BigEndian>()?;
+    let mut registers = Vec::new();
+    for _ in 0..count {
+        let register = match cur.read_u8()? {
+            0x00 => None,
+            0x01 => Some(read_constant(cur, pc::VER_MINOR)?),
+            flag => {
+                return Err(Error::other(format!("Invalid register flag {} at position {}", flag, cur.position() - 1)))
+            }
+        };
+        registers.push(register);
+    }
+    Ok(registers)
+}
+
+/// Reads an instruction index
+fn read_index<R: Read>(cur: &mut PositionReader<R>) -> Result<usize, Error> {
+    let index = cur.read_u64::<BigEndian>()?;
+    usize::try_from(index).map_err(|_| {
+        Error::other(format!("Instruction index {} at position {} is too large", index, cur.position() - 8))
+    })
+}
@@ -0,0 +1,565 @@
+/*!
+# Borrowed Bytecode API
+This module provides [`BytecodeView`], which loads bytecode without copying it out of the input
+buffer. Only the offsets of the constants are recorded when loading; constants and instructions are
+decoded when they are accessed, and strings can be borrowed straight from the buffer.
+
+The buffer can come from anywhere, including a memory-mapped file.
+
+# Examples
+```no_run
+use resurgence::bytecode::BytecodeView;
+
+let data = std::fs::read("path/to/bytecode.rvm").unwrap();
+let view = BytecodeView::new(&data).unwrap();
+for index in 0..view.constant_count() {
+    if let Some(string) = view.string_constant(index) {
+        println!("{}", string);
+    }
+}
+```
+*/
+
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+use std::result::Result;
+use std::sync::OnceLock;
+
+use super::codereader::{
+    check_instruction_minor, read_constant, read_header, read_instruction, read_metadata, read_u8_or_end,
+    too_deep, IntegrityState, PositionReader,
+};
+use super::integrity::{Crc32, ReadOptions};
+use super::parser_constants as pc;
+use crate::objects::codeholder::CodeHolder;
+use crate::objects::constant::Constant;
+use crate::objects::instruction::Instruction;
+use crate::objects::metadata::ModuleMetadata;
+
+/// Reads values from a byte slice, handing out borrowed slices instead of copies
+struct SliceCursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SliceCursor<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() - self.pos < len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("Bytecode ends before position {}", self.pos.saturating_add(len)),
+            ));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a length field and checks that the data can hold it
+    fn len(&mut self) -> Result<usize, Error> {
+        let len = self.u64()?;
+        if len > (self.data.len() - self.pos) as u64 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("Length {} at position {} exceeds the bytecode", len, self.pos - 8),
+            ));
+        }
+        Ok(len as usize)
+    }
+
+    fn str(&mut self) -> Result<&'a str, Error> {
+        let len = self.len()?;
+        let start = self.pos;
+        std::str::from_utf8(self.bytes(len)?).map_err(|error| {
+            Error::other(format!("Bad UTF-8 string at position {}: {}", start, error))
+        })
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    /// Fails if the rest of the data is too short for `count` elements, each of which takes at
+    /// least one byte
+    fn check_count(&self, count: u64, kind: &str, position: usize) -> Result<(), Error> {
+        if count > (self.data.len() - self.pos) as u64 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} constant at position {} has {} elements, more than the bytecode can hold",
+                    kind, position, count
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Checks a constant nested in `depth` vector and map constants and moves the cursor past it,
+/// without decoding it
+fn skip_constant(cur: &mut SliceCursor, vminor: u16, depth: u32) -> Result<(), Error> {
+    let ctype = cur.u8()?;
+    if matches!(ctype, pc::CONST_VEC | pc::CONST_MAP) && depth >= pc::MAX_CONSTANT_DEPTH {
+        return Err(too_deep(cur.pos as u64 - 1));
+    }
+    match ctype {
+        pc::CONST_INT | pc::CONST_DOUBLE => {
+            cur.bytes(8)?;
+        }
+        pc::CONST_STRING => {
+            cur.str()?;
+        }
+        pc::CONST_BOOLEAN => {
+            cur.u8()?;
+        }
+        pc::CONST_ADDRESS => {
+            cur.bytes(4)?;
+            let locval = cur.u8()?;
+            if !(pc::LOC_CONSTANT..=pc::LOC_LOCAL).contains(&locval) {
+                return Err(Error::other(format!("Invalid RegisterLocation value {} at position {}", locval, cur.pos - 1)));
+            }
+        }
+        pc::CONST_VEC => {
+            let position = cur.pos - 1;
+            let size = match vminor < pc::VER_MINOR_VEC_U64_LEN {
+                true => cur.u8()? as u64,
+                false => cur.u64()?,
+            };
+            cur.check_count(size, "Vector", position)?;
+            for _ in 0..size {
+                skip_constant(cur, vminor, depth + 1)?;
+            }
+        }
+        pc::CONST_MAP => {
+            if vminor < pc::VER_MINOR_MAPS {
+                return Err(Error::other(format!(
+                    "Map constant at position {} requires bytecode version {}.{}, found {}.{}",
+                    cur.pos - 1,
+                    pc::VER_MAJOR,
+                    pc::VER_MINOR_MAPS,
+                    pc::VER_MAJOR,
+                    vminor
+                )));
+            }
+            let position = cur.pos - 1;
+            let size = cur.u64()?;
+            cur.check_count(size, "Map", position)?;
+            for _ in 0..size {
+                let key_pos = cur.pos;
+                if !matches!(cur.data.get(key_pos), Some(&pc::CONST_INT) | Some(&pc::CONST_STRING)) {
+                    return Err(Error::other(format!("Invalid map key type at position {}", key_pos)));
+                }
+                skip_constant(cur, vminor, depth + 1)?;
+                skip_constant(cur, vminor, depth + 1)?;
+            }
+        }
+        _ => {
+            return Err(Error::other(format!("Unrecognized constant type {} at position {}", ctype, cur.pos - 1)));
+        }
+    }
+    Ok(())
+}
+
+/// Creates the error returned when a section does not match its length
+fn length_mismatch(tag: u8, section_pos: usize, length: usize) -> Error {
+    Error::other(format!(
+        "Section {} at position {} does not match its length of {} bytes",
+        tag, section_pos, length
+    ))
+}
+
+/// Decodes a constant that was checked by [`skip_constant`]
+fn decode_constant(data: &[u8], offset: usize, vminor: u16) -> Result<Constant, Error> {
+    let mut cur = PositionReader::with_length(&data[offset..], offset as u64, (data.len() - offset) as u64);
+    read_constant(&mut cur, vminor)
+}
+
+/// A read-only view of bytecode that borrows its contents from the input buffer.
+///
+/// Loading a view checks the structure of the header, the tables and all constants, but does not
+/// decode constants or instructions. Instructions are checked when they are decoded. Checksums that
+/// are present are validated over the borrowed buffer, like [`crate::bytecode::read_bytecode`].
+pub struct BytecodeView<'a> {
+    data: &'a [u8],
+    minor: u16,
+    /// Offset of each constant in `data`
+    constants: Vec<usize>,
+    imports: Vec<&'a str>,
+    exports: Vec<(&'a str, u64)>,
+    metadata: Option<ModuleMetadata>,
+    custom_sections: Vec<(&'a str, &'a [u8])>,
+    /// Range of `data` holding the instructions
+    code: Range<usize>,
+}
+
+impl<'a> BytecodeView<'a> {
+    /// Loads bytecode contained in `data`. Fails with an [`ErrorKind::Unsupported`] error if the
+    /// bytecode is compressed, as compressed bytecode can not be borrowed.
+    pub fn new(data: &'a [u8]) -> Result<BytecodeView<'a>, Error> {
+        Self::new_with_options(data, &ReadOptions::default())
+    }
+
+    /// Loads bytecode contained in `data` like [`BytecodeView::new`], failing with an
+    /// [`ErrorKind::InvalidData`] error if the integrity checks required by `options` fail.
+    pub fn new_with_options(data: &'a [u8], options: &ReadOptions) -> Result<BytecodeView<'a>, Error> {
+        let mut header = PositionReader::with_position(data, 0);
+        let (minor, flags) = read_header(&mut header)?;
+        if flags & pc::HEADER_FLAG_COMPRESSED != 0 {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "Compressed bytecode can not be viewed, use read_bytecode instead",
+            ));
+        }
+
+        let mut view = BytecodeView {
+            data,
+            minor,
+            constants: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            metadata: None,
+            custom_sections: Vec::new(),
+            code: data.len()..data.len(),
+        };
+        let mut cur = SliceCursor {
+            data,
+            pos: header.position() as usize,
+        };
+
+        if minor < pc::VER_MINOR_SECTIONS {
+            // older minor versions store the tables and instructions in a fixed order
+            view.scan_constants(&mut cur)?;
+            view.scan_imports(&mut cur)?;
+            view.scan_exports(&mut cur)?;
+            view.code = cur.pos..data.len();
+            // integrity sections did not exist yet
+            IntegrityState::default().check_required(options)?;
+            return Ok(view);
+        }
+
+        let mut integrity = IntegrityState::default();
+        while !cur.at_end() {
+            let section_pos = cur.pos;
+            let tag = cur.u8()?;
+            if integrity.has_signature || (integrity.has_checksum && tag != pc::SECTION_SIGNATURE) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Section {} at position {} follows an integrity section",
+                        tag, section_pos
+                    ),
+                ));
+            }
+            let flags = cur.u8()?;
+            let length = cur.len()?;
+            let start = cur.pos;
+            cur.bytes(length)?;
+
+            // limit the section to its length so it can not read into the next one
+            let mut section = SliceCursor {
+                data: &data[..start + length],
+                pos: start,
+            };
+            match tag {
+                pc::SECTION_CONSTANTS => view.scan_constants(&mut section)?,
+                pc::SECTION_IMPORTS => view.scan_imports(&mut section)?,
+                pc::SECTION_EXPORTS => view.scan_exports(&mut section)?,
+                pc::SECTION_CODE => view.code = start..start + length,
+                pc::SECTION_CUSTOM => {
+                    let name = section.str()?;
+                    view.custom_sections.push((name, &data[section.pos..start + length]));
+                }
+                pc::SECTION_METADATA => {
+                    let mut reader = PositionReader::with_position(&data[start..start + length], start as u64);
+                    view.metadata = Some(read_metadata(&mut reader)?);
+                    if reader.position() as usize != start + length {
+                        return Err(length_mismatch(tag, section_pos, length));
+                    }
+                }
+                // integrity sections cover everything before them
+                pc::SECTION_CHECKSUM => {
+                    let algorithm = section.u8()?;
+                    if algorithm != pc::CHECKSUM_CRC32 {
+                        return Err(Error::other(format!(
+                            "Unrecognized checksum algorithm {} at position {}",
+                            algorithm, section_pos
+                        )));
+                    }
+                    let expected = section.u32()?;
+                    let mut checksum = Crc32::new();
+                    checksum.update(&data[..section_pos]);
+                    if checksum.value() != expected {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "Bytecode checksum does not match, the file is corrupted",
+                        ));
+                    }
+                    integrity.has_checksum = true;
+                }
+                pc::SECTION_SIGNATURE => {
+                    let key_id = section.str()?;
+                    let signature = section.bytes(start + length - section.pos)?;
+                    if let Some(verifier) = options.verifier {
+                        if !verifier.verify(key_id, &data[..section_pos], signature) {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!("Bytecode signature by key \"{}\" is not valid", key_id),
+                            ));
+                        }
+                        integrity.verified = true;
+                    }
+                    integrity.has_signature = true;
+                }
+                _ => {
+                    if flags & pc::SECTION_FLAG_REQUIRED != 0 {
+                        return Err(Error::other(format!(
+                            "Unrecognized required section {} at position {}",
+                            tag, section_pos
+                        )));
+                    }
+                }
+            }
+
+            match tag {
+                pc::SECTION_CONSTANTS | pc::SECTION_IMPORTS | pc::SECTION_EXPORTS | pc::SECTION_CHECKSUM
+                    if !section.at_end() =>
+                {
+                    return Err(length_mismatch(tag, section_pos, length));
+                }
+                _ => (),
+            }
+        }
+
+        integrity.check_required(options)?;
+        Ok(view)
+    }
+
+    /// Records the offsets of the constants in the constants table
+    fn scan_constants(&mut self, cur: &mut SliceCursor<'a>) -> Result<(), Error> {
+        let clen = cur.u32()?;
+        for _ in 0..clen {
+            self.constants.push(cur.pos);
+            skip_constant(cur, self.minor, 0)?;
+        }
+        Ok(())
+    }
+
+    fn scan_imports(&mut self, cur: &mut SliceCursor<'a>) -> Result<(), Error> {
+        let ilen = cur.u64()?;
+        for _ in 0..ilen {
+            self.imports.push(cur.str()?);
+        }
+        Ok(())
+    }
+
+    fn scan_exports(&mut self, cur: &mut SliceCursor<'a>) -> Result<(), Error> {
+        let elen = cur.u64()?;
+        for _ in 0..elen {
+            let name = cur.str()?;
+            self.exports.push((name, cur.u64()?));
+        }
+        Ok(())
+    }
+
+    /// Returns the version of the bytecode as `(major, minor)`
+    pub fn version(&self) -> (u16, u16) {
+        (pc::VER_MAJOR, self.minor)
+    }
+
+    /// Returns the amount of constants in the constant pool
+    pub fn constant_count(&self) -> usize {
+        self.constants.len()
+    }
+
+    /// Decodes the constant at `index` in the constant pool
+    ///
+    /// `index` (`usize`): index of the constant
+    pub fn constant(&self, index: usize) -> Result<Constant, Error> {
+        match self.constants.get(index) {
+            Some(offset) => decode_constant(self.data, *offset, self.minor),
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Constant {} does not exist", index),
+            )),
+        }
+    }
+
+    /// Returns the constant at `index` without copying it, if it is a string
+    ///
+    /// `index` (`usize`): index of the constant
+    pub fn string_constant(&self, index: usize) -> Option<&'a str> {
+        let offset = *self.constants.get(index)?;
+        if self.data[offset] != pc::CONST_STRING {
+            return None;
+        }
+        let mut cur = SliceCursor {
+            data: self.data,
+            pos: offset + 1,
+        };
+        cur.str().ok()
+    }
+
+    /// Returns the names of the functions the code imports
+    pub fn imports(&self) -> &[&'a str] {
+        &self.imports
+    }
+
+    /// Returns the functions the code exports and the index of their first instruction
+    pub fn exports(&self) -> &[(&'a str, u64)] {
+        &self.exports
+    }
+
+    /// Returns the module metadata, if the bytecode provides it
+    pub fn metadata(&self) -> Option<&ModuleMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the contents of the custom section called `name`
+    ///
+    /// `name` (`&str`): name of the custom section
+    pub fn custom_section(&self, name: &str) -> Option<&'a [u8]> {
+        // like read_bytecode, the last section with a name wins
+        self.custom_sections
+            .iter()
+            .rev()
+            .find(|(section, _)| *section == name)
+            .map(|(_, data)| *data)
+    }
+
+    /// Returns an iterator that decodes the instructions one at a time
+    pub fn instructions(&self) -> Instructions<'a> {
+        Instructions {
+            cur: PositionReader::with_position(&self.data[self.code.clone()], self.code.start as u64),
+            minor: self.minor,
+            failed: false,
+        }
+    }
+
+    /// Decodes everything except the constant pool into a [`CodeHolder`]
+    fn code_holder_without_constants(&self) -> Result<CodeHolder, Error> {
+        let mut holder = CodeHolder::new();
+        for ins in self.instructions() {
+            holder.instructions.push(Some(ins?));
+        }
+        holder.imports = self.imports.iter().map(|name| name.to_string()).collect();
+        for (name, index) in &self.exports {
+            holder.exports.insert(name.to_string(), *index);
+        }
+        holder.metadata = self.metadata.clone();
+        holder.custom_sections = self
+            .custom_sections
+            .iter()
+            .map(|(name, data)| (name.to_string(), data.to_vec()))
+            .collect::<BTreeMap<_, _>>();
+        Ok(holder)
+    }
+
+    /// Decodes the whole bytecode into a [`CodeHolder`], like [`crate::bytecode::read_bytecode`]
+    pub fn to_code_holder(&self) -> Result<CodeHolder, Error> {
+        let mut holder = self.code_holder_without_constants()?;
+        for index in 0..self.constants.len() {
+            holder.constant_pool.push(self.constant(index)?);
+        }
+        Ok(holder)
+    }
+}
+
+/// Iterator over the instructions of a [`BytecodeView`], decoding each instruction when it is
+/// reached. Stops after returning an error.
+pub struct Instructions<'a> {
+    cur: PositionReader<&'a [u8]>,
+    minor: u16,
+    failed: bool,
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = Result<Instruction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            let position = self.cur.position();
+            let res = match read_u8_or_end(&mut self.cur) {
+                Ok(None) => return None,
+                Ok(Some(op)) => read_instruction(&mut self.cur, op, self.minor >= pc::VER_MINOR_COMPACT)
+                    .and_then(|ins| match ins {
+                        Some(ins) => check_instruction_minor(&ins, self.minor, position).map(|()| Some(ins)),
+                        None => Ok(None),
+                    }),
+                Err(err) => Err(err),
+            };
+            match res {
+                Ok(Some(ins)) => return Some(Ok(ins)),
+                Ok(None) => continue,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A constant pool that keeps the bytecode buffer and decodes each constant the first time it is
+/// used. Used by [`crate::Interpreter::from_bytes_with_options`].
+pub(crate) struct LazyConstantPool {
+    data: Box<dyn AsRef<[u8]> + Send + Sync>,
+    minor: u16,
+    offsets: Vec<usize>,
+    cache: Vec<OnceLock<Constant>>,
+}
+
+impl LazyConstantPool {
+    /// Loads bytecode held in `data`, decoding everything except the constant pool
+    pub(crate) fn load<B: AsRef<[u8]> + Send + Sync + 'static>(
+        data: B,
+        options: &ReadOptions,
+    ) -> Result<(CodeHolder, LazyConstantPool), Error> {
+        let view = BytecodeView::new_with_options(data.as_ref(), options)?;
+        let holder = view.code_holder_without_constants()?;
+        let minor = view.minor;
+        let offsets = view.constants;
+
+        let cache = (0..offsets.len()).map(|_| OnceLock::new()).collect();
+        let pool = LazyConstantPool {
+            data: Box::new(data),
+            minor,
+            offsets,
+            cache,
+        };
+        Ok((holder, pool))
+    }
+
+    /// Returns the number of constants in the pool
+    pub(crate) fn count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns the constant at `index`, decoding it if this is the first use, or `None` if there
+    /// is no such constant. Fails if the constant can not be decoded.
+    pub(crate) fn get(&self, index: usize) -> Result<Option<&Constant>, Error> {
+        let cell = match self.cache.get(index) {
+            Some(cell) => cell,
+            None => return Ok(None),
+        };
+        if cell.get().is_none() {
+            // another thread may decode the same constant at the same time, either result is kept
+            let constant = decode_constant((*self.data).as_ref(), self.offsets[index], self.minor)?;
+            let _ = cell.set(constant);
+        }
+        Ok(cell.get())
+    }
+}
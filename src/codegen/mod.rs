@@ -81,6 +81,38 @@ pub fn generate_jump(holder: &mut CodeHolder, instructions: i64) {
     holder.instructions.push(Some(Instruction::Jump(instructions)));
 }
 
+/// Generates a JumpTo instruction
+///
+/// loc (`u64`): The instruction to jump to (based on by index)
+pub fn generate_jump_to(holder: &mut CodeHolder, loc: u64) {
+    holder.instructions.push(Some(Instruction::JumpTo(loc)));
+}
+
+/// Generates a JumpIfTrue instruction
+///
+/// register (`RVMRegister`): The boolean register to check
+/// loc (`u64`): The instruction to jump to if the register holds true
+pub fn generate_jump_if_true(holder: &mut CodeHolder, register: RVMRegister, loc: u64) {
+    holder.instructions.push(Some(Instruction::JumpIfTrue(real_register(register), loc)));
+}
+
+/// Generates a JumpIfFalse instruction
+///
+/// register (`RVMRegister`): The boolean register to check
+/// loc (`u64`): The instruction to jump to if the register holds false
+pub fn generate_jump_if_false(holder: &mut CodeHolder, register: RVMRegister, loc: u64) {
+    holder.instructions.push(Some(Instruction::JumpIfFalse(real_register(register), loc)));
+}
+
+/// Generates a JumpTable instruction
+///
+/// register (`RVMRegister`): The integer register used to index into the table
+/// default (`u64`): The instruction to jump to if the index is outside of the table
+/// table (`Vec<u64>`): The instructions to jump to
+pub fn generate_jump_table(holder: &mut CodeHolder, register: RVMRegister, default: u64, table: Vec<u64>) {
+    holder.instructions.push(Some(Instruction::JumpTable(real_register(register), default, table)));
+}
+
 /// Generates a call instruction
 ///
 /// loc (`u64`): The instruction to jump to (based on by index)
@@ -251,6 +283,180 @@ pub fn generate_less_equal(holder: &mut CodeHolder, register_1: RVMRegister, reg
     holder.instructions.push(Some(Instruction::LessEqual(real_register(register_1), real_register(register_2))));
 }
 
+/// Generates a ToInt instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register to convert to an integer
+pub fn generate_to_int(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::ToInt(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a ToDouble instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register to convert to a double
+pub fn generate_to_double(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::ToDouble(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a ToString instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register to convert to a string
+pub fn generate_to_string(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::ToString(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a ToBool instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register to convert to a boolean
+pub fn generate_to_bool(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::ToBool(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a TypeOf instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register to get the type tag of
+pub fn generate_type_of(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::TypeOf(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a StrLen instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the string
+pub fn generate_str_len(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::StrLen(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a Substr instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the string
+/// register_3 (`RVMRegister`): the register holding the index of the first character
+/// register_4 (`RVMRegister`): the register holding the amount of characters
+pub fn generate_substr(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister, register_4: RVMRegister) {
+    holder.instructions.push(Some(Instruction::Substr(real_register(register_1), real_register(register_2), real_register(register_3), real_register(register_4))));
+}
+
+/// Generates a CharAt instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the string
+/// register_3 (`RVMRegister`): the register holding the index of the character
+pub fn generate_char_at(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::CharAt(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a VecNew instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+pub fn generate_vec_new(holder: &mut CodeHolder, register_1: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecNew(real_register(register_1))));
+}
+
+/// Generates a VecGet instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the vector
+/// register_3 (`RVMRegister`): the register holding the index of the element
+pub fn generate_vec_get(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecGet(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a VecSet instruction
+///
+/// register_1 (`RVMRegister`): the register holding the vector
+/// register_2 (`RVMRegister`): the register holding the index of the element
+/// register_3 (`RVMRegister`): the register holding the new element
+pub fn generate_vec_set(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecSet(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a VecPush instruction
+///
+/// register_1 (`RVMRegister`): the register holding the vector
+/// register_2 (`RVMRegister`): the register holding the new element
+pub fn generate_vec_push(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecPush(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a VecPop instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the vector
+pub fn generate_vec_pop(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecPop(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a VecLen instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the vector
+pub fn generate_vec_len(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::VecLen(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a MapNew instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+pub fn generate_map_new(holder: &mut CodeHolder, register_1: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapNew(real_register(register_1))));
+}
+
+/// Generates a MapGet instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the map
+/// register_3 (`RVMRegister`): the register holding the key
+pub fn generate_map_get(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapGet(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a MapSet instruction
+///
+/// register_1 (`RVMRegister`): the register holding the map
+/// register_2 (`RVMRegister`): the register holding the key
+/// register_3 (`RVMRegister`): the register holding the value
+pub fn generate_map_set(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapSet(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a MapRemove instruction
+///
+/// register_1 (`RVMRegister`): the register holding the map
+/// register_2 (`RVMRegister`): the register holding the key
+pub fn generate_map_remove(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapRemove(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a MapContains instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the map
+/// register_3 (`RVMRegister`): the register holding the key
+pub fn generate_map_contains(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister, register_3: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapContains(real_register(register_1), real_register(register_2), real_register(register_3))));
+}
+
+/// Generates a MapLen instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the map
+pub fn generate_map_len(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapLen(real_register(register_1), real_register(register_2))));
+}
+
+/// Generates a MapKeys instruction
+///
+/// register_1 (`RVMRegister`): the destination register
+/// register_2 (`RVMRegister`): the register holding the map
+pub fn generate_map_keys(holder: &mut CodeHolder, register_1: RVMRegister, register_2: RVMRegister) {
+    holder.instructions.push(Some(Instruction::MapKeys(real_register(register_1), real_register(register_2))));
+}
+
 /// Returns the index of the last object in a vector as a u32
 ///
 /// $vec: the vector in question
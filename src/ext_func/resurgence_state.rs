@@ -1,4 +1,5 @@
-use crate::objects::constant::Constant;
+use crate::objects::constant::{Constant, MapKey};
+use std::collections::BTreeMap;
 use std::io::{Error, ErrorKind};
 pub struct ResurgenceState<'a> {
     args: &'a mut Vec<Constant>,
@@ -10,6 +11,14 @@ impl ResurgenceState<'_> {
             args,
         }
     }
+
+    /// Pops the top of the stack, or returns an error if the stack is empty
+    fn pop_arg(&mut self) -> Result<Constant, Error> {
+        match self.args.pop() {
+            Some(constant) => Ok(constant),
+            None => Err(Error::other(String::from("Stack empty, missing argument"))),
+        }
+    }
 }
 
 impl ResurgenceState<'_> {
@@ -20,7 +29,7 @@ impl ResurgenceState<'_> {
     /// let int_val = state.get_i64();
     /// ```
     pub fn get_i64(&mut self) -> Result<i64, Error> { 
-        if let Constant::Int(res) = self.args.pop().unwrap() {
+        if let Constant::Int(res) = self.pop_arg()? {
             return Result::Ok(res);
         }
         Err(Error::new(ErrorKind::Other, String::from("Invalid type, expected i64")))
@@ -32,7 +41,7 @@ impl ResurgenceState<'_> {
     /// let f64_val = state.get_f64();
     /// ```
     pub fn get_f64(&mut self) -> Result<f64, Error> {
-        if let Constant::Double(res) = self.args.pop().unwrap() {
+        if let Constant::Double(res) = self.pop_arg()? {
             return Result::Ok(res);
         }
         Err(Error::new(ErrorKind::Other, String::from("Invalid type, expected f64")))
@@ -44,7 +53,7 @@ impl ResurgenceState<'_> {
     /// let string_val = state.get_string();
     /// ```
     pub fn get_string(&mut self) -> Result<String, Error> {
-        if let Constant::String(res) = self.args.pop().unwrap() {
+        if let Constant::String(res) = self.pop_arg()? {
             return Result::Ok((*res).to_string());
         }
         Err(Error::new(ErrorKind::Other, String::from("Invalid type, expected String")))
@@ -56,19 +65,43 @@ impl ResurgenceState<'_> {
     /// let bool_val = state.get_bool();
     /// ```
     pub fn get_bool(&mut self) -> Result<bool, Error> {
-        if let Constant::Boolean(res) = self.args.pop().unwrap() {
+        if let Constant::Boolean(res) = self.pop_arg()? {
             return Result::Ok(res);
         }
         Err(Error::new(ErrorKind::Other, String::from("Invalid type, expected bool")))
     }
 
+    /// Returns an `Result<Vec<Constant>>` from the top of the stack
+    ///
+    /// ```
+    /// let vec_val = state.get_vec();
+    /// ```
+    pub fn get_vec(&mut self) -> Result<Vec<Constant>, Error> {
+        if let Constant::Vec(res) = self.pop_arg()? {
+            return Result::Ok(res);
+        }
+        Err(Error::other(String::from("Invalid type, expected Vec")))
+    }
+
+    /// Returns an `Result<BTreeMap<MapKey, Constant>>` from the top of the stack
+    ///
+    /// ```
+    /// let map_val = state.get_map();
+    /// ```
+    pub fn get_map(&mut self) -> Result<BTreeMap<MapKey, Constant>, Error> {
+        if let Constant::Map(res) = self.pop_arg()? {
+            return Result::Ok(res);
+        }
+        Err(Error::other(String::from("Invalid type, expected Map")))
+    }
+
     /// Returns the topmost constant as an `Result<String>`
     ///
     /// ```
     /// let val = state.get_value_as_string();
     /// ```
     pub fn get_value_as_string(&mut self) -> Result<String, Error> {
-        let constant = self.args.pop().unwrap();
+        let constant = self.pop_arg()?;
         match constant {
             Constant::Int(ref val) => Result::Ok(val.to_string()),
             Constant::Double(ref val) => Result::Ok(val.to_string()),
@@ -125,4 +158,28 @@ impl ResurgenceState<'_> {
     pub fn push_bool(&mut self, val: bool) {
         self.args.push(Constant::Boolean(val));
     }
+
+    /// Pushes a vector on the stack
+    ///
+    ///
+    /// val (`Vec<Constant>`): The value to be pushed on the stack
+    ///
+    /// ```
+    /// state.push_vec(vec![Constant::Int(1), Constant::Int(2)]);
+    /// ```
+    pub fn push_vec(&mut self, val: Vec<Constant>) {
+        self.args.push(Constant::Vec(val));
+    }
+
+    /// Pushes a map on the stack
+    ///
+    ///
+    /// val (`BTreeMap<MapKey, Constant>`): The value to be pushed on the stack
+    ///
+    /// ```
+    /// state.push_map(BTreeMap::from([(MapKey::String("hp".to_string()), Constant::Int(100))]));
+    /// ```
+    pub fn push_map(&mut self, val: BTreeMap<MapKey, Constant>) {
+        self.args.push(Constant::Map(val));
+    }
 }
@@ -1,11 +1,12 @@
 use crate::ext_func::resurgence_state::ResurgenceState;
 use crate::internal::execution_engine::ExecutionEngine;
 use crate::internal::interpreter::Interpreter;
-use crate::CodeHolder;
+use crate::{CodeHolder, InterruptHandle, SemanticVersion};
 use std::boxed::Box;
 use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::str;
+use std::time::{Duration, Instant};
 
 /// Creates an instance of an Interpreter. If successful, returns a pointer to an Interpreter
 /// instance. If this fails, it returns a null pointer. Consumes a CodeHolder.
@@ -49,11 +50,163 @@ pub extern "C" fn rvm_interpreter_register_function(
         }
     };
 
-    interpreter.register_native_function(cbfunc, String::from(name_slice));
+    interpreter.register_native_function(cbfunc, String::from(name_slice), None);
 
     return 0;
 }
 
+/// Registers a function that belongs to a capability. Code may only import it once the capability
+/// has been granted. If this succeeds, returns 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter, and `name_char` and `capability_char` must
+/// be null or point to nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_register_function_with_capability(
+    inter: *mut Interpreter,
+    callback: Option<extern "C" fn(&mut ResurgenceState) -> u8>,
+    name_char: *const c_char,
+    capability_char: *const c_char,
+) -> u8 {
+    // Make sure parameters are non-null
+    if inter.is_null() || name_char.is_null() || capability_char.is_null() {
+        return 1;
+    }
+
+    let interpreter = unsafe { &mut *inter };
+
+    let cbfunc = match callback {
+        Some(f) => f,
+        None => return 1,
+    };
+
+    let name_str: &CStr = unsafe { CStr::from_ptr(name_char) };
+    let capability_str: &CStr = unsafe { CStr::from_ptr(capability_char) };
+    let (name_slice, capability_slice) = match (name_str.to_str(), capability_str.to_str()) {
+        (Ok(name), Ok(capability)) => (name, capability),
+        _ => return 1,
+    };
+
+    interpreter.register_native_function(cbfunc, String::from(name_slice), Some(String::from(capability_slice)));
+    0
+}
+
+/// Allows the code to import the functions belonging to a capability. If this succeeds, returns 0;
+/// If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter, and `capability_char` must be null or point
+/// to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_grant_capability(
+    inter: *mut Interpreter,
+    capability_char: *const c_char,
+) -> u8 {
+    if inter.is_null() || capability_char.is_null() {
+        return 1;
+    }
+    let interpreter = unsafe { &mut *inter };
+
+    let capability_str: &CStr = unsafe { CStr::from_ptr(capability_char) };
+    match capability_str.to_str() {
+        Ok(capability) => interpreter.grant_capability(String::from(capability)),
+        Err(_) => return 1,
+    }
+    0
+}
+
+/// Sets the version of the API the host provides. Once set, resolving imports fails for modules
+/// whose metadata requires an incompatible host API. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_set_host_api_version(
+    inter: *mut Interpreter,
+    major: u64,
+    minor: u64,
+    patch: u64,
+) -> u8 {
+    if inter.is_null() {
+        return 1;
+    }
+    let interpreter = unsafe { &mut *inter };
+
+    interpreter.set_host_api_version(SemanticVersion::new(major, minor, patch));
+    0
+}
+
+/// Creates a handle that can stop the code running in the Interpreter from another thread. If
+/// successful, returns a pointer to the handle. If this fails, it returns a null pointer.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_interrupt_handle(inter: *mut Interpreter) -> *mut InterruptHandle {
+    if inter.is_null() {
+        return std::ptr::null_mut();
+    }
+    let interpreter = unsafe { &*inter };
+
+    Box::into_raw(Box::new(interpreter.interrupt_handle()))
+}
+
+/// Asks the Interpreter the handle belongs to to stop. May be called from any thread. If this
+/// succeeds, returns 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `handle` must be null or point to a handle that has not been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interrupt_handle_interrupt(handle: *const InterruptHandle) -> u8 {
+    if handle.is_null() {
+        return 1;
+    }
+    let handle = unsafe { &*handle };
+
+    handle.interrupt();
+    0
+}
+
+/// Destroys an interrupt handle. The Interpreter it belongs to is not affected.
+///
+/// # Safety
+///
+/// `handle` must be null or point to a handle that has not been destroyed, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interrupt_handle_destroy(handle: *mut InterruptHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Stops execution once `milliseconds` have passed from now. A timeout of 0 removes the deadline.
+/// If this succeeds, returns 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_set_timeout(inter: *mut Interpreter, milliseconds: u64) -> u8 {
+    if inter.is_null() {
+        return 1;
+    }
+    let interpreter = unsafe { &mut *inter };
+
+    let deadline = match milliseconds {
+        0 => None,
+        _ => Instant::now().checked_add(Duration::from_millis(milliseconds)),
+    };
+    interpreter.set_deadline(deadline);
+    0
+}
+
 /// Attempts to resolve all imports requested by the CodeHolder. If this succeeds, returns 0; If
 /// this fails, it returns 1.
 #[no_mangle]
@@ -95,6 +248,23 @@ pub extern "C" fn rvm_interpreter_execute_function(
     }
 }
 
+/// Clears the registers, stack frames and stack so the code can run again, keeping the code and
+/// registered functions. If this succeeds, returns 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `inter` must be null or point to a live Interpreter.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_interpreter_reset(inter: *mut Interpreter) -> u8 {
+    if inter.is_null() {
+        return 1;
+    }
+    let interpreter = unsafe { &mut *inter };
+
+    interpreter.reset();
+    0
+}
+
 /// Destroys an Interpreter instance
 #[no_mangle]
 pub unsafe extern "C" fn rvm_interpreter_destroy(inter: *mut Interpreter) {
@@ -0,0 +1,591 @@
+use crate::ext_func::resurgence_state::ResurgenceState;
+use crate::objects::constant::{Constant, MapKey};
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+/// The map type handed to C code as an opaque `RVMMap` pointer
+pub type RVMMap = BTreeMap<MapKey, Constant>;
+
+/// Converts a C string into a string map key
+unsafe fn string_key(key_char: *const c_char) -> Option<MapKey> {
+    if key_char.is_null() {
+        return None;
+    }
+
+    let key_str: &CStr = unsafe { CStr::from_ptr(key_char) };
+    match key_str.to_str() {
+        Ok(v) => Some(MapKey::String(String::from(v))),
+        Err(_) => None,
+    }
+}
+
+/// Stores a value under a key, returning 0 if successful and 1 if not
+unsafe fn map_set(map: *mut RVMMap, key: Option<MapKey>, value: Constant) -> u8 {
+    if map.is_null() {
+        return 1;
+    }
+    let map = unsafe { &mut *map };
+
+    match key {
+        Some(k) => {
+            map.insert(k, value);
+            0
+        }
+        None => 1,
+    }
+}
+
+/// Returns a reference to the value stored under a key
+unsafe fn map_get<'a>(map: *mut RVMMap, key: Option<MapKey>) -> Option<&'a Constant> {
+    if map.is_null() {
+        return None;
+    }
+    let map = unsafe { &*map };
+
+    match key {
+        Some(k) => map.get(&k),
+        None => None,
+    }
+}
+
+unsafe fn map_get_integer(map: *mut RVMMap, key: Option<MapKey>, out_value: *mut i64) -> u8 {
+    if out_value.is_null() {
+        return 1;
+    }
+
+    match map_get(map, key) {
+        Some(Constant::Int(v)) => {
+            unsafe { *out_value = *v };
+            0
+        }
+        _ => 1,
+    }
+}
+
+unsafe fn map_get_float(map: *mut RVMMap, key: Option<MapKey>, out_value: *mut f64) -> u8 {
+    if out_value.is_null() {
+        return 1;
+    }
+
+    match map_get(map, key) {
+        Some(Constant::Double(v)) => {
+            unsafe { *out_value = *v };
+            0
+        }
+        _ => 1,
+    }
+}
+
+unsafe fn map_get_string(map: *mut RVMMap, key: Option<MapKey>, out_value: *mut *mut c_char) -> u8 {
+    if out_value.is_null() {
+        return 1;
+    }
+
+    match map_get(map, key) {
+        Some(Constant::String(v)) => {
+            let c_string = match CString::new(v.clone()) {
+                Ok(x) => x,
+                Err(_) => return 1,
+            };
+            unsafe {
+                *out_value = c_string.into_raw();
+            }
+            0
+        }
+        _ => 1,
+    }
+}
+
+unsafe fn map_get_bool(map: *mut RVMMap, key: Option<MapKey>, out_value: *mut u8) -> u8 {
+    if out_value.is_null() {
+        return 1;
+    }
+
+    match map_get(map, key) {
+        Some(Constant::Boolean(v)) => {
+            unsafe {
+                *out_value = match v {
+                    true => 1,
+                    false => 0,
+                }
+            };
+            0
+        }
+        _ => 1,
+    }
+}
+
+unsafe fn map_contains(map: *mut RVMMap, key: Option<MapKey>, out_value: *mut u8) -> u8 {
+    if map.is_null() || out_value.is_null() {
+        return 1;
+    }
+    let map = unsafe { &*map };
+
+    match key {
+        Some(k) => {
+            unsafe {
+                *out_value = match map.contains_key(&k) {
+                    true => 1,
+                    false => 0,
+                }
+            };
+            0
+        }
+        None => 1,
+    }
+}
+
+unsafe fn map_remove(map: *mut RVMMap, key: Option<MapKey>) -> u8 {
+    if map.is_null() {
+        return 1;
+    }
+    let map = unsafe { &mut *map };
+
+    match key {
+        Some(k) => {
+            map.remove(&k);
+            0
+        }
+        None => 1,
+    }
+}
+
+/// Creates an empty Map instance.
+#[no_mangle]
+pub extern "C" fn rvm_map_new() -> *mut RVMMap {
+    Box::into_raw(Box::new(RVMMap::new()))
+}
+
+/// Destroys a Map instance
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_destroy(map: *mut RVMMap) {
+    if !map.is_null() {
+        let map = Box::from_raw(map);
+        drop(map);
+    }
+}
+
+/// Retrieves the amount of entries in a Map.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_len(map: *mut RVMMap, out_value: *mut u64) -> u8 {
+    if map.is_null() || out_value.is_null() {
+        return 1;
+    }
+    let map = unsafe { &*map };
+
+    unsafe { *out_value = map.len() as u64 };
+    0
+}
+
+/// Returns the key at a position of a Map. Maps keep their integer keys in ascending order,
+/// followed by their string keys in ascending order.
+unsafe fn map_key_at<'a>(map: *mut RVMMap, index: u64) -> Option<&'a MapKey> {
+    if map.is_null() {
+        return None;
+    }
+    let map = unsafe { &*map };
+
+    map.keys().nth(usize::try_from(index).ok()?)
+}
+
+/// Tells whether the key at a position of a Map is an integer key, so C code can list the keys
+/// of a Map it did not build
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_key_is_integer(map: *mut RVMMap, index: u64, out_value: *mut u8) -> u8 {
+    if out_value.is_null() {
+        return 1;
+    }
+
+    match map_key_at(map, index) {
+        Some(k) => {
+            unsafe {
+                *out_value = match k {
+                    MapKey::Int(_) => 1,
+                    MapKey::String(_) => 0,
+                }
+            };
+            0
+        }
+        None => 1,
+    }
+}
+
+/// Retrieves the integer key at a position of a Map
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_key` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_key_integer(map: *mut RVMMap, index: u64, out_key: *mut i64) -> u8 {
+    if out_key.is_null() {
+        return 1;
+    }
+
+    match map_key_at(map, index) {
+        Some(MapKey::Int(k)) => {
+            unsafe { *out_key = *k };
+            0
+        }
+        _ => 1,
+    }
+}
+
+/// Retrieves the string key at a position of a Map. The string output by this function is owned
+/// by the caller and must be freed with `rvm_string_free`.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_key` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_key_string(map: *mut RVMMap, index: u64, out_key: *mut *mut c_char) -> u8 {
+    if out_key.is_null() {
+        return 1;
+    }
+
+    match map_key_at(map, index) {
+        Some(MapKey::String(k)) => {
+            let c_string = match CString::new(k.clone()) {
+                Ok(x) => x,
+                Err(_) => return 1,
+            };
+            unsafe {
+                *out_key = c_string.into_raw();
+            }
+            0
+        }
+        _ => 1,
+    }
+}
+
+/// Stores an integer under a string key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `key` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_integer(map: *mut RVMMap, key: *const c_char, value: i64) -> u8 {
+    map_set(map, string_key(key), Constant::Int(value))
+}
+
+/// Stores a float under a string key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `key` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_float(map: *mut RVMMap, key: *const c_char, value: f64) -> u8 {
+    map_set(map, string_key(key), Constant::Double(value))
+}
+
+/// Stores a copy of a string under a string key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `value` must be null or point to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_string(
+    map: *mut RVMMap,
+    key: *const c_char,
+    value: *const c_char,
+) -> u8 {
+    if value.is_null() {
+        return 1;
+    }
+
+    let v_str: &CStr = unsafe { CStr::from_ptr(value) };
+    let v_slice: &str = match v_str.to_str() {
+        Ok(v) => v,
+        Err(_) => {
+            return 1;
+        }
+    };
+
+    map_set(map, string_key(key), Constant::String(v_slice.to_owned()))
+}
+
+/// Stores a boolean under a string key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `key` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_bool(map: *mut RVMMap, key: *const c_char, value: u8) -> u8 {
+    map_set(map, string_key(key), Constant::Boolean(value != 0))
+}
+
+/// Stores an integer under an integer key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_integer_at(map: *mut RVMMap, key: i64, value: i64) -> u8 {
+    map_set(map, Some(MapKey::Int(key)), Constant::Int(value))
+}
+
+/// Stores a float under an integer key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_float_at(map: *mut RVMMap, key: i64, value: f64) -> u8 {
+    map_set(map, Some(MapKey::Int(key)), Constant::Double(value))
+}
+
+/// Stores a copy of a string under an integer key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `value` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_string_at(map: *mut RVMMap, key: i64, value: *const c_char) -> u8 {
+    if value.is_null() {
+        return 1;
+    }
+
+    let v_str: &CStr = unsafe { CStr::from_ptr(value) };
+    let v_slice: &str = match v_str.to_str() {
+        Ok(v) => v,
+        Err(_) => {
+            return 1;
+        }
+    };
+
+    map_set(map, Some(MapKey::Int(key)), Constant::String(v_slice.to_owned()))
+}
+
+/// Stores a boolean under an integer key of a Map. If this succeeds, returns 0; If this fails, it
+/// returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_set_bool_at(map: *mut RVMMap, key: i64, value: u8) -> u8 {
+    map_set(map, Some(MapKey::Int(key)), Constant::Boolean(value != 0))
+}
+
+/// Retrieves the integer stored under a string key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_integer(
+    map: *mut RVMMap,
+    key: *const c_char,
+    out_value: *mut i64,
+) -> u8 {
+    map_get_integer(map, string_key(key), out_value)
+}
+
+/// Retrieves the float stored under a string key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_float(
+    map: *mut RVMMap,
+    key: *const c_char,
+    out_value: *mut f64,
+) -> u8 {
+    map_get_float(map, string_key(key), out_value)
+}
+
+/// Retrieves the string stored under a string key of a Map. The string output by this function is
+/// owned by the caller and must be freed with `rvm_string_free`. If this succeeds, returns 0; If
+/// this fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_string(
+    map: *mut RVMMap,
+    key: *const c_char,
+    out_value: *mut *mut c_char,
+) -> u8 {
+    map_get_string(map, string_key(key), out_value)
+}
+
+/// Retrieves the boolean stored under a string key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_bool(map: *mut RVMMap, key: *const c_char, out_value: *mut u8) -> u8 {
+    map_get_bool(map, string_key(key), out_value)
+}
+
+/// Retrieves the integer stored under an integer key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_integer_at(map: *mut RVMMap, key: i64, out_value: *mut i64) -> u8 {
+    map_get_integer(map, Some(MapKey::Int(key)), out_value)
+}
+
+/// Retrieves the float stored under an integer key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_float_at(map: *mut RVMMap, key: i64, out_value: *mut f64) -> u8 {
+    map_get_float(map, Some(MapKey::Int(key)), out_value)
+}
+
+/// Retrieves the string stored under an integer key of a Map. The string output by this function is
+/// owned by the caller and must be freed with `rvm_string_free`. If this succeeds, returns 0; If
+/// this fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_string_at(
+    map: *mut RVMMap,
+    key: i64,
+    out_value: *mut *mut c_char,
+) -> u8 {
+    map_get_string(map, Some(MapKey::Int(key)), out_value)
+}
+
+/// Retrieves the boolean stored under an integer key of a Map. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_get_bool_at(map: *mut RVMMap, key: i64, out_value: *mut u8) -> u8 {
+    map_get_bool(map, Some(MapKey::Int(key)), out_value)
+}
+
+/// Tells whether a Map has a value under a string key. If this succeeds, returns 0; If this fails,
+/// it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, `key` must be null or point to a nul-terminated
+/// string, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_contains(map: *mut RVMMap, key: *const c_char, out_value: *mut u8) -> u8 {
+    map_contains(map, string_key(key), out_value)
+}
+
+/// Tells whether a Map has a value under an integer key. If this succeeds, returns 0; If this
+/// fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `out_value` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_contains_at(map: *mut RVMMap, key: i64, out_value: *mut u8) -> u8 {
+    map_contains(map, Some(MapKey::Int(key)), out_value)
+}
+
+/// Removes the value stored under a string key of a Map, if there is one. If this succeeds, returns
+/// 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map, and `key` must be null or point to a nul-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_remove(map: *mut RVMMap, key: *const c_char) -> u8 {
+    map_remove(map, string_key(key))
+}
+
+/// Removes the value stored under an integer key of a Map, if there is one. If this succeeds,
+/// returns 0; If this fails, it returns 1.
+///
+/// # Safety
+///
+/// `map` must be null or point to a live Map.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_map_remove_at(map: *mut RVMMap, key: i64) -> u8 {
+    map_remove(map, Some(MapKey::Int(key)))
+}
+
+/// Retrieves a Map from an RVMState. The Map output by this function is owned by the caller and
+/// must be freed with `rvm_map_destroy`.
+///
+/// # Safety
+///
+/// `state` must be null or point to the RVMState passed to the current function, and `out_value`
+/// must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_state_get_map(state: *mut ResurgenceState, out_value: *mut *mut RVMMap) -> u8 {
+    if state.is_null() || out_value.is_null() {
+        return 1;
+    }
+
+    let state = unsafe { &mut *state };
+
+    match state.get_map() {
+        Ok(v) => {
+            unsafe { *out_value = Box::into_raw(Box::new(v)) };
+            0
+        }
+        Err(_) => 1,
+    }
+}
+
+/// Pushes a Map on the stack of an RVMState. Consumes the Map.
+///
+/// # Safety
+///
+/// `state` must be null or point to the RVMState passed to the current function, and `map` must be
+/// null or point to a live Map, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rvm_state_push_map(state: *mut ResurgenceState, map: *mut RVMMap) -> u8 {
+    if state.is_null() || map.is_null() {
+        return 1;
+    }
+
+    let state = unsafe { &mut *state };
+    let map = unsafe { *(Box::from_raw(map)) };
+    state.push_map(map);
+    0
+}
@@ -13,6 +13,9 @@ pub use codeholder::*;
 mod state;
 pub use state::*;
 
+mod map;
+pub use map::*;
+
 use std::ffi::CString;
 use std::os::raw::c_char;
 
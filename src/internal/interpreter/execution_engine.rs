@@ -1,6 +1,8 @@
-use super::super::{execution_engine::ExecutionEngine, interpreter::Interpreter};
+use std::sync::Arc;
+
+use super::super::{execution_engine::ExecutionEngine, interpreter::{Interpreter, INTERRUPT_CHECK_INTERVAL}};
 use crate::{objects::{
-    instruction::Instruction, stackframe::StackFrame, resurgence_error::{ResurgenceError, ResurgenceErrorKind, ResurgenceContext}
+    instruction::Instruction, resurgence_error::{ResurgenceError, ResurgenceErrorKind, ResurgenceContext, ContextState}
 }, create_new_trace};
 
 /// Creates a `ResurgenceContext` object
@@ -14,21 +16,35 @@ macro_rules! create_context {
     ($self:expr, $ins:expr, $ip:expr) =>
     {
         ResurgenceContext {
-            call_stack: $self.call_stack.clone(), 
-            constant_stack: $self.stack.clone(),
-            rust_and_native_fns: $self.rust_functions.clone(),
+            state: Box::new(ContextState {
+                call_stack: $self.call_stack.clone(), 
+                constant_stack: $self.stack.clone(),
+                rust_and_native_fns: $self.rust_functions.clone(),
+                recursion_depth: $self.current_recursion_depth,
+            }),
             instruction: $ins,
             instruction_pointer: $ip,
-            recursion_depth: $self.current_recursion_depth,
         }
     }
 }
 
+/// Where execution stopped when the code ran out of fuel or was interrupted, so it can be resumed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Suspension {
+    /// Index of the next instruction to execute
+    pub(crate) index: usize,
+    /// Return addresses of the calls that were running
+    pub(crate) returns: Vec<usize>,
+}
+
 impl ExecutionEngine for Interpreter {
     /// Execute Resurgence Instructions
+    ///
+    /// The code is never modified, so it can be run again after execution fails, after
+    /// [`Interpreter::reset`] if the state left behind is not wanted
     fn execute_instruction(&mut self, start_index: usize) -> Result<(), ResurgenceError> {
-        // Resolve imports if the programmer already hasn't done so 
-        if !self.code_holder.resolved_imports {
+        // Resolve imports if the programmer already hasn't done so, which verifies the code as well
+        if !self.verified || !self.resolved_imports {
             let res = self.resolve_imports();
             if let Err(mut err) = res {
                 // This will always occur in the first call
@@ -37,108 +53,283 @@ impl ExecutionEngine for Interpreter {
                 return Err(err);
             }
         }
+
+        self.suspended = None;
+        let res = self.run_from(start_index, Vec::new());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    // Execute an exported function.
+    fn execute_function(&mut self, func_name: &str) -> Result<(), ResurgenceError> {
+        match self.code_holder.exports.get(func_name) {
+            // This call is the first call of the instace
+            Some(inst) => self.execute_instruction(*inst as usize),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::FUNCTION_DOES_NOT_EXIST, 
+                &format!("Function {} does not exist!", func_name));
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Interpreter {
+    /// Continues executing code that stopped because it ran out of fuel or was interrupted, from
+    /// the instruction it stopped at. Give the interpreter more fuel or remove its deadline first,
+    /// or it stops again right away.
+    pub fn resume(&mut self) -> Result<(), ResurgenceError> {
+        let suspension = match self.suspended.take() {
+            Some(suspension) => suspension,
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "There is no stopped code to resume!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        if !self.verified || !self.resolved_imports {
+            if let Err(mut err) = self.resolve_imports() {
+                self.suspended = Some(suspension);
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+
+        let res = self.run_from(suspension.index, suspension.returns);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the code ran out of fuel or was interrupted, and can be continued with
+    /// [`Interpreter::resume`]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
+
+    /// Runs [`Interpreter::execute_from`] with the calls in `returns` already running, and adds
+    /// the calls that were still running to the context of any error
+    fn run_from(&mut self, start_index: usize, mut returns: Vec<usize>) -> Result<(), ResurgenceError> {
+        let depth = self.current_recursion_depth;
+        self.current_recursion_depth += returns.len();
+        let res = self.execute_from(start_index, &mut returns);
+        self.current_recursion_depth = depth;
+        if let Err(mut err) = res {
+            // Add the calls that were still running, innermost first
+            let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+            for call_index in returns.iter().rev().map(|ret| ret - 1) {
+                if let Some(Some(call)) = self.code_holder.instructions.get(call_index) {
+                    context.instruction.push(call.clone());
+                    context.instruction_pointer.push(call_index);
+                }
+            }
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Executes instructions starting at `start_index` until the outermost function returns.
+    ///
+    /// Calls do not recurse on the native stack; instead the index of the instruction after each
+    /// `Call` is pushed to `returns` and popped again by `Ret`, so deep recursion in the code can
+    /// not overflow the stack of the host.
+    ///
+    /// `start_index` (`usize`): index of the first instruction to execute
+    /// `returns` (`&mut Vec<usize>`): return addresses of the calls that are running
+    fn execute_from(&mut self, start_index: usize, returns: &mut Vec<usize>) -> Result<(), ResurgenceError> {
         let mut index = start_index;
-        let max_length = self.code_holder.instructions.len();
-        while index < max_length {
-            // Move operation out of vector
-            let operation = self.code_holder.instructions[index].take().unwrap();
-            let ins_index = index;
+        // Holding a handle to the code lets instructions be borrowed while the state is modified
+        let code = Arc::clone(&self.code_holder);
+        let max_length = code.instructions.len();
+        let mut until_interrupt_check = 0;
+        loop {
+            // Running past the end of the code returns from the current function
+            if index >= max_length {
+                match returns.pop() {
+                    Some(ret) => {
+                        self.current_recursion_depth -= 1;
+                        index = ret;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            // Burn fuel before executing the instruction
+            if let Some(fuel) = self.fuel.as_mut() {
+                if *fuel == 0 {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::OUT_OF_FUEL, "Ran out of fuel!");
+                    self.suspended = Some(Suspension { index, returns: returns.clone() });
+                    err.context = Some(create_context!(self, vec![], vec![index]));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+                *fuel -= 1;
+            }
+
+            // Checking the clock is slow compared to an instruction, so only check now and then
+            if until_interrupt_check == 0 {
+                if let Err(mut err) = self.check_interrupt() {
+                    self.suspended = Some(Suspension { index, returns: returns.clone() });
+                    err.context = Some(create_context!(self, vec![], vec![index]));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+                until_interrupt_check = INTERRUPT_CHECK_INTERVAL;
+            }
+            until_interrupt_check -= 1;
 
-            // To encourage the compiler to optimze extra bounds checks
-            assert!(ins_index < max_length);
-            assert!(ins_index == index);
+            let operation = match &code.instructions[index] {
+                Some(operation) => operation,
+                None => {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, &format!("Instruction {} is missing!", index));
+                    err.context = Some(create_context!(self, vec![], vec![index]));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            };
 
             // Instruction evaluation
-            match operation {
+            match *operation {
                 Instruction::Alloc(ref register_amount) => {
-                    self.call_stack.push(StackFrame::from(*register_amount))
+                    let res = self.alloc_frame(*register_amount);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
                 }
                 Instruction::FrameAlloc(ref register_amount, ref location) => {
-                    match *location {
-                        crate::objects::register::RegisterLocation::Global => {
-                            for _ in 0..*register_amount {
-                                self.global.push(Option::None);
-                            }
-                        },
-                        crate::objects::register::RegisterLocation::Local => {
-                            let stackframe = self.call_stack.last_mut().unwrap();
-                            for _ in 0..*register_amount {
-                                stackframe.registers.push(Option::None);
-                            }
-                        }
-                        _ => {
-                            let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Attempted to add more memory to an invalid location!");
-                            let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                            context.instruction.push(operation);
-                            context.instruction_pointer.push(index);
-                            create_new_trace!(err);
-                            return Err(err);
-                        }
+                    let res = self.alloc_registers(*register_amount, location);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
                     }
                 }
                 Instruction::Free(ref block_amount) => {
-                    for _ in 0..*block_amount {
-                        self.call_stack.pop();
-                    }
+                    self.free_frames(*block_amount);
                 }
                 Instruction::FrameFree(ref register_amount, ref location) => {
-                    match *location {
-                        crate::objects::register::RegisterLocation::Global => {
-                            for _ in 0..*register_amount {
-                                self.global.pop();
-                            }
-                        },
-                        crate::objects::register::RegisterLocation::Local => {
-                            let stackframe = self.call_stack.last_mut().unwrap();
-                            for _ in 0..*register_amount {
-                                stackframe.registers.pop();
-                            }
-                        },
-                        _ => {
-                            let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can not allocate more memory outside of local and global memory.");
-                            let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                            context.instruction.push(operation);
-                            context.instruction_pointer.push(index);
-                            create_new_trace!(err);
-                            return Err(err);
-                        }
+                    let res = self.free_registers(*register_amount, location);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
                     }
                 }
                 Instruction::Jump(ref jmp_amount) => {
-                    index = (index as i64 + jmp_amount) as usize;
-                    self.code_holder.instructions[ins_index] = Some(operation);
+                    let res = self.jump_relative(index, *jmp_amount);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                    index = res.unwrap();
+                    continue;
+                }
+                Instruction::JumpTo(ref target) => {
+                    let res = self.jump_target(*target);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                    index = res.unwrap();
+                    continue;
+                }
+                Instruction::JumpIfTrue(ref reg, ref target) | Instruction::JumpIfFalse(ref reg, ref target) => {
+                    let condition = matches!(operation, Instruction::JumpIfTrue(..));
+                    let res = self.cond_jump(reg, condition, *target);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                    if let Some(new_index) = res.unwrap() {
+                        index = new_index;
+                        continue;
+                    }
+                }
+                Instruction::JumpTable(ref reg, ref default, ref table) => {
+                    let res = self.table_jump(reg, *default, table);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                    index = res.unwrap();
                     continue;
                 }
                 Instruction::Call(ref func_index) => {
-                    let res = self.execute_instruction(*func_index as usize);
+                    if self.current_recursion_depth >= self.max_recursion_depth {
+                        let mut err = ResurgenceError::from(ResurgenceErrorKind::RECURSION_LIMIT, &format!("Calls are nested deeper than the limit of {}!", self.max_recursion_depth));
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                    let res = self.jump_target(*func_index);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
                     }
+                    self.current_recursion_depth += 1;
+                    returns.push(index + 1);
+                    index = res.unwrap();
+                    continue;
                 },
                 Instruction::ExtCall(ref func_reg) => {
                     let res = self.ext_call(*func_reg);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err); 
                         return Err(err);
                     }
                 },
                 Instruction::Ret => {
-                    self.code_holder.instructions[ins_index] = Some(operation);
-                    return Result::Ok(());
+                    match returns.pop() {
+                        Some(ret) => {
+                            self.current_recursion_depth -= 1;
+                            index = ret;
+                            continue;
+                        }
+                        None => return Result::Ok(()),
+                    }
                 },
 
                 Instruction::Mov(ref dst_reg, ref dst_reg_ref, ref src_reg, ref src_reg_ref) => {
                     let res = self.mov_registers(dst_reg, dst_reg_ref, src_reg, src_reg_ref);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -148,7 +339,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.cpy_registers(dst_reg, dst_reg_ref, src_reg, src_reg_ref);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -158,7 +349,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.ref_registers(dst_reg, dst_reg_ref, src_reg, src_reg_ref);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -169,7 +360,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.push_on_stack(register, reference);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -179,7 +370,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.stack_mov(register, reference);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -193,7 +384,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.add(dst_reg, reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -203,7 +394,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.sub(dst_reg, reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -213,7 +404,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.mul(dst_reg, reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -223,7 +414,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.div(dst_reg, reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -233,7 +424,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.modlo(dst_reg, reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -244,7 +435,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.equal(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -257,7 +448,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.not_equal(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -270,7 +461,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.greater_than(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -283,7 +474,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.less_than(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -296,7 +487,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.greater_or_equal(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -309,7 +500,7 @@ impl ExecutionEngine for Interpreter {
                     let res = self.less_or_equal(reg_1, reg_2);
                     if let Err(mut err) = res {
                         let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                        context.instruction.push(operation);
+                        context.instruction.push(operation.clone());
                         context.instruction_pointer.push(index);
                         create_new_trace!(err);
                         return Err(err);
@@ -318,34 +509,228 @@ impl ExecutionEngine for Interpreter {
                         index += 1;
                     }
                 }
+                Instruction::ToInt(ref dst_reg, ref src_reg) => {
+                    let res = self.convert_int(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::ToDouble(ref dst_reg, ref src_reg) => {
+                    let res = self.convert_double(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::ToString(ref dst_reg, ref src_reg) => {
+                    let res = self.convert_string(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::ToBool(ref dst_reg, ref src_reg) => {
+                    let res = self.convert_bool(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::TypeOf(ref dst_reg, ref src_reg) => {
+                    let res = self.type_of(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::StrLen(ref dst_reg, ref src_reg) => {
+                    let res = self.str_len(dst_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::Substr(ref dst_reg, ref src_reg, ref start_reg, ref length_reg) => {
+                    let res = self.substr(dst_reg, src_reg, start_reg, length_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::CharAt(ref dst_reg, ref src_reg, ref index_reg) => {
+                    let res = self.char_at(dst_reg, src_reg, index_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecNew(ref dst_reg) => {
+                    let res = self.vec_new(dst_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecGet(ref dst_reg, ref vec_reg, ref index_reg) => {
+                    let res = self.vec_get(dst_reg, vec_reg, index_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecSet(ref vec_reg, ref index_reg, ref src_reg) => {
+                    let res = self.vec_set(vec_reg, index_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecPush(ref vec_reg, ref src_reg) => {
+                    let res = self.vec_push(vec_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecPop(ref dst_reg, ref vec_reg) => {
+                    let res = self.vec_pop(dst_reg, vec_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::VecLen(ref dst_reg, ref vec_reg) => {
+                    let res = self.vec_len(dst_reg, vec_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapNew(ref dst_reg) => {
+                    let res = self.map_new(dst_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapGet(ref dst_reg, ref map_reg, ref key_reg) => {
+                    let res = self.map_get(dst_reg, map_reg, key_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapSet(ref map_reg, ref key_reg, ref src_reg) => {
+                    let res = self.map_set(map_reg, key_reg, src_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapRemove(ref map_reg, ref key_reg) => {
+                    let res = self.map_remove(map_reg, key_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapContains(ref dst_reg, ref map_reg, ref key_reg) => {
+                    let res = self.map_contains(dst_reg, map_reg, key_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapLen(ref dst_reg, ref map_reg) => {
+                    let res = self.map_len(dst_reg, map_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
+                Instruction::MapKeys(ref dst_reg, ref map_reg) => {
+                    let res = self.map_keys(dst_reg, map_reg);
+                    if let Err(mut err) = res {
+                        let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
+                        context.instruction.push(operation.clone());
+                        context.instruction_pointer.push(index);
+                        create_new_trace!(err);
+                        return Err(err);
+                    }
+                }
                 _ => {
                     let mut err = ResurgenceError::from(ResurgenceErrorKind::I_GOOFED_UP, "Either this bytecode operation is from a future version of RVM or God himself because I don't know what to do with it");
                     let context = err.context.get_or_insert_with(|| create_context!(self, vec![], vec![]));
-                    context.instruction.push(operation);
+                    context.instruction.push(operation.clone());
                     context.instruction_pointer.push(index);
                     create_new_trace!(err);
                     return Err(err);
                 }
             }
 
-            // Store instruction back into memory and increment index
-            self.code_holder.instructions[ins_index] = Some(operation);
             index += 1;
         }
         Result::Ok(())
     }
-
-    // Execute an exported function.
-    fn execute_function(&mut self, func_name: &str) -> Result<(), ResurgenceError> {
-        match self.code_holder.exports.get(func_name) {
-            // This call is the first call of the instace
-            Some(inst) => self.execute_instruction(*inst as usize),
-            None => {
-                let mut err = ResurgenceError::from(ResurgenceErrorKind::FUNCTION_DOES_NOT_EXIST, 
-                &format!("Function {} does not exist!", func_name));
-                create_new_trace!(err);
-                Err(err)
-            }
-        }
-    }
 }
@@ -10,10 +10,27 @@ pub struct RustFunc {
     // undefined behavior WILL result if this is not adhered to. See ext_call.rs to learn why.
     pub native: bool,
     pub native_func: Option<extern "C" fn(&mut ResurgenceState) -> u8>,
+    /// Capability the code needs to be granted to import this function, or `None` if any code may
+    /// import it
+    pub capability: Option<String>,
+}
+
+/// Describes one import of the code, as reported by [`Interpreter::audit_imports`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportAudit {
+    /// Name of the imported function
+    pub name: String,
+    /// Whether a function with this name has been registered
+    pub registered: bool,
+    /// Capability the function belongs to, or `None` if it does not need one
+    pub capability: Option<String>,
+    /// Whether the code is allowed to import the function. Functions that have not been
+    /// registered are never allowed.
+    pub allowed: bool,
 }
 
 impl Interpreter {
-    /// Registers a single function to the interpreter instance
+    /// Registers a single function to the interpreter instance. Any code may import it.
     ///
     /// `function` (`fn(&mut ResurgenceState) -> Result<(), Error>`)
     pub fn register_function(
@@ -26,6 +43,29 @@ impl Interpreter {
             func: Some(function),
             native: false,
             native_func: None,
+            capability: None,
+        });
+    }
+
+    /// Registers a single function that belongs to a capability, such as `"fs"` or `"network"`.
+    /// Code may only import it once the capability has been granted with
+    /// [`Interpreter::grant_capability`].
+    ///
+    /// `function` (`fn(&mut ResurgenceState) -> Result<(), Error>`)
+    /// `func_name` (`String`): name the code imports the function by
+    /// `capability` (`String`): name of the capability
+    pub fn register_function_with_capability(
+        &mut self,
+        function: fn(&mut ResurgenceState) -> Result<(), Error>,
+        func_name: String,
+        capability: String,
+    ) {
+        self.rust_functions.push(RustFunc {
+            name: func_name,
+            func: Some(function),
+            native: false,
+            native_func: None,
+            capability: Some(capability),
         });
     }
 
@@ -33,12 +73,75 @@ impl Interpreter {
         &mut self,
         function: extern "C" fn(&mut ResurgenceState) -> u8,
         func_name: String,
+        capability: Option<String>,
     ) {
         self.rust_functions.push(RustFunc {
             name: func_name,
             func: None,
             native: true,
             native_func: Some(function),
+            capability,
         });
     }
+
+    /// Allows the code to import the functions belonging to a capability
+    ///
+    /// `capability` (`String`): name of the capability
+    pub fn grant_capability(&mut self, capability: String) {
+        self.granted_capabilities.insert(capability);
+    }
+
+    /// Takes back a capability. Imports that have already been resolved are not affected.
+    ///
+    /// `capability` (`&str`): name of the capability
+    pub fn revoke_capability(&mut self, capability: &str) {
+        self.granted_capabilities.remove(capability);
+    }
+
+    /// Returns true if the capability has been granted
+    ///
+    /// `capability` (`&str`): name of the capability
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.granted_capabilities.contains(capability)
+    }
+
+    /// Reports every function the code imports, which capability it needs and whether the code
+    /// is allowed to use it, so the host can check a module before resolving its imports
+    pub fn audit_imports(&self) -> Vec<ImportAudit> {
+        self.code_holder
+            .imports
+            .iter()
+            .map(|name| match self.find_function(name) {
+                Some(function) => ImportAudit {
+                    name: name.clone(),
+                    registered: true,
+                    capability: function.capability.clone(),
+                    allowed: self.may_import(function),
+                },
+                None => ImportAudit {
+                    name: name.clone(),
+                    registered: false,
+                    capability: None,
+                    allowed: false,
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the index of the first registered function called `name`
+    pub(crate) fn find_function_index(&self, name: &str) -> Option<usize> {
+        self.rust_functions.iter().position(|function| function.name == name)
+    }
+
+    fn find_function(&self, name: &str) -> Option<&RustFunc> {
+        self.find_function_index(name).map(|index| &self.rust_functions[index])
+    }
+
+    /// Returns true if the code may import `function`
+    pub(crate) fn may_import(&self, function: &RustFunc) -> bool {
+        match &function.capability {
+            Some(capability) => self.has_capability(capability),
+            None => true,
+        }
+    }
 }
@@ -6,7 +6,12 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn equal(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError> {
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 == *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok((*val_1) as f64 == *val_2),
@@ -28,7 +33,12 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn not_equal(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError> {
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 != *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok((*val_1) as f64 != *val_2),
@@ -50,12 +60,18 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn greater_than(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError> {
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 > *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok((*val_1) as f64 > *val_2),
             (Constant::Double(val_1), Constant::Int(val_2)) => Ok(*val_1 > (*val_2) as f64),
             (Constant::Double(val_1), Constant::Double(val_2)) => Ok(*val_1 > *val_2),
+            (Constant::String(val_1), Constant::String(val_2)) => Ok(*val_1 > *val_2),
             _ => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Greater Than not implemented for the given types!");
                 create_new_trace!(err);
@@ -69,12 +85,18 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn less_than(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError>{
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 < *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok(((*val_1) as f64) < *val_2),
             (Constant::Double(val_1), Constant::Int(val_2)) => Ok(*val_1 < (*val_2) as f64),
             (Constant::Double(val_1), Constant::Double(val_2)) => Ok(*val_1 < *val_2),
+            (Constant::String(val_1), Constant::String(val_2)) => Ok(*val_1 < *val_2),
             _ => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Less Than not implemented for the given types!");
                 create_new_trace!(err);
@@ -89,12 +111,18 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn greater_or_equal(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError> {
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 >= *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok((*val_1) as f64 >= *val_2),
             (Constant::Double(val_1), Constant::Int(val_2)) => Ok(*val_1 >= (*val_2) as f64),
             (Constant::Double(val_1), Constant::Double(val_2)) => Ok(*val_1 >= *val_2),
+            (Constant::String(val_1), Constant::String(val_2)) => Ok(*val_1 >= *val_2),
             _ => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Less Than not implemented for the given types!");
                 create_new_trace!(err);
@@ -108,12 +136,18 @@ impl Interpreter {
     /// `reg_1` (`&Register`): first register
     /// `reg_2` (`&Register`): second register
     pub(crate) fn less_or_equal(&mut self, reg_1: &Register, reg_2: &Register) -> Result<bool, ResurgenceError> {
-        let (const_1, const_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (const_1, const_2) = constants.unwrap();
         match (const_1, const_2) {
             (Constant::Int(val_1), Constant::Int(val_2)) => Ok(*val_1 <= *val_2),
             (Constant::Int(val_1), Constant::Double(val_2)) => Ok((*val_1) as f64 <= *val_2),
             (Constant::Double(val_1), Constant::Int(val_2)) => Ok(*val_1 <= (*val_2) as f64),
             (Constant::Double(val_1), Constant::Double(val_2)) => Ok(*val_1 <= *val_2),
+            (Constant::String(val_1), Constant::String(val_2)) => Ok(*val_1 <= *val_2),
             _ => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Less Than not implemented for the given types!");
                 create_new_trace!(err);
@@ -0,0 +1,105 @@
+use crate::{Interpreter, objects::register::Register, ResurgenceError, create_new_trace};
+
+impl Interpreter {
+    /// Converts a register to an integer and stores it in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register to convert
+    pub(crate) fn convert_int(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().cast_int();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Converts a register to a double and stores it in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register to convert
+    pub(crate) fn convert_double(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().cast_double();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Converts a register to a string and stores it in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register to convert
+    pub(crate) fn convert_string(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().cast_string();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Converts a register to a boolean and stores it in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register to convert
+    pub(crate) fn convert_bool(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().cast_bool();
+        if let Err(mut err) = self.mov_dst(dst, res) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores the type tag of a register in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register to inspect
+    pub(crate) fn type_of(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().type_of();
+        if let Err(mut err) = self.mov_dst(dst, res) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
@@ -2,11 +2,20 @@ use crate::{ext_func::resurgence_state::ResurgenceState, Interpreter, Resurgence
 
 impl Interpreter {
     pub(crate) fn ext_call(&mut self, index: u64) -> Result<(), ResurgenceError> {
-        let real_id = &self.code_holder.byte_to_interal[index as usize];
+        let function = match self.byte_to_interal.get(index as usize) {
+            Some(real_id) => self.rust_functions.get(*real_id as usize),
+            None => None,
+        };
+        let function = match function {
+            Some(function) => function,
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::MISSING_IMPORTS, &format!("Import {} is not resolved!", index));
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
         let mut state = ResurgenceState::new(&mut self.stack);
 
-        let function = &self.rust_functions[*real_id as usize];
-
         if function.native {
             // function.native guarantees this will succeed, so it should be safe
             let func = unsafe { function.native_func.unwrap_unchecked() };
@@ -14,6 +23,7 @@ impl Interpreter {
             if ec != 0 {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::FUNCTION_RETURN_ERROR, &format!("Native function \"{}\" returned nonzero status code {}", function.name, ec));
                 create_new_trace!(err);
+                return Err(err);
             }
 
             return Ok(());
@@ -0,0 +1,98 @@
+use crate::{Interpreter, objects::{register::Register, constant::Constant, resurgence_error::ResurgenceErrorKind}, ResurgenceError, create_new_trace};
+
+impl Interpreter {
+    /// Checks if an instruction index is within bounds and returns it as a `usize`
+    ///
+    /// `target` (`u64`): index of the instruction to jump to
+    pub(crate) fn jump_target(&self, target: u64) -> Result<usize, ResurgenceError> {
+        if target >= self.code_holder.instructions.len() as u64 {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::INSTRUCTION_OUT_OF_BOUNDS, &format!("Can not jump to instruction {}, it is beyond bounds!", target));
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(target as usize)
+    }
+
+    /// Adds a relative jump to an instruction index and checks that the result is within bounds
+    ///
+    /// `index` (`usize`): index of the jump instruction
+    /// `offset` (`i64`): amount of instructions to jump
+    pub(crate) fn jump_relative(&self, index: usize, offset: i64) -> Result<usize, ResurgenceError> {
+        let target = match (index as i64).checked_add(offset).map(u64::try_from) {
+            Some(Ok(target)) => target,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INSTRUCTION_OUT_OF_BOUNDS, &format!("Can not jump {} instructions from instruction {}, it is beyond bounds!", offset, index));
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+
+        let res = self.jump_target(target);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(res.unwrap())
+    }
+
+    /// Returns the instruction to jump to if the register holds `condition`, otherwise `None`
+    ///
+    /// `reg` (`&Register`): register holding a boolean
+    /// `condition` (`bool`): value the register must hold to jump
+    /// `target` (`u64`): index of the instruction to jump to
+    pub(crate) fn cond_jump(&mut self, reg: &Register, condition: bool, target: u64) -> Result<Option<usize>, ResurgenceError> {
+        let value = match self.get_constant(reg) {
+            Ok(Constant::Boolean(val)) => *val,
+            Err(mut err) => {
+                create_new_trace!(err);
+                return Err(err);
+            },
+            Ok(_) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Conditional jumps require a boolean register!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        if value != condition {
+            return Ok(None);
+        }
+
+        let res = self.jump_target(target);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(Some(res.unwrap()))
+    }
+
+    /// Returns the instruction to jump to based on the index held by an integer register
+    ///
+    /// `reg` (`&Register`): register holding the index into the table
+    /// `default` (`u64`): instruction to jump to if the index is outside of the table
+    /// `table` (`&[u64]`): instructions to jump to
+    pub(crate) fn table_jump(&mut self, reg: &Register, default: u64, table: &[u64]) -> Result<usize, ResurgenceError> {
+        let index = match self.get_constant(reg) {
+            Ok(Constant::Int(val)) => *val,
+            Err(mut err) => {
+                create_new_trace!(err);
+                return Err(err);
+            },
+            Ok(_) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Jump tables require an integer register!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let target = match usize::try_from(index) {
+            Ok(i) if i < table.len() => table[i],
+            _ => default,
+        };
+
+        let res = self.jump_target(target);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(res.unwrap())
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+
+use crate::{Interpreter, objects::{register::Register, constant::Constant}, ResurgenceError, create_new_trace};
+
+impl Interpreter {
+    /// Stores a new, empty map in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    pub(crate) fn map_new(&mut self, dst: &Register) -> Result<(), ResurgenceError> {
+        if let Err(mut err) = self.mov_dst(dst, Constant::Map(BTreeMap::new())) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores a copy of the value stored under a key of a map in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `map` (`&Register`): register holding the map
+    /// `key` (`&Register`): register holding the key
+    pub(crate) fn map_get(&mut self, dst: &Register, map: &Register, key: &Register) -> Result<(), ResurgenceError> {
+        let key_res = self.get_constant(key);
+        if let Err(mut err) = key_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let key_const = key_res.unwrap().clone();
+
+        let map_res = self.get_constant(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_res.unwrap().map_get(&key_const);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.memory_limits.check_constant(res.as_ref().unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores a copy of a register under a key of a map
+    ///
+    /// `map` (`&Register`): register holding the map
+    /// `key` (`&Register`): register holding the key
+    /// `value` (`&Register`): register holding the value
+    pub(crate) fn map_set(&mut self, map: &Register, key: &Register, value: &Register) -> Result<(), ResurgenceError> {
+        let limits = self.memory_limits;
+        let key_res = self.get_constant(key);
+        if let Err(mut err) = key_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let key_const = key_res.unwrap().clone();
+        let value_res = self.get_constant(value);
+        if let Err(mut err) = value_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = value_res.unwrap();
+        if let Err(mut err) = limits.check_constant(element) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = element.clone();
+
+        let map_res = self.get_constant_mut(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let map_const = map_res.unwrap();
+        // Replacing the value of a key does not grow the map
+        let new_key = !matches!(map_const.map_contains(&key_const), Ok(Constant::Boolean(true)));
+        if let Err(mut err) = limits.check_growth(map_const, new_key as usize) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_const.map_set(&key_const, element);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Removes a key and its value from a map
+    ///
+    /// `map` (`&Register`): register holding the map
+    /// `key` (`&Register`): register holding the key
+    pub(crate) fn map_remove(&mut self, map: &Register, key: &Register) -> Result<(), ResurgenceError> {
+        let key_res = self.get_constant(key);
+        if let Err(mut err) = key_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let key_const = key_res.unwrap().clone();
+
+        let map_res = self.get_constant_mut(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_res.unwrap().map_remove(&key_const);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores whether a map contains a key in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `map` (`&Register`): register holding the map
+    /// `key` (`&Register`): register holding the key
+    pub(crate) fn map_contains(&mut self, dst: &Register, map: &Register, key: &Register) -> Result<(), ResurgenceError> {
+        let key_res = self.get_constant(key);
+        if let Err(mut err) = key_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let key_const = key_res.unwrap().clone();
+
+        let map_res = self.get_constant(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_res.unwrap().map_contains(&key_const);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores the amount of entries in a map in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `map` (`&Register`): register holding the map
+    pub(crate) fn map_len(&mut self, dst: &Register, map: &Register) -> Result<(), ResurgenceError> {
+        let map_res = self.get_constant(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_res.unwrap().map_len();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores the keys of a map as a vector in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `map` (`&Register`): register holding the map
+    pub(crate) fn map_keys(&mut self, dst: &Register, map: &Register) -> Result<(), ResurgenceError> {
+        let map_res = self.get_constant(map);
+        if let Err(mut err) = map_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = map_res.unwrap().map_keys();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.memory_limits.check_constant(res.as_ref().unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Interpreter, objects::register::{Register, RegisterLocation}, objects::constant::Constant, ResurgenceError, create_new_trace};
+use crate::{Interpreter, objects::register::{Register, RegisterLocation}, objects::constant::Constant, objects::resurgence_error::ResurgenceErrorKind, ResurgenceError, create_new_trace};
 
 
 impl Interpreter {
@@ -6,96 +6,190 @@ impl Interpreter {
         Private utility functions used by this module
     */
 
-    /// Moves a value to the destination register
+    /// Returns an error if a value can not be moved to the destination register, because:
+    /// - Register is in the constant pool
+    /// - Register is the accumulator and the value is not a number
+    /// - Register is beyond bounds
+    ///
+    /// Instructions that take their value out of another register call this first, so a failed
+    /// move leaves the source untouched.
+    ///
+    /// `dst` (`&Register`): Destination register
+    /// `value` (`&Constant`): Constant about to be moved
+    pub(crate) fn check_dst(&self, dst: &Register, value: &Constant) -> Result<(), ResurgenceError> {
+        let Register(dst_index, dst_loc) = dst; let dst_index_usize = *dst_index as usize;
+
+        let registers_len = match *dst_loc {
+            RegisterLocation::ConstantPool => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can not assign to a constant!");
+                create_new_trace!(err);
+                return Err(err);
+            },
+            RegisterLocation::Accumulator => {
+                if let Constant::Int(_) | Constant::Double(_) = value {
+                    return Ok(());
+                }
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only write integers and doubles to the accumulator register");
+                create_new_trace!(err);
+                return Err(err);
+            },
+            RegisterLocation::Global => self.global.len(),
+            RegisterLocation::Local => match self.ref_stack_frame_imut() {
+                Ok(stack_frame) => stack_frame.registers.len(),
+                Err(mut err) => {
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            },
+        };
+        if dst_index_usize >= registers_len {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Register beyond bounds!");
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Moves a value to the destination register, or returns an error if [`Interpreter::check_dst`]
+    /// rejects it
+    ///
+    /// The value is not checked against the memory limits, since moving it does not make it
+    /// larger. Instructions that build or copy values check them before calling this.
     /// 
     /// `dst` (`&Register`): Destination register
     /// `value` (`&Constant`): Constant being moved
-    fn mov_dst(&mut self, dst: &Register, value: Constant) {
+    pub(crate) fn mov_dst(&mut self, dst: &Register, value: Constant) -> Result<(), ResurgenceError> {
+        if let Err(mut err) = self.check_dst(dst, &value) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
         // Destination register itself
         let Register(dst_index, dst_loc) = dst; let dst_index_usize = *dst_index as usize;
 
         // Get the location of the destination register
-        match *dst_loc {
-            RegisterLocation::ConstantPool => panic!("Segmentation fault! Can not assign to a constant!"),
+        let registers = match *dst_loc {
             RegisterLocation::Accumulator => {
                 match value {
                     Constant::Int(int_value) => self.accumulator = int_value as f64,
                     Constant::Double(double_value) => self.accumulator = double_value,
-                    _ => panic!("Invalid type!")
+                    _ => unreachable!("checked by check_dst"),
                 }
-            } 
-            RegisterLocation::Global => self.global[dst_index_usize] = Some(value),
-            RegisterLocation::Local => {
-                let stack_frame = self.ref_stack_frame();
-                stack_frame.registers[dst_index_usize] = Some(value);
-            }
-        }
+                return Ok(());
+            },
+            RegisterLocation::Global => &mut self.global,
+            RegisterLocation::Local => match self.ref_stack_frame() {
+                Ok(stack_frame) => &mut stack_frame.registers,
+                Err(mut err) => {
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            },
+            RegisterLocation::ConstantPool => unreachable!("checked by check_dst"),
+        };
+        registers[dst_index_usize] = Some(value);
+        Ok(())
     }
 
     /*
         All of the actual math functions used in the execution engine
     */
     pub(crate) fn add(&mut self, dst: &Register, reg_1: &Register, reg_2: &Register) -> Result<(), ResurgenceError> {
-        let (constant_1, constant_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (constant_1, constant_2) = constants.unwrap();
         let res = constant_1.add(constant_2);
         if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        if let Ok(obj) = res {
-            self.mov_dst(dst, obj); 
+        // Adding strings concatenates them, so the result may be beyond the limits
+        if let Err(mut err) = self.memory_limits.check_constant(res.as_ref().unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Ok(())
     }
 
     pub(crate) fn sub(&mut self, dst: &Register, reg_1: &Register, reg_2: &Register) -> Result<(), ResurgenceError> {
-        let (constant_1, constant_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (constant_1, constant_2) = constants.unwrap();
         let res = constant_1.sub(constant_2);
         if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        if let Ok(obj) = res {
-            self.mov_dst(dst, obj);
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Ok(())
     }
 
     pub(crate) fn mul(&mut self, dst: &Register, reg_1: &Register, reg_2: &Register) -> Result<(), ResurgenceError> {
-        let (constant_1, constant_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (constant_1, constant_2) = constants.unwrap();
         let res = constant_1.mul(constant_2);
         if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        if let Ok(obj) = res {
-            self.mov_dst(dst, obj); 
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Ok(())
     }
 
     pub(crate) fn div(&mut self, dst: &Register, reg_1: &Register, reg_2: &Register) -> Result<(), ResurgenceError> {
-        let (constant_1, constant_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (constant_1, constant_2) = constants.unwrap();
         let res = constant_1.div(constant_2);
         if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        if let Ok(obj) = res {
-            self.mov_dst(dst, obj);
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Ok(())
     }
 
     pub(crate) fn modlo(&mut self, dst: &Register, reg_1: &Register, reg_2: &Register) -> Result<(), ResurgenceError> {
-        let (constant_1, constant_2) = self.get_constants(reg_1, reg_2);
+        let constants = self.get_constants(reg_1, reg_2);
+        if let Err(mut err) = constants {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let (constant_1, constant_2) = constants.unwrap();
         let res = constant_1.modlo(constant_2);
         if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        if let Ok(obj) = res {
-            self.mov_dst(dst, obj);
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Ok(())
     }
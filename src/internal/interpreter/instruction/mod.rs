@@ -5,3 +5,8 @@ pub(crate) mod stk_push;
 pub(crate) mod math;
 pub(crate) mod cmp;
 pub(crate) mod ext_call;
+pub(crate) mod jump;
+pub(crate) mod convert;
+pub(crate) mod strings;
+pub(crate) mod vector;
+pub(crate) mod map;
@@ -1,119 +1,73 @@
 use super::super::super::interpreter::Interpreter;
 use crate::{ResurgenceError, create_new_trace};
-use crate::objects::constant::{Constant, create_constant_double};
+use crate::objects::constant::create_constant_double;
 use crate::objects::register::{Register, RegisterLocation, RegisterReference};
 use crate::objects::resurgence_error::ResurgenceErrorKind;
 
 impl Interpreter {
     pub(crate) fn mov_registers(&mut self, dst_reg: &Register, dst_reg_ref: &RegisterReference, src_reg: &Register, src_reg_ref: &RegisterReference) -> Result<(), ResurgenceError> {
-        // Destination register
-        let Register(mut dst_index, mut dst_loc) = dst_reg; 
-        let mut dst_index_usize = dst_index as usize;
-
-        // Dereference the destination register if needed
-        if *dst_reg_ref == RegisterReference::Dereference {
-            Register(dst_index, dst_loc) = self.dereference_register(dst_index_usize, &dst_loc);
-            dst_index_usize = dst_index as usize;
+        // Destination register, dereferenced if needed
+        let dst = self.resolve_register(dst_reg, dst_reg_ref);
+        if let Err(mut err) = dst {
+            create_new_trace!(err);
+            return Err(err);
         }
-        
-        // Source register
-        let Register(mut src_index, mut src_loc) = src_reg; 
-        let mut src_index_usize = src_index as usize;
+        let dst = dst.unwrap();
 
-        // Dereference the source register if needed
-        if *src_reg_ref == RegisterReference::Dereference {
-            Register(src_index, src_loc) = self.dereference_register(src_index_usize, &src_loc);
-            src_index_usize = src_index as usize;
+        // Source register, dereferenced if needed
+        let src = self.resolve_register(src_reg, src_reg_ref);
+        if let Err(mut err) = src {
+            create_new_trace!(err);
+            return Err(err);
         }
-    
-        match (dst_loc, src_loc) {
-            (RegisterLocation::Accumulator, RegisterLocation::Global) => {
-                // Start doing the move if src_register is not None
-                let src_register = self.mov_global(src_index_usize); // take the value from global memory
-                if let Err(mut err) = src_register {
-                    create_new_trace!(err);
-                    return Err(err);
-                }
-                match src_register.unwrap() {
-                    Constant::Int(src_int) => {
-                        self.accumulator = src_int as f64;
-                    }
-                    Constant::Double(src_double) => {
-                        self.accumulator = src_double;
-                    }
-                    _ => {
-                        let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Invalid move to accumulator register!");
-                        create_new_trace!(err);
-                        return Err(err);
-                    }
-                }
-            },
-            (RegisterLocation::Accumulator, RegisterLocation::Local) => {
-                let src_register = self.mov_local(src_index_usize);
-                match src_register {
-                    Constant::Int(src_int) => {
-                        self.accumulator = src_int as f64;
-                    }
-                    Constant::Double(src_double) => {
-                        self.accumulator = src_double;
-                    },
-                    _ => {
-                        let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Invalid move to accumulator register!");
-                        create_new_trace!(err);
-                        return Err(err);
-                    }
-                }
-            },
-    
-            (RegisterLocation::Global, RegisterLocation::Accumulator) => self.global[dst_index_usize] = Some(create_constant_double(&self.accumulator)),
-            (RegisterLocation::Global, RegisterLocation::Global) => {
-                let src_reg = self.mov_global(src_index_usize);
-                if let Err(mut err) = src_reg {
-                    create_new_trace!(err);
-                    return Err(err);
-                }
-                self.global[dst_index_usize] = Some(src_reg.unwrap());
-            }, 
-            (RegisterLocation::Global, RegisterLocation::Local) => self.global[dst_index_usize] = Some(self.mov_local(src_index_usize)),
-    
-            (RegisterLocation::Local, RegisterLocation::Accumulator) => {
-                let accumulator = self.accumulator;
-                let stack_frame = self.ref_stack_frame();
-                stack_frame.registers[dst_index_usize] = Some(create_constant_double(&accumulator));
-            },
-            (RegisterLocation::Local, RegisterLocation::Global) => {
-                let global_value = self.mov_global(src_index_usize);
-                let stack_frame = self.ref_stack_frame();
+        let Register(src_index, src_loc) = src.unwrap();
+        let src_index_usize = src_index as usize;
 
-                if let Err(mut err) = global_value {
-                    create_new_trace!(err);
-                    return Err(err);
-                }
-                stack_frame.registers[dst_index_usize] = Some(global_value.unwrap());
-            },
-            (RegisterLocation::Local, RegisterLocation::Local) => {
-                let stack_frame = self.ref_stack_frame();
-                stack_frame.registers[dst_index_usize] = Some(stack_frame.mov_register(src_index_usize));
-            },
-            _ => {
+        // Check the operation before taking the value out of the source register
+        match (dst.1, src_loc) {
+            (RegisterLocation::ConstantPool, _) | (_, RegisterLocation::ConstantPool) | (RegisterLocation::Accumulator, RegisterLocation::Accumulator) => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Invalid MOV operation");
                 create_new_trace!(err);
                 return Err(err);
-            }
+            },
+            _ => {}
+        }
+
+        // Check the destination as well, since a failed move would lose the value otherwise
+        let checked = match src_loc {
+            RegisterLocation::Accumulator => self.check_dst(&dst, &create_constant_double(&self.accumulator)),
+            RegisterLocation::Global => self.ref_global(src_index_usize).and_then(|value| self.check_dst(&dst, value)),
+            _ => self.ref_local(src_index_usize).and_then(|value| self.check_dst(&dst, value)),
+        };
+        if let Err(mut err) = checked {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        let value = match src_loc {
+            RegisterLocation::Accumulator => Ok(create_constant_double(&self.accumulator)),
+            RegisterLocation::Global => self.mov_global(src_index_usize), // take the value from global memory
+            _ => self.mov_local(src_index_usize),
+        };
+        if let Err(mut err) = value {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(&dst, value.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Result::Ok(())
     }
 
     pub(crate) fn stack_mov(&mut self, dst_reg: &Register, dst_reg_ref: &RegisterReference) -> Result<(), ResurgenceError> {
-        // Destination register
-        let Register(mut dst_index, mut dst_loc) = dst_reg; 
-        let mut dst_index_usize = dst_index as usize;
-
-        // Dereference the destination register if needed
-        if *dst_reg_ref == RegisterReference::Dereference {
-            Register(dst_index, dst_loc) = self.dereference_register(dst_index_usize, &dst_loc);
-            dst_index_usize = dst_index as usize;
+        // Destination register, dereferenced if needed
+        let dst = self.resolve_register(dst_reg, dst_reg_ref);
+        if let Err(mut err) = dst {
+            create_new_trace!(err);
+            return Err(err);
         }
+        let dst = dst.unwrap();
 
         // Check if the stack is empty
         if self.stack.is_empty() {
@@ -121,29 +75,20 @@ impl Interpreter {
             create_new_trace!(err);
             return Err(err);
         }
+        if dst.1 == RegisterLocation::ConstantPool {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Moving to the constant pool is forbidden!");
+            create_new_trace!(err);
+            return Err(err);
+        }
+        // Check the destination before popping, so a failed move leaves the value on the stack
+        if let Err(mut err) = self.check_dst(&dst, self.stack.last().unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
         let object = self.stack.remove(self.stack.len() - 1); // We do self.stack.len() - 1 to get the true index. For example, if the vector has a size of 1, the true index is 0
-        match dst_loc {
-            RegisterLocation::ConstantPool => {
-                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Moving to the constant pool is forbidden!");
-                create_new_trace!(err);
-                return Err(err);
-            },
-            RegisterLocation::Accumulator => {
-                match object {
-                    Constant::Int(val) => self.accumulator = val as f64,
-                    Constant::Double(val) => self.accumulator = val,
-                    _ => {
-                        let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only write integers and doubles to the accumulator register");
-                        create_new_trace!(err);
-                        return Err(err);
-                    }
-                }
-            },
-            RegisterLocation::Global => self.global[dst_index_usize] = Some(object),
-            RegisterLocation::Local => {
-                let stack_frame = self.ref_stack_frame();
-                stack_frame.registers[dst_index_usize] = Some(object);
-            },
+        if let Err(mut err) = self.mov_dst(&dst, object) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Result::Ok(())
     }
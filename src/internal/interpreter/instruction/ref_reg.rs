@@ -3,36 +3,27 @@ use crate::{objects::{register::{Register, RegisterLocation, RegisterReference},
 
 impl Interpreter {
     pub(crate) fn ref_registers(&mut self, dst_reg: &Register, dst_reg_ref: &RegisterReference, src_reg: &Register, src_reg_ref: &RegisterReference) -> Result<(), ResurgenceError> {
-        let Register(mut dst_index, mut dst_loc) = dst_reg;
-        let mut dst_index_usize = dst_index as usize;
+        let dst = self.resolve_register(dst_reg, dst_reg_ref);
+        if let Err(mut err) = dst {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let dst = dst.unwrap();
 
-        if *dst_reg_ref == RegisterReference::Dereference {
-            Register(dst_index, dst_loc) = self.dereference_register(dst_index_usize, &dst_loc);
-            dst_index_usize = dst_index as usize;
+        if dst.1 != RegisterLocation::Global && dst.1 != RegisterLocation::Local {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Invalid register location! Can only reference local or global registers!");
+            create_new_trace!(err);
+            return Err(err);
         }
 
-        match dst_loc {
-            RegisterLocation::Global => {
-                if *src_reg_ref == RegisterReference::Dereference {
-                    self.global[dst_index_usize] = Some(Constant::Address(self.dereference_register(src_reg.0 as usize, &src_reg.1)));
-                    return Result::Ok(());
-                }
-                self.global[dst_index_usize] = Some(Constant::Address(*src_reg));
-            },
-            RegisterLocation::Local => {
-                if *src_reg_ref == RegisterReference::Dereference {
-                    let register = Some(Constant::Address(self.dereference_register(src_reg.0 as usize, &src_reg.1))); let stack_frame = self.ref_stack_frame();
-                    stack_frame.registers[dst_index_usize] = register;
-                    return Result::Ok(());
-                }
-                let stack_frame = self.ref_stack_frame();
-                stack_frame.registers[dst_index_usize] = Some(Constant::Address(*src_reg));
-            },
-            _ => {
-                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Invalid register location! Can only reference local or global registers!");
-                create_new_trace!(err);
-                return Err(err);
-            },
+        let src = self.resolve_register(src_reg, src_reg_ref);
+        if let Err(mut err) = src {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(&dst, Constant::Address(src.unwrap())) {
+            create_new_trace!(err);
+            return Err(err);
         }
         Result::Ok(())
     }
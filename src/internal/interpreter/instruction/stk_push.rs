@@ -3,27 +3,29 @@ use crate::{objects::{register::{Register, RegisterReference, RegisterLocation},
 
 impl Interpreter {
     pub(crate) fn push_on_stack(&mut self, register: &Register, reference: &RegisterReference) -> Result<(), ResurgenceError> {
-        let Register(mut reg_index, mut reg_loc) = register; let mut reg_index_usize = reg_index as usize;
-        if *reference == RegisterReference::Dereference {
-            Register(reg_index, reg_loc) = self.dereference_register(reg_index_usize, &reg_loc); reg_index_usize = reg_index as usize;
+        // Check the limit before taking the value out of the register
+        if let Err(mut err) = self.check_stack_push() {
+            create_new_trace!(err);
+            return Err(err);
         }
+        let reg = self.resolve_register(register, reference);
+        if let Err(mut err) = reg {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let Register(reg_index, reg_loc) = reg.unwrap(); let reg_index_usize = reg_index as usize;
         
-        match reg_loc {
-            RegisterLocation::ConstantPool => self.stack.push(self.cpy_constant(reg_index_usize)),
-            RegisterLocation::Accumulator => self.stack.push(create_constant_double(&self.accumulator)),
-            RegisterLocation::Global => {
-                let val = self.mov_global(reg_index_usize);
-                if let Err(mut err) = val {
-                    create_new_trace!(err);
-                    return Err(err);
-                }
-                self.stack.push(val.unwrap());
-            },
-            RegisterLocation::Local => {
-                let val = self.mov_local(reg_index_usize);
-                self.stack.push(val);
-            },
+        let val = match reg_loc {
+            RegisterLocation::ConstantPool => self.cpy_constant(reg_index_usize),
+            RegisterLocation::Accumulator => Ok(create_constant_double(&self.accumulator)),
+            RegisterLocation::Global => self.mov_global(reg_index_usize),
+            RegisterLocation::Local => self.mov_local(reg_index_usize),
+        };
+        if let Err(mut err) = val {
+            create_new_trace!(err);
+            return Err(err);
         }
+        self.stack.push(val.unwrap());
         Ok(())
     }
 }
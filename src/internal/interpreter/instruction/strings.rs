@@ -0,0 +1,89 @@
+use crate::{Interpreter, objects::register::Register, ResurgenceError, create_new_trace};
+
+impl Interpreter {
+    /// Stores the length of a string in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register holding the string
+    pub(crate) fn str_len(&mut self, dst: &Register, src: &Register) -> Result<(), ResurgenceError> {
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().str_len();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores part of a string in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register holding the string
+    /// `start` (`&Register`): register holding the index of the first character
+    /// `length` (`&Register`): register holding the amount of characters
+    pub(crate) fn substr(&mut self, dst: &Register, src: &Register, start: &Register, length: &Register) -> Result<(), ResurgenceError> {
+        let start_res = self.get_int(start);
+        if let Err(mut err) = start_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let length_res = self.get_int(length);
+        if let Err(mut err) = length_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().substr(start_res.unwrap(), length_res.unwrap());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores the character at an index of a string in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `src` (`&Register`): register holding the string
+    /// `index` (`&Register`): register holding the index of the character
+    pub(crate) fn char_at(&mut self, dst: &Register, src: &Register, index: &Register) -> Result<(), ResurgenceError> {
+        let index_res = self.get_int(index);
+        if let Err(mut err) = index_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        let src_res = self.get_constant(src);
+        if let Err(mut err) = src_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = src_res.unwrap().char_at(index_res.unwrap());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
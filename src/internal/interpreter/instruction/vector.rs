@@ -0,0 +1,164 @@
+use crate::{Interpreter, objects::{register::Register, constant::Constant}, ResurgenceError, create_new_trace};
+
+impl Interpreter {
+    /// Stores a new, empty vector in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    pub(crate) fn vec_new(&mut self, dst: &Register) -> Result<(), ResurgenceError> {
+        if let Err(mut err) = self.mov_dst(dst, Constant::Vec(Vec::new())) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores a copy of an element of a vector in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `vec` (`&Register`): register holding the vector
+    /// `index` (`&Register`): register holding the index of the element
+    pub(crate) fn vec_get(&mut self, dst: &Register, vec: &Register, index: &Register) -> Result<(), ResurgenceError> {
+        let index_res = self.get_int(index);
+        if let Err(mut err) = index_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        let vec_res = self.get_constant(vec);
+        if let Err(mut err) = vec_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = vec_res.unwrap().vec_get(index_res.unwrap());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.memory_limits.check_constant(res.as_ref().unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Replaces an element of a vector with a copy of a register
+    ///
+    /// `vec` (`&Register`): register holding the vector
+    /// `index` (`&Register`): register holding the index of the element
+    /// `value` (`&Register`): register holding the new element
+    pub(crate) fn vec_set(&mut self, vec: &Register, index: &Register, value: &Register) -> Result<(), ResurgenceError> {
+        let limits = self.memory_limits;
+        let index_res = self.get_int(index);
+        if let Err(mut err) = index_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let value_res = self.get_constant(value);
+        if let Err(mut err) = value_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = value_res.unwrap();
+        if let Err(mut err) = limits.check_constant(element) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = element.clone();
+
+        let vec_res = self.get_constant_mut(vec);
+        if let Err(mut err) = vec_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = vec_res.unwrap().vec_set(index_res.unwrap(), element);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Appends a copy of a register to the end of a vector
+    ///
+    /// `vec` (`&Register`): register holding the vector
+    /// `value` (`&Register`): register holding the new element
+    pub(crate) fn vec_push(&mut self, vec: &Register, value: &Register) -> Result<(), ResurgenceError> {
+        let limits = self.memory_limits;
+        let value_res = self.get_constant(value);
+        if let Err(mut err) = value_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = value_res.unwrap();
+        if let Err(mut err) = limits.check_constant(element) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let element = element.clone();
+
+        let vec_res = self.get_constant_mut(vec);
+        if let Err(mut err) = vec_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let vec_const = vec_res.unwrap();
+        if let Err(mut err) = limits.check_growth(vec_const, 1) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = vec_const.vec_push(element);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Removes the last element of a vector and stores it in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `vec` (`&Register`): register holding the vector
+    pub(crate) fn vec_pop(&mut self, dst: &Register, vec: &Register) -> Result<(), ResurgenceError> {
+        let vec_res = self.get_constant_mut(vec);
+        if let Err(mut err) = vec_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = vec_res.unwrap().vec_pop();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Stores the length of a vector in the destination register
+    ///
+    /// `dst` (`&Register`): destination register
+    /// `vec` (`&Register`): register holding the vector
+    pub(crate) fn vec_len(&mut self, dst: &Register, vec: &Register) -> Result<(), ResurgenceError> {
+        let vec_res = self.get_constant(vec);
+        if let Err(mut err) = vec_res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = vec_res.unwrap().vec_len();
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Err(mut err) = self.mov_dst(dst, res.unwrap()) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Instant;
+
+use super::Interpreter;
+use crate::{create_new_trace, objects::resurgence_error::ResurgenceErrorKind, ResurgenceError};
+
+/// How many instructions are executed between checks for interrupts and the deadline
+pub(crate) const INTERRUPT_CHECK_INTERVAL: u32 = 1024;
+
+/// Stops an [`Interpreter`] from another thread. Get one with [`Interpreter::interrupt_handle`];
+/// clones control the same interpreter.
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    /// Asks the interpreter to stop. The running code, or the next code to run if none is running,
+    /// stops with an `INTERRUPTED` error within a few instructions. Functions registered by the
+    /// host are not interrupted; execution stops once they return.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if an interrupt was requested and has not stopped the interpreter yet
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Withdraws an interrupt that has not stopped the interpreter yet
+    pub fn clear(&self) {
+        self.interrupted.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns true and clears the interrupt if one was requested
+    fn take(&self) -> bool {
+        self.interrupted.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Interpreter {
+    /// Returns a handle that can stop the code running in this interpreter from another thread
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Sets a point in time after which execution stops with an `INTERRUPTED` error. The deadline
+    /// stays in place for later calls until it is changed; `None` removes it, which is the default.
+    ///
+    /// deadline (`Option<Instant>`): When execution has to stop
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Returns the point in time after which execution stops, if there is one
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns an error if the interpreter was interrupted or its deadline has passed. An
+    /// interrupt only stops execution once.
+    pub(crate) fn check_interrupt(&self) -> Result<(), ResurgenceError> {
+        if self.interrupt.take() {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::INTERRUPTED, "Execution was interrupted!");
+            create_new_trace!(err);
+            return Err(err);
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INTERRUPTED, "Execution passed its deadline!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use super::Interpreter;
+use crate::{
+    create_new_trace,
+    objects::{constant::Constant, register::RegisterLocation, resurgence_error::ResurgenceErrorKind, stackframe::StackFrame},
+    ResurgenceError,
+};
+
+/// Limits on the memory the code running in an [`Interpreter`] may use. Every limit is checked on
+/// its own, so they bound each kind of memory rather than the total.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryLimits {
+    /// Most registers all stack frames may hold together. Every stack frame counts as one
+    /// register as well, so empty frames can not be allocated endlessly.
+    pub max_registers: usize,
+    /// Most global registers
+    pub max_globals: usize,
+    /// Most values the stack may hold
+    pub max_stack: usize,
+    /// Longest string, in bytes, a register may hold. For vectors and maps, this limits the
+    /// bytes of all strings nested in them together.
+    pub max_string_length: usize,
+    /// Most elements a vector or map may hold, counting the elements of nested vectors and maps
+    /// as well when it is copied, so nesting can not be used to get around the limit
+    pub max_vector_size: usize,
+}
+
+impl Default for MemoryLimits {
+    fn default() -> Self {
+        MemoryLimits {
+            max_registers: 1 << 20,
+            max_globals: 1 << 20,
+            max_stack: 1 << 20,
+            max_string_length: 1 << 24,
+            max_vector_size: 1 << 20,
+        }
+    }
+}
+
+impl MemoryLimits {
+    /// Returns limits that are never reached
+    pub fn unlimited() -> MemoryLimits {
+        MemoryLimits {
+            max_registers: usize::MAX,
+            max_globals: usize::MAX,
+            max_stack: usize::MAX,
+            max_string_length: usize::MAX,
+            max_vector_size: usize::MAX,
+        }
+    }
+
+    /// Returns an error if a constant is larger than the limits. Vectors and maps count the
+    /// elements and string bytes of everything nested in them as well. Counting stops at the
+    /// limits, so this is cheap compared to copying the constant.
+    ///
+    /// `constant` (`&Constant`): the constant to check; numbers and booleans always fit
+    pub(crate) fn check_constant(&self, constant: &Constant) -> Result<(), ResurgenceError> {
+        let mut elements: usize = 0;
+        let mut string_bytes: usize = 0;
+        let mut pending = vec![constant];
+        while let Some(constant) = pending.pop() {
+            let res = match constant {
+                Constant::String(val) => {
+                    string_bytes = string_bytes.saturating_add(val.len());
+                    self.check_size(string_bytes, 0, self.max_string_length, "String")
+                },
+                Constant::Vec(val) => {
+                    elements = elements.saturating_add(val.len());
+                    let res = self.check_size(elements, 0, self.max_vector_size, "Vector");
+                    if res.is_ok() {
+                        pending.extend(val.iter().filter(|element| needs_check(element)));
+                    }
+                    res
+                },
+                Constant::Map(val) => {
+                    elements = elements.saturating_add(val.len());
+                    let res = self.check_size(elements, 0, self.max_vector_size, "Map");
+                    if res.is_ok() {
+                        pending.extend(val.values().filter(|element| needs_check(element)));
+                    }
+                    res
+                },
+                _ => Ok(()),
+            };
+            if let Err(mut err) = res {
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error if a string, vector or map would be beyond the limits after growing by
+    /// `extra` elements. Only its own elements are counted, not those of nested vectors and maps.
+    ///
+    /// `constant` (`&Constant`): the string, vector or map about to grow
+    /// `extra` (`usize`): the amount of elements about to be added
+    pub(crate) fn check_growth(&self, constant: &Constant, extra: usize) -> Result<(), ResurgenceError> {
+        match constant {
+            Constant::String(val) => self.check_size(val.len(), extra, self.max_string_length, "String"),
+            Constant::Vec(val) => self.check_size(val.len(), extra, self.max_vector_size, "Vector"),
+            Constant::Map(val) => self.check_size(val.len(), extra, self.max_vector_size, "Map"),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_size(&self, size: usize, extra: usize, max: usize, name: &str) -> Result<(), ResurgenceError> {
+        if size.saturating_add(extra) > max {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_LIMIT, &format!("{} is larger than the limit of {}!", name, max));
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// Returns true for constants whose size is limited
+fn needs_check(constant: &Constant) -> bool {
+    matches!(constant, Constant::String(_) | Constant::Vec(_) | Constant::Map(_))
+}
+
+impl Interpreter {
+    /// Pushes a new stack frame, or returns an error if its registers do not fit in the limits
+    ///
+    /// `size` (`u32`): amount of registers in the frame
+    pub(crate) fn alloc_frame(&mut self, size: u32) -> Result<(), ResurgenceError> {
+        let needed = size as usize + 1;
+        if self.local_registers.saturating_add(needed) > self.memory_limits.max_registers {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_LIMIT, &format!("Can not allocate {} more registers; the limit is {}!", size, self.memory_limits.max_registers));
+            create_new_trace!(err);
+            return Err(err);
+        }
+        self.call_stack.push(StackFrame::from(size));
+        self.local_registers += needed;
+        Ok(())
+    }
+
+    /// Pops up to `amount` stack frames
+    pub(crate) fn free_frames(&mut self, amount: u32) {
+        for _ in 0..amount {
+            match self.call_stack.pop() {
+                Some(frame) => self.local_registers -= frame.registers.len() + 1,
+                None => break,
+            }
+        }
+    }
+
+    /// Adds registers to the last stack frame or to the global registers, or returns an error if
+    /// they do not fit in the limits
+    ///
+    /// `amount` (`u32`): amount of registers to add
+    /// `location` (`&RegisterLocation`): either `Global` or `Local`
+    pub(crate) fn alloc_registers(&mut self, amount: u32, location: &RegisterLocation) -> Result<(), ResurgenceError> {
+        let amount = amount as usize;
+        let (used, max) = match location {
+            RegisterLocation::Global => (self.global.len(), self.memory_limits.max_globals),
+            RegisterLocation::Local => (self.local_registers, self.memory_limits.max_registers),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Attempted to add more memory to an invalid location!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        if used.saturating_add(amount) > max {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_LIMIT, &format!("Can not allocate {} more registers; the limit is {}!", amount, max));
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        if *location == RegisterLocation::Global {
+            self.global.resize(used + amount, Option::None);
+            return Ok(());
+        }
+        let stack_frame = self.ref_stack_frame();
+        if let Err(mut err) = stack_frame {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let registers = &mut stack_frame.unwrap().registers;
+        registers.resize(registers.len() + amount, Option::None);
+        self.local_registers += amount;
+        Ok(())
+    }
+
+    /// Removes up to `amount` registers from the last stack frame or from the global registers
+    ///
+    /// `amount` (`u32`): amount of registers to remove
+    /// `location` (`&RegisterLocation`): either `Global` or `Local`
+    pub(crate) fn free_registers(&mut self, amount: u32, location: &RegisterLocation) -> Result<(), ResurgenceError> {
+        let amount = amount as usize;
+        let registers = match location {
+            RegisterLocation::Global => &mut self.global,
+            RegisterLocation::Local => match self.ref_stack_frame() {
+                Ok(stack_frame) => &mut stack_frame.registers,
+                Err(mut err) => {
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            },
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can not allocate more memory outside of local and global memory.");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let removed = amount.min(registers.len());
+        registers.truncate(registers.len() - removed);
+        if *location == RegisterLocation::Local {
+            self.local_registers -= removed;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the stack can not hold another value
+    pub(crate) fn check_stack_push(&self) -> Result<(), ResurgenceError> {
+        if self.stack.len() >= self.memory_limits.max_stack {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_LIMIT, &format!("The stack can not hold more than {} values!", self.memory_limits.max_stack));
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
@@ -1,19 +1,41 @@
+use std::collections::BTreeSet;
 use std::io::Error;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 use std::result::Result;
 pub(crate) mod execution_engine;
 pub(crate) mod imports;
 mod instruction;
+mod interrupt;
+mod limits;
+mod pool;
+mod shared;
+mod snapshot;
 mod utils;
 
+use self::execution_engine::Suspension;
 use self::imports::RustFunc;
+pub use self::imports::ImportAudit;
+pub use self::interrupt::InterruptHandle;
+pub(crate) use self::interrupt::INTERRUPT_CHECK_INTERVAL;
+pub use self::limits::MemoryLimits;
+pub use self::pool::{InterpreterPool, PoolResult, PoolTask};
+pub use self::shared::SharedCode;
 use super::super::constant::Constant;
 use crate::bytecode::codereader;
+use crate::bytecode::integrity::ReadOptions;
+use crate::bytecode::view::LazyConstantPool;
 use crate::objects::codeholder::CodeHolder;
+use crate::objects::metadata::SemanticVersion;
 use crate::objects::stackframe::StackFrame;
 
 pub mod resolve_imports; 
 
 /// `Interpreter`: Built-in Register Virtual Machine
+///
+/// Interpreters are `Send` and `Sync`, so each can run on its own thread, for example through an
+/// [`InterpreterPool`]. Registered functions are plain function pointers that can not capture
+/// state, so any state they share between threads has to be synchronized by the host.
 pub struct Interpreter {
     /// Special register used for fast math
     accumulator: f64,
@@ -22,34 +44,108 @@ pub struct Interpreter {
     call_stack: Vec<StackFrame>,
     /// Holds temporary values
     stack: Vec<Constant>,
-    /// The object that holds the bytecode to iterate over
-    code_holder: CodeHolder,
+    /// The object that holds the bytecode to iterate over; never modified, so it can be shared
+    /// with other interpreters
+    code_holder: Arc<CodeHolder>,
     /// Holds global variables
     global: Vec<Option<Constant>>,
     /// All Rust functions registered before runtime
     rust_functions: Vec<RustFunc>,
+    /// Have imports been resolved?
+    resolved_imports: bool,
+    /// Has the code been verified? Code that was not must never run
+    verified: bool,
+    /// Converts bytecode import indices into indices of `rust_functions`
+    byte_to_interal: Vec<u64>,
+    /// Capabilities the code may import functions from
+    granted_capabilities: BTreeSet<String>,
+    /// Where the code stopped, if it ran out of fuel or was interrupted
+    suspended: Option<Suspension>,
     /// Defines how many times we've recursed
     current_recursion_depth: usize,
     /// Defines the recursion limit
     max_recursion_depth: usize,
+    /// How many more instructions may be executed, or `None` for no limit
+    fuel: Option<u64>,
+    /// Lets other threads stop the execution
+    interrupt: InterruptHandle,
+    /// When execution has to stop, or `None` for no limit
+    deadline: Option<Instant>,
+    /// Limits on the memory the code may use
+    memory_limits: MemoryLimits,
+    /// Registers in all stack frames, plus one for every frame, counted against the memory limits
+    local_registers: usize,
+    /// Version of the host API, checked against the module metadata when resolving imports
+    host_api_version: Option<SemanticVersion>,
+    /// Constants decoded on first use, replacing the constant pool of the CodeHolder
+    lazy_constants: Option<Arc<LazyConstantPool>>,
+    /// Hash of the code, computed the first time a snapshot is taken or restored
+    code_hash: OnceLock<u64>,
 }
 
 impl Interpreter {
     /// Creates a new `Interpreter` instance using a given CodeHolder
     pub fn from(ch: CodeHolder) -> Interpreter {
+        Self::from_shared(Arc::new(ch))
+    }
+
+    /// Creates a new `Interpreter` instance running code that may be shared with other
+    /// interpreters. Each interpreter has its own registers, stack, registered functions and
+    /// resolved imports, while the code and constants are only kept in memory once.
+    ///
+    /// `code` (`impl Into<SharedCode>`): the code to run, either an `Arc<CodeHolder>` or the
+    /// [`SharedCode`] of another interpreter, which keeps lazily loaded constants
+    pub fn from_shared(code: impl Into<SharedCode>) -> Interpreter {
+        let SharedCode { holder: code, lazy_constants, code_hash } = code.into();
         Interpreter {
             accumulator: 0.0,
             accumulator_as_const: Constant::Double(0.0),
             call_stack: Vec::new(),
             stack: Vec::new(),
-            code_holder: ch,
+            code_holder: code,
             global: Vec::new(),
             rust_functions: Vec::new(),
+            resolved_imports: false,
+            verified: false,
+            byte_to_interal: Vec::new(),
+            granted_capabilities: BTreeSet::new(),
+            suspended: None,
             current_recursion_depth: 0,
             max_recursion_depth: 1000,
+            fuel: None,
+            interrupt: InterruptHandle::default(),
+            deadline: None,
+            memory_limits: MemoryLimits::default(),
+            local_registers: 0,
+            host_api_version: None,
+            lazy_constants,
+            code_hash,
         }
     }
 
+    /// Parses bytecode held in `data` and creates an [`Interpreter`] instance without copying the
+    /// constant pool out of the buffer. Each constant is decoded the first time the code uses it.
+    ///
+    /// `data` can be any buffer the interpreter can own, such as a `Vec<u8>`, an `Arc<[u8]>` or a
+    /// memory-mapped file. Checksums that are present are validated. Compressed bytecode is
+    /// rejected; use [`Interpreter::from`] with [`crate::bytecode::read_bytecode`] for it.
+    pub fn from_bytes<B: AsRef<[u8]> + Send + Sync + 'static>(data: B) -> Result<Interpreter, Error> {
+        Self::from_bytes_with_options(data, &ReadOptions::default())
+    }
+
+    /// Creates an [`Interpreter`] from bytecode held in `data` like [`Interpreter::from_bytes`],
+    /// failing with an [`std::io::ErrorKind::InvalidData`] error if the integrity checks required
+    /// by `options` fail. Checksums and signatures are verified over the buffer without copying it.
+    pub fn from_bytes_with_options<B: AsRef<[u8]> + Send + Sync + 'static>(
+        data: B,
+        options: &ReadOptions,
+    ) -> Result<Interpreter, Error> {
+        let (holder, constants) = LazyConstantPool::load(data, options)?;
+        let mut interpreter = Self::from(holder);
+        interpreter.lazy_constants = Some(Arc::new(constants));
+        Ok(interpreter)
+    }
+
     /// Reads a file at a given path, parses it, and creates an [`Interpreter`] instance.
     ///
     /// This is a convenience wrapper for [`crate::api::codereader::read_bytecode_file`] and
@@ -58,10 +154,83 @@ impl Interpreter {
         Ok(Self::from(codereader::read_bytecode_file(path)?))
     }
 
+    /// Creates a new `Interpreter` instance running the same code as this one, without copying the
+    /// code or constants. The registered functions, granted capabilities and limits are copied;
+    /// imports are resolved again, and the registers, stack, fuel and deadline start out empty.
+    pub fn share_code(&self) -> Interpreter {
+        let mut interpreter = Self::from_shared(self.code());
+        interpreter.rust_functions = self.rust_functions.clone();
+        interpreter.granted_capabilities = self.granted_capabilities.clone();
+        interpreter.max_recursion_depth = self.max_recursion_depth;
+        interpreter.memory_limits = self.memory_limits;
+        interpreter.host_api_version = self.host_api_version;
+        interpreter
+    }
+
+    /// Returns the code the interpreter runs, along with its lazily loaded constants, which can
+    /// be given to [`Interpreter::from_shared`] to run it in other interpreters
+    pub fn code(&self) -> SharedCode {
+        SharedCode {
+            holder: Arc::clone(&self.code_holder),
+            lazy_constants: self.lazy_constants.clone(),
+            code_hash: self.code_hash.clone(),
+        }
+    }
+
+    /// Clears the registers, stack frames, stack and accumulator, and forgets where stopped code
+    /// has to continue, so the code can run again as if the interpreter was just created. The
+    /// code, registered functions, resolved imports, granted capabilities and limits are kept, as
+    /// are the fuel, deadline and interrupt handles.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.accumulator_as_const = Constant::Double(0.0);
+        self.call_stack.clear();
+        self.stack.clear();
+        self.global.clear();
+        self.suspended = None;
+        self.current_recursion_depth = 0;
+        self.local_registers = 0;
+    }
+
     /// Modifies the max recursion depth
     ///
     /// new_depth (`usize`): The new max depth
     pub fn set_max_depth(&mut self, new_depth: usize) {
         self.max_recursion_depth = new_depth;
     }
+
+    /// Limits how many more instructions the interpreter may execute. Once the fuel runs out,
+    /// execution stops with an error. `None` removes the limit, which is the default.
+    ///
+    /// fuel (`Option<u64>`): The amount of instructions that may be executed
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.fuel = fuel;
+    }
+
+    /// Returns how many more instructions the interpreter may execute, or `None` if there is no
+    /// limit
+    pub fn remaining_fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Sets the limits on the memory the code may use. Allocations beyond them stop execution
+    /// with an error. See [`MemoryLimits::default`] for the limits used if this is never called.
+    ///
+    /// limits (`MemoryLimits`): The new limits
+    pub fn set_memory_limits(&mut self, limits: MemoryLimits) {
+        self.memory_limits = limits;
+    }
+
+    /// Returns the limits on the memory the code may use
+    pub fn memory_limits(&self) -> MemoryLimits {
+        self.memory_limits
+    }
+
+    /// Sets the version of the API the host provides. Once set, [`Interpreter::resolve_imports`]
+    /// rejects modules whose metadata requires an incompatible host API.
+    ///
+    /// version (`SemanticVersion`): The version of the host API
+    pub fn set_host_api_version(&mut self, version: SemanticVersion) {
+        self.host_api_version = Some(version);
+    }
 }
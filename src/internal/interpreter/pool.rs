@@ -0,0 +1,148 @@
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::thread;
+
+use super::Interpreter;
+use crate::{
+    internal::execution_engine::ExecutionEngine, objects::codeholder::CodeHolder, ResurgenceError,
+};
+
+// The pool moves interpreters and their results to other threads, so make sure nothing stored in
+// them (code, constants, registered functions or errors) stops that
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Interpreter>();
+    assert_send_sync::<CodeHolder>();
+    assert_send_sync::<ResurgenceError>();
+};
+
+/// Where a [`PoolTask`] starts executing
+enum Entry {
+    Instruction(usize),
+    Function(String),
+}
+
+/// An [`Interpreter`] waiting to be run by an [`InterpreterPool`]
+pub struct PoolTask {
+    interpreter: Interpreter,
+    entry: Entry,
+    fuel: Option<u64>,
+}
+
+impl PoolTask {
+    /// Creates a task that runs the code from the instruction at `start_index`
+    ///
+    /// `interpreter` (`Interpreter`): the interpreter to run
+    /// `start_index` (`usize`): index of the first instruction to execute
+    pub fn from_instruction(interpreter: Interpreter, start_index: usize) -> PoolTask {
+        PoolTask {
+            interpreter,
+            entry: Entry::Instruction(start_index),
+            fuel: None,
+        }
+    }
+
+    /// Creates a task that runs an exported function
+    ///
+    /// `interpreter` (`Interpreter`): the interpreter to run
+    /// `func_name` (`&str`): name of the exported function
+    pub fn from_function(interpreter: Interpreter, func_name: &str) -> PoolTask {
+        PoolTask {
+            interpreter,
+            entry: Entry::Function(String::from(func_name)),
+            fuel: None,
+        }
+    }
+
+    /// Gives the task its own fuel, replacing the fuel of its interpreter once it starts. Tasks
+    /// without fuel keep whatever fuel their interpreter has.
+    ///
+    /// `fuel` (`u64`): the amount of instructions the task may execute
+    pub fn set_fuel(&mut self, fuel: u64) {
+        self.fuel = Some(fuel);
+    }
+
+    fn run(mut self) -> PoolResult {
+        if self.fuel.is_some() {
+            self.interpreter.set_fuel(self.fuel);
+        }
+        let result = match &self.entry {
+            Entry::Instruction(start_index) => self.interpreter.execute_instruction(*start_index),
+            Entry::Function(func_name) => self.interpreter.execute_function(func_name),
+        };
+        PoolResult {
+            interpreter: self.interpreter,
+            result,
+        }
+    }
+}
+
+/// The outcome of a [`PoolTask`]
+pub struct PoolResult {
+    /// The interpreter the task ran, so its remaining fuel can be read or it can be run again
+    pub interpreter: Interpreter,
+    /// What executing the code returned
+    pub result: Result<(), ResurgenceError>,
+}
+
+/// Runs many independent interpreters across a number of threads
+pub struct InterpreterPool {
+    workers: usize,
+}
+
+impl Default for InterpreterPool {
+    /// Creates a pool with one worker for every thread the machine can run at once
+    fn default() -> Self {
+        let workers = thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+        InterpreterPool::new(workers)
+    }
+}
+
+impl InterpreterPool {
+    /// Creates a pool that runs up to `workers` tasks at once
+    ///
+    /// `workers` (`usize`): the amount of threads; at least one is always used
+    pub fn new(workers: usize) -> InterpreterPool {
+        InterpreterPool {
+            workers: workers.max(1),
+        }
+    }
+
+    /// Returns the most threads the pool runs tasks on
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Runs every task and returns their results in the same order as the tasks. Errors in one
+    /// task do not affect the others. Returns once all tasks are done; if a registered function
+    /// panics, the panic is passed on after the other tasks finish.
+    ///
+    /// `tasks` (`Vec<PoolTask>`): the tasks to run
+    pub fn run(&self, tasks: Vec<PoolTask>) -> Vec<PoolResult> {
+        let count = tasks.len();
+        let queue = Mutex::new(tasks.into_iter().enumerate());
+        let results: Mutex<Vec<Option<PoolResult>>> = Mutex::new((0..count).map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(count) {
+                scope.spawn(|| loop {
+                    // Only hold the lock while taking a task, so a panicking task can not poison it
+                    let next = queue.lock().unwrap().next();
+                    let (index, task) = match next {
+                        Some(next) => next,
+                        None => break,
+                    };
+                    let result = task.run();
+                    results.lock().unwrap()[index] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.expect("every task is run exactly once"))
+            .collect()
+    }
+}
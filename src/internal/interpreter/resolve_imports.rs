@@ -4,26 +4,67 @@ use super::Interpreter;
 
 impl Interpreter {
     /// Resolves any Rust functions used in the bytecode file by creating a "compatibility layer" based on indicies
+    ///
+    /// Fails without resolving anything if a host API version was set and the module's metadata requires an incompatible one,
+    /// or if the code refers to instructions, constants or imports that do not exist (see [`crate::CodeHolder::verify`]).
+    /// Also fails if the code imports a function whose capability has not been granted (see [`Interpreter::grant_capability`])
     #[inline(never)]
     pub fn resolve_imports(&mut self) -> Result<(), ResurgenceError> {
+        if let Err(mut err) = self.verify() {
+            create_new_trace!(err);
+            return Err(err);
+        }
+
+        if let Some(host_api) = &self.host_api_version {
+            if let Err(mut err) = self.code_holder.check_host_api(host_api) {
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+
+        // Resolve into a separate table, so a failed attempt leaves nothing behind and can be retried
+        // after registering the missing functions or granting the missing capabilities
         let imports = &self.code_holder.imports;
-        self.code_holder.byte_to_interal.reserve(imports.len()); // We know the amount of functions being used, so let's take advantage of that
-        for (_, name) in imports.iter().enumerate() {
-            let mut success = false;
-            for (internal_index, internal_name) in self.rust_functions.iter().enumerate() {
-                if *internal_name.name == *name {
-                    self.code_holder.byte_to_interal.push(internal_index as u64);
-                    success = true;
+        let mut byte_to_interal = Vec::with_capacity(imports.len()); // We know the amount of functions being used, so let's take advantage of that
+        for name in imports.iter() {
+            let internal_index = match self.find_function_index(name) {
+                Some(index) => index,
+                None => {
+                    // Failed to find a matching import
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::MISSING_IMPORTS, &format!("Could not find function {} for it has not been registered", *name));
+                    create_new_trace!(err);
+                    return Err(err);
                 }
-            }
-            if !success {
-                // Failed to find a matching import
-                let mut err = ResurgenceError::from(ResurgenceErrorKind::MISSING_IMPORTS, &format!("Could not find function {} for it has not been registered", *name));
+            };
+            let function = &self.rust_functions[internal_index];
+            if !self.may_import(function) {
+                let capability = function.capability.as_deref().unwrap_or_default();
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::PERMISSION_DENIED, &format!("Function {} needs the capability {}, which has not been granted", *name, capability));
                 create_new_trace!(err);
                 return Err(err);
             }
-        } 
-        self.code_holder.resolved_imports = true;
+            byte_to_interal.push(internal_index as u64);
+        }
+        self.byte_to_interal = byte_to_interal;
+        self.resolved_imports = true;
         Result::Ok(())
     }
+
+    /// Checks that the code only refers to instructions, constants and imports that exist (see
+    /// [`crate::CodeHolder::verify`]). The code is never modified, so it is only checked once.
+    pub(crate) fn verify(&mut self) -> Result<(), ResurgenceError> {
+        if self.verified {
+            return Ok(());
+        }
+        let constant_count = match &self.lazy_constants {
+            Some(constants) => constants.count(),
+            None => self.code_holder.constant_pool.len(),
+        };
+        if let Err(mut err) = self.code_holder.verify_code(constant_count) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        self.verified = true;
+        Ok(())
+    }
 }
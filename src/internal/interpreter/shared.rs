@@ -0,0 +1,51 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::bytecode::view::LazyConstantPool;
+use crate::objects::codeholder::CodeHolder;
+
+/// Code that can run in many [`crate::Interpreter`]s without being copied, returned by
+/// [`crate::Interpreter::code`] and taken by [`crate::Interpreter::from_shared`].
+///
+/// Interpreters created with [`crate::Interpreter::from_bytes`] keep their constants in the
+/// bytecode buffer rather than in the [`CodeHolder`], so the handle carries them along with it.
+/// Cloning it only clones the `Arc`s.
+#[derive(Clone)]
+pub struct SharedCode {
+    pub(super) holder: Arc<CodeHolder>,
+    pub(super) lazy_constants: Option<Arc<LazyConstantPool>>,
+    pub(super) code_hash: OnceLock<u64>,
+}
+
+impl SharedCode {
+    /// Returns the code holder. For lazily loaded code its constant pool is empty, since the
+    /// constants are only decoded from the bytecode buffer when the code uses them.
+    pub fn holder(&self) -> &Arc<CodeHolder> {
+        &self.holder
+    }
+
+    /// Returns true if both handles share the same code and constants
+    pub fn ptr_eq(&self, other: &SharedCode) -> bool {
+        let same_constants = match (&self.lazy_constants, &other.lazy_constants) {
+            (Some(constants), Some(other_constants)) => Arc::ptr_eq(constants, other_constants),
+            (None, None) => true,
+            _ => false,
+        };
+        Arc::ptr_eq(&self.holder, &other.holder) && same_constants
+    }
+}
+
+impl From<Arc<CodeHolder>> for SharedCode {
+    fn from(holder: Arc<CodeHolder>) -> Self {
+        SharedCode {
+            holder,
+            lazy_constants: None,
+            code_hash: OnceLock::new(),
+        }
+    }
+}
+
+impl From<CodeHolder> for SharedCode {
+    fn from(holder: CodeHolder) -> Self {
+        Self::from(Arc::new(holder))
+    }
+}
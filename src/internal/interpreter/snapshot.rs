@@ -0,0 +1,100 @@
+use std::io::Error;
+
+use super::Interpreter;
+use crate::bytecode::snapshot::{code_hash, read_snapshot, write_snapshot, VmState};
+use crate::objects::constant::Constant;
+
+impl Interpreter {
+    /// Saves the runtime state to bytes: the global registers, stack frames, stack, accumulator
+    /// and, if the code ran out of fuel or was interrupted, where it stopped. [`Interpreter::restore`]
+    /// loads it into an interpreter running the same code.
+    ///
+    /// Registered functions, granted capabilities, limits, fuel and the deadline are not saved.
+    pub fn snapshot(&self) -> Result<Vec<u8>, Error> {
+        let hash = self.code_hash()?;
+        write_snapshot(
+            hash,
+            self.accumulator,
+            &self.global,
+            &self.call_stack,
+            &self.stack,
+            self.suspended.as_ref(),
+        )
+    }
+
+    /// Replaces the runtime state with one saved by [`Interpreter::snapshot`]. If the code was
+    /// stopped when the snapshot was taken, it can be continued with [`Interpreter::resume`].
+    ///
+    /// Fails without changing anything if the snapshot is corrupted, was taken from different
+    /// code, or does not fit in the memory limits.
+    ///
+    /// `data` (`&[u8]`): the snapshot
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), Error> {
+        let hash = self.code_hash()?;
+        let state = read_snapshot(data, hash)?;
+        let local_registers = self.check_snapshot(&state)?;
+
+        self.accumulator = state.accumulator;
+        self.accumulator_as_const = Constant::Double(state.accumulator);
+        self.global = state.global;
+        self.call_stack = state.call_stack;
+        self.stack = state.stack;
+        self.suspended = state.suspended;
+        self.current_recursion_depth = 0;
+        self.local_registers = local_registers;
+        Ok(())
+    }
+
+    /// Checks that a restored state fits in the limits and only returns to instructions of the
+    /// code, and returns the amount of local registers it uses
+    fn check_snapshot(&self, state: &VmState) -> Result<usize, Error> {
+        let limits = &self.memory_limits;
+        let local_registers = state
+            .call_stack
+            .iter()
+            .fold(0usize, |total, frame| total.saturating_add(frame.registers.len() + 1));
+        let constants_fit = state
+            .global
+            .iter()
+            .chain(state.call_stack.iter().flat_map(|frame| frame.registers.iter()))
+            .flatten()
+            .chain(state.stack.iter())
+            .all(|constant| limits.check_constant(constant).is_ok());
+        if state.global.len() > limits.max_globals
+            || local_registers > limits.max_registers
+            || state.stack.len() > limits.max_stack
+            || !constants_fit
+        {
+            return Err(Error::other("Snapshot does not fit in the memory limits"));
+        }
+
+        if let Some(suspension) = &state.suspended {
+            let length = self.code_holder.instructions.len();
+            // Return addresses point after a call, so they are never 0
+            let returns_valid = suspension.returns.iter().all(|ret| (1..=length).contains(ret));
+            if suspension.index > length || !returns_valid {
+                return Err(Error::other("Snapshot continues at an instruction outside of the code"));
+            }
+            if suspension.returns.len() > self.max_recursion_depth {
+                return Err(Error::other("Snapshot has calls nested deeper than the recursion limit"));
+            }
+        }
+        Ok(local_registers)
+    }
+
+    /// Returns the hash of the code, computing it on first use
+    fn code_hash(&self) -> Result<u64, Error> {
+        if let Some(hash) = self.code_hash.get() {
+            return Ok(*hash);
+        }
+        let constants: Vec<&Constant> = match &self.lazy_constants {
+            Some(pool) => (0..pool.count())
+                .filter_map(|index| pool.get(index).transpose())
+                .collect::<Result<_, _>>()?,
+            None => self.code_holder.constant_pool.iter().collect(),
+        };
+        let hash = code_hash(&self.code_holder, &constants)?;
+        let _ = self.code_hash.set(hash);
+        Ok(hash)
+    }
+}
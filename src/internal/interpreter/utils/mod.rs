@@ -1,7 +1,7 @@
 use crate::{
     objects::{
         constant::Constant,
-        register::{Register, RegisterLocation},
+        register::{Register, RegisterLocation, RegisterReference},
         stackframe::StackFrame, resurgence_error::ResurgenceErrorKind,
     },
     Interpreter, ResurgenceError, create_new_trace,
@@ -29,195 +29,314 @@ impl Interpreter {
     /// Copies a value from a global register and returns that value, or an error if:
     /// - Register is beyond bounds
     /// - Register contains a `Option::None` instead of `Option::Some`
+    /// - The value is larger than the memory limits allow
     pub(crate) fn cpy_global(&mut self, index: usize) -> Result<Constant, ResurgenceError> {
-        if index >= self.global.len() {
-            let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Register beyond bounds!");
+        let res = self.ref_global(index);
+        if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        let reg = self.global[index].clone();
-        if let None = reg {
-            let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_ADDRESS_NONE, "Global register None!");
+        let res = self.cpy_checked(res.unwrap());
+        if let Err(mut err) = res {
             create_new_trace!(err);
             return Err(err);
         }
-        Ok(reg.unwrap())
+        res
     }
 
-    /// Returns a reference to a global register
+    /// Returns a reference to a global register, or an error if:
+    /// - Register is beyond bounds
+    /// - Register contains a `Option::None` instead of `Option::Some`
     ///
     /// `index` (`usize`): index of register
-    pub(crate) fn ref_global(&self, index: usize) -> &Constant {
-        self.global[index].as_ref().expect("Non-existant register!")
+    pub(crate) fn ref_global(&self, index: usize) -> Result<&Constant, ResurgenceError> {
+        match self.global.get(index) {
+            Some(Some(constant)) => Ok(constant),
+            Some(None) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_ADDRESS_NONE, "Global register None!");
+                create_new_trace!(err);
+                Err(err)
+            },
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Register beyond bounds!");
+                create_new_trace!(err);
+                Err(err)
+            },
+        }
     }
 
     /// Moves a local register
     ///
     /// `index` (`usize`): index of register
-    pub(crate) fn mov_local(&mut self, index: usize) -> Constant {
+    pub(crate) fn mov_local(&mut self, index: usize) -> Result<Constant, ResurgenceError> {
         let stack_frame = self.ref_stack_frame();
-        stack_frame.mov_register(index)
+        if let Err(mut err) = stack_frame {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = stack_frame.unwrap().mov_register(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
     }
 
     /// Copies a local register
     ///
     /// `index` (`usize`): index of register
-    pub(crate) fn cpy_local(&mut self, index: usize) -> Constant {
-        let stack_frame = self.ref_stack_frame();
-        stack_frame.cpy_register(index)
+    pub(crate) fn cpy_local(&mut self, index: usize) -> Result<Constant, ResurgenceError> {
+        let res = self.ref_local(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = self.cpy_checked(res.unwrap());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
     }
 
     /// References a local register
     ///
     /// `index` (`usize`): index of register
-    pub(crate) fn ref_local(&self, index: usize) -> &Constant {
+    pub(crate) fn ref_local(&self, index: usize) -> Result<&Constant, ResurgenceError> {
         let stack_frame = self.ref_stack_frame_imut();
-        stack_frame.ref_register(index)
+        if let Err(mut err) = stack_frame {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let res = stack_frame.unwrap().ref_register(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
     }
 
     /// Copies a constant from the constant pool
     ///
     /// `index` (`usize`): index of the constant
-    pub(crate) fn cpy_constant(&self, index: usize) -> Constant {
-        unsafe {
-            self.code_holder.constant_pool.get_unchecked(index).clone()
+    pub(crate) fn cpy_constant(&self, index: usize) -> Result<Constant, ResurgenceError> {
+        let res = self.ref_constant(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
         }
+        let res = self.cpy_checked(res.unwrap());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
     }
 
-    /// References a constant from the constant pool
+    /// Copies a constant, or returns an error if it is larger than the memory limits allow
+    fn cpy_checked(&self, constant: &Constant) -> Result<Constant, ResurgenceError> {
+        if let Err(mut err) = self.memory_limits.check_constant(constant) {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(constant.clone())
+    }
+
+    /// References a constant from the constant pool, or returns an error if it does not exist
     /// 
     /// `index` (`usize`): index of the constant
-    pub(crate) fn ref_constant(&self, index: usize) -> &Constant {
+    pub(crate) fn ref_constant(&self, index: usize) -> Result<&Constant, ResurgenceError> {
         /*
-            Since the constant pool is a fixed size, the Resurgence code generator should never allow any non-existant constants
+            Constant registers in the code are verified before it runs, but addresses can point anywhere, so the index has to be checked
         */
-        unsafe {
-            self.code_holder.constant_pool.get_unchecked(index)
+        let constant = match &self.lazy_constants {
+            Some(constants) => match constants.get(index) {
+                Ok(constant) => constant,
+                Err(error) => {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, &format!("Can not decode constant {}: {}", index, error));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            },
+            None => self.code_holder.constant_pool.get(index),
+        };
+        match constant {
+            Some(constant) => Ok(constant),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Constant does not exist!");
+                create_new_trace!(err);
+                Err(err)
+            }
         }
     }
 
-    /// Returns a reference to the last stackframe
-    pub(crate) fn ref_stack_frame(&mut self) -> &mut StackFrame {
-        self.call_stack.last_mut().unwrap()
+    /// Returns a reference to the last stackframe, or an error if there is none
+    pub(crate) fn ref_stack_frame(&mut self) -> Result<&mut StackFrame, ResurgenceError> {
+        match self.call_stack.last_mut() {
+            Some(stack_frame) => Ok(stack_frame),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "No stack frame; use Alloc first!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
     }
 
-    pub(crate) fn ref_stack_frame_imut(&self) -> &StackFrame {
-        self.call_stack.last().unwrap()
+    /// Returns an immutable reference to the last stackframe, or an error if there is none
+    pub(crate) fn ref_stack_frame_imut(&self) -> Result<&StackFrame, ResurgenceError> {
+        match self.call_stack.last() {
+            Some(stack_frame) => Ok(stack_frame),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "No stack frame; use Alloc first!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
     }
 
     pub(crate) fn accu_const(&mut self) {
-        let val = match self.accumulator_as_const {
-                    Constant::Double(ref mut val) => val,
-                    _ => unreachable!(),
-                };
-        *val = self.accumulator
-    }
-
-    pub(crate) fn dereference_register(&mut self, index: usize, reg_loc: &RegisterLocation) -> Register {
-        match reg_loc {
-            RegisterLocation::Global => {
-                let register = self.ref_global(index); // get the register that stores the address
-                if let Constant::Address(dref_reg) = register {
-                    *dref_reg
-                } else {
-                    panic!("Dereferencing requires address")
-                }
+        self.accumulator_as_const = Constant::Double(self.accumulator);
+    }
+
+    /// Returns the register an address register points to, or an error if:
+    /// - Register is not a global or local register
+    /// - Register does not hold an address
+    pub(crate) fn dereference_register(&mut self, index: usize, reg_loc: &RegisterLocation) -> Result<Register, ResurgenceError> {
+        let register = match reg_loc {
+            RegisterLocation::Global => self.ref_global(index), // get the register that stores the address
+            RegisterLocation::Local => self.ref_local(index),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only dereference local or global registers!");
+                create_new_trace!(err);
+                return Err(err);
             }
-            RegisterLocation::Local => {
-                let stack_frame = self.ref_stack_frame(); // reference the last stackframe
-                let src_reg = stack_frame.ref_register(index); // get the register that stores the address
-                if let Constant::Address(dref_reg) = src_reg {
-                    *dref_reg
-                } else {
-                    panic!("Must dereference a address!")
-                }
+        };
+        match register {
+            Ok(Constant::Address(dref_reg)) => Ok(*dref_reg),
+            Ok(_) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Dereferencing requires an address!");
+                create_new_trace!(err);
+                Err(err)
+            },
+            Err(mut err) => {
+                create_new_trace!(err);
+                Err(err)
             }
-            _ => panic!("Invalid dereference operation!"),
         }
     }
 
-    /// Takes 2 `Register` objects, and returns 2 `Constant` objects
-    pub(crate) fn get_constants(&mut self, reg_1: &Register, reg_2: &Register) -> (&Constant, &Constant) {
-        let Register(index_1, loc_1) = reg_1;
-        let Register(index_2, loc_2) = reg_2;
-        let index_1_usize = *index_1 as usize;
-        let index_2_usize = *index_2 as usize;
+    /// Returns the register an instruction operates on: the register itself, or the register it
+    /// points to if it has to be dereferenced
+    pub(crate) fn resolve_register(&mut self, reg: &Register, reference: &RegisterReference) -> Result<Register, ResurgenceError> {
+        if *reference != RegisterReference::Dereference {
+            return Ok(*reg);
+        }
+        let res = self.dereference_register(reg.0 as usize, &reg.1);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
+    }
 
-        let const_1: &Constant;
-        let const_2: &Constant;
-        match (loc_1, loc_2) {
-            (RegisterLocation::ConstantPool, RegisterLocation::ConstantPool) => {
-                const_1 = self.ref_constant(index_1_usize);
-                const_2 = self.ref_constant(index_2_usize);
-            },
-            (RegisterLocation::ConstantPool, RegisterLocation::Accumulator) => {
-                self.accu_const();
-                const_1 = self.ref_constant(index_1_usize);
-                const_2 = &self.accumulator_as_const;
-            },
-            (RegisterLocation::ConstantPool, RegisterLocation::Global) => {
-                const_1 = self.ref_constant(index_1_usize);
-                const_2 = self.ref_global(index_2_usize);
-            },
-            (RegisterLocation::ConstantPool, RegisterLocation::Local) => {
-                const_1 = self.ref_constant(index_1_usize);
-                const_2 = self.ref_local(index_2_usize);
-            },
-            (RegisterLocation::Accumulator, RegisterLocation::ConstantPool) => {
-                self.accu_const();
-                const_1 = &self.accumulator_as_const;
-                const_2 = self.ref_constant(index_2_usize);
-            },
-            (RegisterLocation::Accumulator, RegisterLocation::Accumulator) => {
-                self.accu_const();
-                const_1 = &self.accumulator_as_const;
-                const_2 = &self.accumulator_as_const;
-            },
-            (RegisterLocation::Accumulator, RegisterLocation::Global) => {
-                self.accu_const();
-                const_1 = &self.accumulator_as_const;
-                const_2 = self.ref_global(index_2_usize);
-            },
-            (RegisterLocation::Accumulator, RegisterLocation::Local) => {
-                self.accu_const();
-                const_1 = &self.accumulator_as_const;
-                const_2 = self.ref_local(index_2_usize);
-            },
-            (RegisterLocation::Global, RegisterLocation::ConstantPool) => {
-                const_1 = self.ref_global(index_1_usize);
-                const_2 = self.ref_constant(index_2_usize);
-            },
-            (RegisterLocation::Global, RegisterLocation::Accumulator) => {
-                self.accu_const();
-                const_1 = self.ref_global(index_1_usize);
-                const_2 = &self.accumulator_as_const;
-            },
-            (RegisterLocation::Global, RegisterLocation::Global) => {
-                const_1 = self.ref_global(index_1_usize);
-                const_2 = self.ref_global(index_2_usize);
-            },
-            (RegisterLocation::Global, RegisterLocation::Local) => {
-                const_1 = self.ref_global(index_1_usize);
-                const_2 = self.ref_local(index_2_usize);
-            },
-            (RegisterLocation::Local, RegisterLocation::ConstantPool) => {
-                const_1 = self.ref_local(index_1_usize);
-                const_2 = self.ref_constant(index_2_usize);
-            },
-            (RegisterLocation::Local, RegisterLocation::Accumulator) => {
-                self.accu_const();
-                const_1 = self.ref_local(index_1_usize);
-                const_2 = &self.accumulator_as_const;
+    /// Takes a `Register` object, and returns a reference to the `Constant` it holds, or an error
+    /// if the register does not exist or holds `Option::None`
+    pub(crate) fn get_constant(&mut self, reg: &Register) -> Result<&Constant, ResurgenceError> {
+        if reg.1 == RegisterLocation::Accumulator {
+            self.accu_const();
+        }
+        self.ref_register(reg)
+    }
+
+    /// Returns a reference to the `Constant` a `Register` holds. The accumulator is read from
+    /// `accumulator_as_const`, so `accu_const` must be called first.
+    fn ref_register(&self, reg: &Register) -> Result<&Constant, ResurgenceError> {
+        let Register(index, loc) = reg;
+        let index_usize = *index as usize;
+
+        let res = match loc {
+            RegisterLocation::ConstantPool => self.ref_constant(index_usize),
+            RegisterLocation::Accumulator => Ok(&self.accumulator_as_const),
+            RegisterLocation::Global => self.ref_global(index_usize),
+            RegisterLocation::Local => self.ref_local(index_usize),
+        };
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        res
+    }
+
+    /// Takes a `Register` object, and returns a mutable reference to the `Constant` it holds, or an error if:
+    /// - Register is not a global or local register
+    /// - Register is beyond bounds
+    /// - Register contains a `Option::None` instead of `Option::Some`
+    pub(crate) fn get_constant_mut(&mut self, reg: &Register) -> Result<&mut Constant, ResurgenceError> {
+        let Register(index, loc) = reg;
+        let index_usize = *index as usize;
+
+        let registers = match loc {
+            RegisterLocation::Global => &mut self.global,
+            RegisterLocation::Local => match self.ref_stack_frame() {
+                Ok(stack_frame) => &mut stack_frame.registers,
+                Err(mut err) => {
+                    create_new_trace!(err);
+                    return Err(err);
+                }
             },
-            (RegisterLocation::Local, RegisterLocation::Global) => {
-                const_1 = self.ref_local(index_1_usize);
-                const_2 = self.ref_global(index_2_usize);
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only modify global or local registers!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        if index_usize >= registers.len() {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Register beyond bounds!");
+            create_new_trace!(err);
+            return Err(err);
+        }
+        match registers[index_usize].as_mut() {
+            Some(constant) => Ok(constant),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_ADDRESS_NONE, "Register None!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Takes a `Register` object, and returns the integer it holds or an error if it doesn't hold one
+    pub(crate) fn get_int(&mut self, reg: &Register) -> Result<i64, ResurgenceError> {
+        match self.get_constant(reg) {
+            Ok(Constant::Int(val)) => Ok(*val),
+            Err(mut err) => {
+                create_new_trace!(err);
+                Err(err)
             },
-            (RegisterLocation::Local, RegisterLocation::Local) => {
-                const_1 = self.ref_local(index_1_usize);
-                const_2 = self.ref_local(index_2_usize);
+            Ok(_) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Register must hold an integer!");
+                create_new_trace!(err);
+                Err(err)
             }
         }
-        (const_1, const_2)
+    }
+
+    /// Takes 2 `Register` objects, and returns 2 `Constant` objects, or an error if one of the
+    /// registers does not exist or holds `Option::None`
+    pub(crate) fn get_constants(&mut self, reg_1: &Register, reg_2: &Register) -> Result<(&Constant, &Constant), ResurgenceError> {
+        if reg_1.1 == RegisterLocation::Accumulator || reg_2.1 == RegisterLocation::Accumulator {
+            self.accu_const();
+        }
+        let const_1 = self.ref_register(reg_1);
+        if let Err(mut err) = const_1 {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        let const_2 = self.ref_register(reg_2);
+        if let Err(mut err) = const_2 {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok((const_1.unwrap(), const_2.unwrap()))
     }
 }
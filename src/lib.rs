@@ -15,12 +15,14 @@ A VM backend library that makes developing interpreters easy. Can be used either
 */
 pub(crate) mod objects;
 pub(crate) use objects::constant;
+pub use objects::constant::{Constant, MapKey};
 pub use objects::codeholder::CodeHolder;
+pub use objects::metadata::{ModuleMetadata, SemanticVersion};
 pub use objects::resurgence_error::ResurgenceError;
 
 pub(crate) mod internal;
 pub use internal::execution_engine::ExecutionEngine;
-pub use internal::interpreter::Interpreter;
+pub use internal::interpreter::{ImportAudit, InterruptHandle, Interpreter, InterpreterPool, MemoryLimits, PoolResult, PoolTask, SharedCode};
 
 pub(crate) mod ext_func;
 pub use ext_func::resurgence_state::ResurgenceState;
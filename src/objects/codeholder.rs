@@ -1,9 +1,16 @@
+use crate::create_new_trace;
 use super::constant::Constant;
 use super::instruction::Instruction;
-use std::collections::HashMap;
+use super::metadata::{ModuleMetadata, SemanticVersion};
+use super::resurgence_error::ResurgenceError;
+use std::collections::{BTreeMap, HashMap};
 
 /// A CodeHolder represents a set of executable instructions and a pool of immutable data for an
 /// [`crate::Interpreter`] to use at runtime.
+///
+/// A CodeHolder is never modified by the interpreters running it, and is `Send` and `Sync`, so it
+/// can be shared between threads in an `Arc` (see [`crate::Interpreter::from_shared`]).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CodeHolder {
     /// A [`Vec`] of executable instructions
     pub instructions: Vec<Option<Instruction>>,
@@ -15,13 +22,21 @@ pub struct CodeHolder {
     pub(crate) imports: Vec<String>,
 
     /// A list of calls that the code exports and makes available to the application at runtime.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_sorted"))]
     pub(crate) exports: HashMap<String, u64>,
 
-    /// Have imports been resolved?
-    pub resolved_imports: bool,
+    /// Information describing the module, if the bytecode provides it
+    pub metadata: Option<ModuleMetadata>,
 
-     // Converts bytecode indices into internal indicies
-    pub(crate) byte_to_interal: Vec<u64>,
+    /// Custom sections of the bytecode, keyed by their name. Resurgence does not interpret these;
+    /// they let the embedder store and read back its own data alongside the code.
+    pub custom_sections: BTreeMap<String, Vec<u8>>,
+}
+
+/// Serializes a map in the order of its keys, so the output is stable
+#[cfg(feature = "serde")]
+fn serialize_sorted<S: serde::Serializer>(map: &HashMap<String, u64>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
 }
 
 impl CodeHolder {
@@ -32,14 +47,34 @@ impl CodeHolder {
             constant_pool: Vec::new(),
             imports: Vec::new(),
             exports: HashMap::new(),
-            resolved_imports: false,
-            byte_to_interal: Vec::new(),
+            metadata: None,
+            custom_sections: BTreeMap::new(),
         }
     }
 
     pub fn has_export(&self, func_name: &String) -> bool {
         self.exports.contains_key(func_name)
     }
+
+    /// Returns the names of the functions the code imports from the host, so the host can see
+    /// what a module requests before running it
+    pub fn imports(&self) -> &[String] {
+        &self.imports
+    }
+
+    /// Checks if the code can run on a host providing version `host_api` of its API. Code without
+    /// metadata is assumed to be compatible.
+    ///
+    /// `host_api` (`&SemanticVersion`): version of the API the host provides
+    pub fn check_host_api(&self, host_api: &SemanticVersion) -> Result<(), ResurgenceError> {
+        if let Some(metadata) = &self.metadata {
+            if let Err(mut err) = metadata.check_host_api(host_api) {
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for CodeHolder {
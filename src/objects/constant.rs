@@ -1,9 +1,12 @@
+use std::collections::BTreeMap;
+
 use crate::{ResurgenceError, create_new_trace};
 
 use super::{register::{Register, RegisterLocation}, resurgence_error::ResurgenceErrorKind};
 
 /// `Constant`: Represents a constant in the backend
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Constant {
     /// 64 bit integer
     Int(i64),
@@ -16,7 +19,69 @@ pub enum Constant {
     /// Represents a register in memory
     Address(Register),
     /// Represents a vector
-    Vec(Vec<Constant>)
+    Vec(Vec<Constant>),
+    /// Represents a map
+    #[cfg_attr(feature = "serde", serde(with = "map_entries"))]
+    Map(BTreeMap<MapKey, Constant>),
+}
+
+/// Serializes maps as a list of key/value pairs, as formats like JSON only allow string keys
+#[cfg(feature = "serde")]
+mod map_entries {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{Constant, MapKey};
+
+    pub(super) fn serialize<S: Serializer>(
+        map: &BTreeMap<MapKey, Constant>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<MapKey, Constant>, D::Error> {
+        let entries = Vec::<(MapKey, Constant)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+/// `MapKey`: Represents a key in a `Constant::Map`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MapKey {
+    /// 64 bit integer
+    Int(i64),
+    /// Rust String type
+    String(String),
+}
+
+impl MapKey {
+    /// Creates a `MapKey` from a `Constant::Int` or `Constant::String`
+    ///
+    /// `constant` (`&Constant`): Constant to use as a key
+    pub fn from_constant(constant: &Constant) -> Result<MapKey, ResurgenceError> {
+        match constant {
+            Constant::Int(val) => Ok(MapKey::Int(*val)),
+            Constant::String(val) => Ok(MapKey::String(val.clone())),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Map keys must be integers or strings!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Creates a `Constant` holding the same value as the key
+    pub fn as_constant(&self) -> Constant {
+        match self {
+            MapKey::Int(val) => Constant::Int(*val),
+            MapKey::String(val) => Constant::String(val.clone()),
+        }
+    }
 }
 
 impl Constant {
@@ -32,9 +97,9 @@ impl Constant {
             Ok(value.unwrap_unchecked())
         }
     }
-    /// Adds 2 numerical Constants together
+    /// Adds 2 numerical Constants together, or concatenates 2 `Constant::String`s
     /// 
-    /// `constant` (`&Constant::Int` or `$Constant::Double`): Constant you want to add to self
+    /// `constant` (`&Constant::Int`, `&Constant::Double`, or `&Constant::String`): Constant you want to add to self
     /// 
     /// # Examples
     /// ```no_run
@@ -67,6 +132,9 @@ impl Constant {
             (Self::Address(val_1), Self::Int(val_2)) | (Self::Int(val_2), Self::Address(val_1)) => {
                 Ok(Self::Address(Register(val_1.0 + val_2 as u32, val_1.1)))
             },
+            (Self::String(val_1), Self::String(val_2)) => {
+                Ok(Self::String(val_1 + &val_2))
+            },
             _ => {
                 let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can not add non-numerical types!");
                 err.add_trace(&format!("{}: line {}", file!(), line!()));
@@ -234,7 +302,14 @@ impl Constant {
                     create_new_trace!(err);
                     return Err(err);
                 }
-                Ok(Self::Int(val_1 % val_2))
+                let res = self.check_overflow(val_1.checked_rem(val_2));
+                if let Err(mut err) = res {
+                    create_new_trace!(err);
+                    Err(err)
+                } else {
+                    // We know there was no error, so we don't need to use the checked version of unwrap
+                    unsafe { Ok(Self::Int(res.unwrap_unchecked())) }
+                }
             },
             (Self::Double(val_1), Self::Double(val_2)) => {
                 if val_2 == 0.0 {
@@ -260,6 +335,566 @@ impl Constant {
         }
     }
     
+    /// Converts a Constant into a `Constant::Int`
+    ///
+    /// Doubles are truncated towards zero, and Strings are parsed as a base 10 integer
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = create_constant_double(&5.7).cast_int();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(5));
+    /// ```
+    pub fn cast_int(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Int(val) => Ok(Self::Int(*val)),
+            Self::Double(val) => {
+                if val.is_nan() {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can not convert NaN to an integer!");
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+                // i64::MAX can't be represented as a f64, so we check against 2^63 instead
+                let truncated = val.trunc();
+                if truncated < i64::MIN as f64 || truncated >= -(i64::MIN as f64) {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::OVERFLOW, &format!("Can not convert {} to an integer without overflowing!", val));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+                Ok(Self::Int(truncated as i64))
+            },
+            Self::String(val) => match val.parse::<i64>() {
+                Ok(int_val) => Ok(Self::Int(int_val)),
+                Err(_) => {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, &format!("Can not convert \"{}\" to an integer!", val));
+                    create_new_trace!(err);
+                    Err(err)
+                }
+            },
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only convert numbers and strings to an integer!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Converts a Constant into a `Constant::Double`
+    ///
+    /// Integers are only converted if the double holds them exactly, so integers beyond 2^53 that
+    /// would lose precision fail. Strings are parsed as a floating point number
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = create_constant_int(&5).cast_double();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Double(5.0));
+    /// ```
+    pub fn cast_double(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Int(val) => {
+                // Compare as i128, as converting 2^63 back to an i64 would saturate to i64::MAX
+                let double_val = *val as f64;
+                if double_val as i128 != *val as i128 {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::OVERFLOW, &format!("Can not convert {} to a double without losing precision!", val));
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+                Ok(Self::Double(double_val))
+            },
+            Self::Double(val) => Ok(Self::Double(*val)),
+            Self::String(val) => match val.parse::<f64>() {
+                Ok(double_val) => Ok(Self::Double(double_val)),
+                Err(_) => {
+                    let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, &format!("Can not convert \"{}\" to a double!", val));
+                    create_new_trace!(err);
+                    Err(err)
+                }
+            },
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only convert numbers and strings to a double!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Converts a Constant into a `Constant::String`
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = create_constant_int(&5).cast_string();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::String("5".to_string()));
+    /// ```
+    pub fn cast_string(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Int(val) => Ok(Self::String(val.to_string())),
+            Self::Double(val) => Ok(Self::String(val.to_string())),
+            Self::String(val) => Ok(Self::String(val.clone())),
+            Self::Boolean(val) => Ok(Self::String(val.to_string())),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only convert numbers, strings, and booleans to a string!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Converts a Constant into a `Constant::Boolean` based on its truthiness
+    ///
+    /// A Constant is `false` if it is `0`, `0.0`, `NaN`, an empty string, `false`, an empty
+    /// vector, or an empty map. Everything else is `true`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// assert_eq!(create_constant_int(&0).cast_bool(), Constant::Boolean(false));
+    /// ```
+    pub fn cast_bool(&self) -> Self {
+        match self {
+            Self::Int(val) => Self::Boolean(*val != 0),
+            Self::Double(val) => Self::Boolean(*val != 0.0 && !val.is_nan()),
+            Self::String(val) => Self::Boolean(!val.is_empty()),
+            Self::Boolean(val) => Self::Boolean(*val),
+            Self::Address(_) => Self::Boolean(true),
+            Self::Vec(val) => Self::Boolean(!val.is_empty()),
+            Self::Map(val) => Self::Boolean(!val.is_empty()),
+        }
+    }
+
+    /// Returns the type tag of a Constant as a `Constant::Int`
+    ///
+    /// The type tags are the same values used to encode constants in bytecode:
+    /// * `1`: Int
+    /// * `2`: Double
+    /// * `3`: String
+    /// * `4`: Boolean
+    /// * `5`: Address
+    /// * `6`: Vec
+    /// * `7`: Map
+    pub fn type_of(&self) -> Self {
+        Self::Int(match self {
+            Self::Int(_) => 1,
+            Self::Double(_) => 2,
+            Self::String(_) => 3,
+            Self::Boolean(_) => 4,
+            Self::Address(_) => 5,
+            Self::Vec(_) => 6,
+            Self::Map(_) => 7,
+        })
+    }
+
+    /// Returns the length of a `Constant::String` in characters
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::String("héllo".to_string()).str_len();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(5));
+    /// ```
+    pub fn str_len(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::String(val) => Ok(Self::Int(val.chars().count() as i64)),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get the length of a string!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns part of a `Constant::String` as a new `Constant::String`
+    ///
+    /// `start` (`i64`): index of the first character
+    /// `length` (`i64`): amount of characters
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::String("héllo".to_string()).substr(1, 3);
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::String("éll".to_string()));
+    /// ```
+    pub fn substr(&self, start: i64, length: i64) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::String(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get a substring of a string!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let char_count = val.chars().count() as i64;
+        if start < 0 || length < 0 || start > char_count || length > char_count - start {
+            let mut err = ResurgenceError::from(ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS, &format!("Substring of {} characters starting at {} is beyond the bounds of a string of {} characters!", length, start, char_count));
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(Self::String(val.chars().skip(start as usize).take(length as usize).collect()))
+    }
+
+    /// Returns the character at an index of a `Constant::String` as a new `Constant::String`
+    ///
+    /// `index` (`i64`): index of the character
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::String("héllo".to_string()).char_at(1);
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::String("é".to_string()));
+    /// ```
+    pub fn char_at(&self, index: i64) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::String(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get a character of a string!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let character = usize::try_from(index).ok().and_then(|i| val.chars().nth(i));
+        match character {
+            Some(c) => Ok(Self::String(c.to_string())),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS, &format!("Character {} is beyond the bounds of the string!", index));
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the length of a `Constant::Vec`
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::Vec(vec![Constant::Int(1), Constant::Int(2)]).vec_len();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(2));
+    /// ```
+    pub fn vec_len(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Vec(val) => Ok(Self::Int(val.len() as i64)),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get the length of a vector!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns a copy of the element at an index of a `Constant::Vec`
+    ///
+    /// `index` (`i64`): index of the element
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::Vec(vec![Constant::Int(1), Constant::Int(2)]).vec_get(1);
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(2));
+    /// ```
+    pub fn vec_get(&self, index: i64) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::Vec(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get an element of a vector!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        match usize::try_from(index).ok().and_then(|i| val.get(i)) {
+            Some(element) => Ok(element.clone()),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS, &format!("Element {} is beyond the bounds of a vector of {} elements!", index, val.len()));
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Replaces the element at an index of a `Constant::Vec`
+    ///
+    /// `index` (`i64`): index of the element
+    /// `element` (`Constant`): the new element
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut vec_const = Constant::Vec(vec![Constant::Int(1)]);
+    /// if let Err(err) = vec_const.vec_set(0, Constant::Int(2)) {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(vec_const, Constant::Vec(vec![Constant::Int(2)]));
+    /// ```
+    pub fn vec_set(&mut self, index: i64, element: Self) -> Result<(), ResurgenceError> {
+        let val = match self {
+            Self::Vec(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only set an element of a vector!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let len = val.len();
+        match usize::try_from(index).ok().and_then(|i| val.get_mut(i)) {
+            Some(slot) => {
+                *slot = element;
+                Ok(())
+            },
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS, &format!("Element {} is beyond the bounds of a vector of {} elements!", index, len));
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Appends an element to the end of a `Constant::Vec`
+    ///
+    /// `element` (`Constant`): the element to append
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut vec_const = Constant::Vec(vec![]);
+    /// if let Err(err) = vec_const.vec_push(Constant::Int(1)) {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(vec_const, Constant::Vec(vec![Constant::Int(1)]));
+    /// ```
+    pub fn vec_push(&mut self, element: Self) -> Result<(), ResurgenceError> {
+        match self {
+            Self::Vec(val) => {
+                val.push(element);
+                Ok(())
+            },
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only push to a vector!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes the last element of a `Constant::Vec` and returns it
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut vec_const = Constant::Vec(vec![Constant::Int(1)]);
+    /// let res = vec_const.vec_pop();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(1));
+    /// ```
+    pub fn vec_pop(&mut self) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::Vec(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only pop from a vector!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        match val.pop() {
+            Some(element) => Ok(element),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS, "Can not pop from an empty vector!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the amount of entries in a `Constant::Map`
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let res = Constant::Map(BTreeMap::new()).map_len();
+    /// if let Err(err) = res {
+    ///     panic!("{}", err);
+    /// }
+    ///
+    /// assert_eq!(res.unwrap(), Constant::Int(0));
+    /// ```
+    pub fn map_len(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Map(val) => Ok(Self::Int(val.len() as i64)),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get the length of a map!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns a copy of the value stored under a key of a `Constant::Map`
+    ///
+    /// `key` (`&Constant::Int` or `&Constant::String`): the key of the value
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut map_const = Constant::Map(BTreeMap::new());
+    /// map_const.map_set(&Constant::Int(1), Constant::Int(2)).unwrap();
+    ///
+    /// assert_eq!(map_const.map_get(&Constant::Int(1)).unwrap(), Constant::Int(2));
+    /// ```
+    pub fn map_get(&self, key: &Self) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::Map(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get a value of a map!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let map_key = MapKey::from_constant(key);
+        if let Err(mut err) = map_key {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        match val.get(&map_key.unwrap()) {
+            Some(element) => Ok(element.clone()),
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::MISSING_KEY, &format!("Map does not contain the key {:?}!", key));
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Stores a value under a key of a `Constant::Map`, replacing the previous value if there was one
+    ///
+    /// `key` (`&Constant::Int` or `&Constant::String`): the key of the value
+    /// `element` (`Constant`): the value to store
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut map_const = Constant::Map(BTreeMap::new());
+    /// if let Err(err) = map_const.map_set(&Constant::Int(1), Constant::Int(2)) {
+    ///     panic!("{}", err);
+    /// }
+    /// ```
+    pub fn map_set(&mut self, key: &Self, element: Self) -> Result<(), ResurgenceError> {
+        let val = match self {
+            Self::Map(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only set a value of a map!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let map_key = MapKey::from_constant(key);
+        if let Err(mut err) = map_key {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        val.insert(map_key.unwrap(), element);
+        Ok(())
+    }
+
+    /// Removes a key and its value from a `Constant::Map` if it exists
+    ///
+    /// `key` (`&Constant::Int` or `&Constant::String`): the key to remove
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut map_const = Constant::Map(BTreeMap::new());
+    /// map_const.map_set(&Constant::Int(1), Constant::Int(2)).unwrap();
+    /// map_const.map_remove(&Constant::Int(1)).unwrap();
+    ///
+    /// assert_eq!(map_const.map_len().unwrap(), Constant::Int(0));
+    /// ```
+    pub fn map_remove(&mut self, key: &Self) -> Result<(), ResurgenceError> {
+        let val = match self {
+            Self::Map(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only remove a value of a map!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let map_key = MapKey::from_constant(key);
+        if let Err(mut err) = map_key {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        val.remove(&map_key.unwrap());
+        Ok(())
+    }
+
+    /// Checks if a `Constant::Map` contains a key and returns the result as a `Constant::Boolean`
+    ///
+    /// `key` (`&Constant::Int` or `&Constant::String`): the key to check for
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let map_const = Constant::Map(BTreeMap::new());
+    ///
+    /// assert_eq!(map_const.map_contains(&Constant::Int(1)).unwrap(), Constant::Boolean(false));
+    /// ```
+    pub fn map_contains(&self, key: &Self) -> Result<Self, ResurgenceError> {
+        let val = match self {
+            Self::Map(val) => val,
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only check the keys of a map!");
+                create_new_trace!(err);
+                return Err(err);
+            }
+        };
+        let map_key = MapKey::from_constant(key);
+        if let Err(mut err) = map_key {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(Self::Boolean(val.contains_key(&map_key.unwrap())))
+    }
+
+    /// Returns the keys of a `Constant::Map` in ascending order as a `Constant::Vec`
+    ///
+    /// Integer keys are ordered before string keys
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mut map_const = Constant::Map(BTreeMap::new());
+    /// map_const.map_set(&Constant::Int(1), Constant::Int(2)).unwrap();
+    ///
+    /// assert_eq!(map_const.map_keys().unwrap(), Constant::Vec(vec![Constant::Int(1)]));
+    /// ```
+    pub fn map_keys(&self) -> Result<Self, ResurgenceError> {
+        match self {
+            Self::Map(val) => Ok(Self::Vec(val.keys().map(|key| key.as_constant()).collect())),
+            _ => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::INVALID_OPERATION, "Can only get the keys of a map!");
+                create_new_trace!(err);
+                Err(err)
+            }
+        }
+    }
+
     /// Returns the type as `String` for error handling reasons
     #[inline]
     pub fn type_as_string(&self) -> String {
@@ -281,6 +916,13 @@ impl Constant {
                 }
                 final_string
             }
+            Self::Map(ref map_val) => {
+                let mut final_string = String::from("");
+                for (key, obj) in map_val {
+                    final_string += &format!("{} => {}", key.as_constant().type_as_string(), obj.type_as_string());
+                }
+                final_string
+            }
         }
     } 
 }
@@ -2,6 +2,7 @@ use super::register::{Register, RegisterReference, RegisterLocation};
 
 /// `Instruction`: Represents instructions the built in Resurgence VM can use (this can be reused for any VM)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
     /// Creates a `StackFrame` object and allocates n amount of registers
     /// 
@@ -42,10 +43,49 @@ pub enum Instruction {
     /// ```
     Jump(i64),
 
-    /// Jumps to index n by doing a recursive call of the execute_bytecode function
+    /// Jumps to instruction n
+    ///
+    /// ```no_run
+    /// 0 Alloc 5 // Stack frame containing 5 registers
+    /// 1 JumpTo 0 // Jumps back to Alloc
+    /// ```
+    JumpTo(u64),
+
+    /// Jumps to instruction n if the register holds `true`
+    ///
+    /// ```no_run
+    /// 0 JumpIfTrue 0, 2 // Jumps to Ret if register 0 holds true
+    /// 1 Alloc 5
+    /// 2 Ret
+    /// ```
+    JumpIfTrue(Register, u64),
+
+    /// Jumps to instruction n if the register holds `false`
+    ///
+    /// ```no_run
+    /// 0 JumpIfFalse 0, 2 // Jumps to Ret if register 0 holds false
+    /// 1 Alloc 5
+    /// 2 Ret
+    /// ```
+    JumpIfFalse(Register, u64),
+
+    /// Jumps to the instruction stored at the index held by an integer register. If the index is
+    /// outside of the table, jumps to the default instruction instead
+    ///
+    /// ```no_run
+    /// 0 JumpTable 0, 4, [2, 3] // Jumps to 2 if register 0 holds 0, 3 if it holds 1, and 4 otherwise
+    /// 1 Ret
+    /// 2 Alloc 5
+    /// 3 Alloc 2
+    /// 4 Ret
+    /// ```
+    JumpTable(Register, u64, Vec<u64>),
+
+    /// Calls the function starting at index n. `Ret` (or running past the last instruction)
+    /// continues after the `Call`
     /// 
     /// ```no_run
-    /// 0 Call 1 // Calls the execute_bytecode function starting at index 1
+    /// 0 Call 1 // Calls the function starting at index 1
     /// 1 Alloc 5 // Stack frame containing 5 registers
     /// 2 Free 1 // Frees the top stack frame
     /// ```
@@ -184,7 +224,154 @@ pub enum Instruction {
     /// LessEqual 0, 1 // Check if 0 is less than or equal to 1 and jumps one operation if it is
     /// ```
     LessEqual(Register, Register),
-    
+
+    /// Converts a register to an integer and stores it in the output
+    ///
+    /// ```no_run
+    /// 0 ToInt 0, 1 // Convert the value from register 1 to an integer and store it in register 0
+    /// ```
+    ToInt(Register, Register),
+
+    /// Converts a register to a double and stores it in the output
+    ///
+    /// ```no_run
+    /// 0 ToDouble 0, 1 // Convert the value from register 1 to a double and store it in register 0
+    /// ```
+    ToDouble(Register, Register),
+
+    /// Converts a register to a string and stores it in the output
+    ///
+    /// ```no_run
+    /// 0 ToString 0, 1 // Convert the value from register 1 to a string and store it in register 0
+    /// ```
+    ToString(Register, Register),
+
+    /// Converts a register to a boolean based on its truthiness and stores it in the output
+    ///
+    /// ```no_run
+    /// 0 ToBool 0, 1 // Convert the value from register 1 to a boolean and store it in register 0
+    /// ```
+    ToBool(Register, Register),
+
+    /// Stores the type tag of a register in the output
+    ///
+    /// ```no_run
+    /// 0 TypeOf 0, 1 // Store the type tag of register 1 in register 0
+    /// ```
+    TypeOf(Register, Register),
+
+    /// Stores the length of a string in characters in the output
+    ///
+    /// ```no_run
+    /// 0 StrLen 0, 1 // Store the length of the string in register 1 in register 0
+    /// ```
+    StrLen(Register, Register),
+
+    /// Stores part of a string in the output
+    ///
+    /// ```no_run
+    /// 0 Substr 0, 1, 2, 3 // Store the substring of register 1 starting at the index in register 2 with the length in register 3 in register 0
+    /// ```
+    Substr(Register, Register, Register, Register),
+
+    /// Stores the character at an index of a string in the output
+    ///
+    /// ```no_run
+    /// 0 CharAt 0, 1, 2 // Store the character of register 1 at the index in register 2 in register 0
+    /// ```
+    CharAt(Register, Register, Register),
+
+    /// Stores a new, empty vector in the output
+    ///
+    /// ```no_run
+    /// 0 VecNew 0 // Store an empty vector in register 0
+    /// ```
+    VecNew(Register),
+
+    /// Stores a copy of an element of a vector in the output
+    ///
+    /// ```no_run
+    /// 0 VecGet 0, 1, 2 // Store the element of the vector in register 1 at the index in register 2 in register 0
+    /// ```
+    VecGet(Register, Register, Register),
+
+    /// Replaces an element of a vector with a copy of a register
+    ///
+    /// ```no_run
+    /// 0 VecSet 0, 1, 2 // Set the element of the vector in register 0 at the index in register 1 to the value in register 2
+    /// ```
+    VecSet(Register, Register, Register),
+
+    /// Appends a copy of a register to the end of a vector
+    ///
+    /// ```no_run
+    /// 0 VecPush 0, 1 // Append the value in register 1 to the vector in register 0
+    /// ```
+    VecPush(Register, Register),
+
+    /// Removes the last element of a vector and stores it in the output
+    ///
+    /// ```no_run
+    /// 0 VecPop 0, 1 // Remove the last element of the vector in register 1 and store it in register 0
+    /// ```
+    VecPop(Register, Register),
+
+    /// Stores the length of a vector in the output
+    ///
+    /// ```no_run
+    /// 0 VecLen 0, 1 // Store the length of the vector in register 1 in register 0
+    /// ```
+    VecLen(Register, Register),
+
+    /// Stores a new, empty map in the output
+    ///
+    /// ```no_run
+    /// 0 MapNew 0 // Store an empty map in register 0
+    /// ```
+    MapNew(Register),
+
+    /// Stores a copy of the value stored under a key of a map in the output
+    ///
+    /// ```no_run
+    /// 0 MapGet 0, 1, 2 // Store the value of the map in register 1 under the key in register 2 in register 0
+    /// ```
+    MapGet(Register, Register, Register),
+
+    /// Stores a copy of a register under a key of a map
+    ///
+    /// ```no_run
+    /// 0 MapSet 0, 1, 2 // Store the value in register 2 under the key in register 1 of the map in register 0
+    /// ```
+    MapSet(Register, Register, Register),
+
+    /// Removes a key and its value from a map
+    ///
+    /// ```no_run
+    /// 0 MapRemove 0, 1 // Remove the key in register 1 from the map in register 0
+    /// ```
+    MapRemove(Register, Register),
+
+    /// Stores whether a map contains a key in the output
+    ///
+    /// ```no_run
+    /// 0 MapContains 0, 1, 2 // Store whether the map in register 1 contains the key in register 2 in register 0
+    /// ```
+    MapContains(Register, Register, Register),
+
+    /// Stores the amount of entries in a map in the output
+    ///
+    /// ```no_run
+    /// 0 MapLen 0, 1 // Store the amount of entries in the map in register 1 in register 0
+    /// ```
+    MapLen(Register, Register),
+
+    /// Stores the keys of a map as a vector in the output
+    ///
+    /// ```no_run
+    /// 0 MapKeys 0, 1 // Store the keys of the map in register 1 in register 0
+    /// ```
+    MapKeys(Register, Register),
+
     /*
     * These are the beginnings of vectorized instructions, instructions that can operatate on
     * multiple registers at once.
@@ -217,3 +404,70 @@ pub enum Instruction {
     /// Should not be used if `vectorized-instructions` is not enabled
     VectorizedMod(Register, Register, Register),
 }
+
+impl Instruction {
+    /// Returns every register the instruction uses as an operand
+    pub(crate) fn registers(&self) -> Vec<&Register> {
+        match self {
+            Instruction::Alloc(_)
+            | Instruction::FrameAlloc(..)
+            | Instruction::Free(_)
+            | Instruction::FrameFree(..)
+            | Instruction::Jump(_)
+            | Instruction::JumpTo(_)
+            | Instruction::Call(_)
+            | Instruction::ExtCall(_)
+            | Instruction::Ret
+            | Instruction::StackPop => vec![],
+
+            Instruction::JumpIfTrue(reg, _)
+            | Instruction::JumpIfFalse(reg, _)
+            | Instruction::JumpTable(reg, ..)
+            | Instruction::StackPush(reg, _)
+            | Instruction::StackMov(reg, _)
+            | Instruction::VecNew(reg)
+            | Instruction::MapNew(reg) => vec![reg],
+
+            Instruction::Mov(reg_1, _, reg_2, _)
+            | Instruction::Cpy(reg_1, _, reg_2, _)
+            | Instruction::Ref(reg_1, _, reg_2, _)
+            | Instruction::Equal(reg_1, reg_2)
+            | Instruction::NotEqual(reg_1, reg_2)
+            | Instruction::Greater(reg_1, reg_2)
+            | Instruction::Less(reg_1, reg_2)
+            | Instruction::GreaterEqual(reg_1, reg_2)
+            | Instruction::LessEqual(reg_1, reg_2)
+            | Instruction::ToInt(reg_1, reg_2)
+            | Instruction::ToDouble(reg_1, reg_2)
+            | Instruction::ToString(reg_1, reg_2)
+            | Instruction::ToBool(reg_1, reg_2)
+            | Instruction::TypeOf(reg_1, reg_2)
+            | Instruction::StrLen(reg_1, reg_2)
+            | Instruction::VecPush(reg_1, reg_2)
+            | Instruction::VecPop(reg_1, reg_2)
+            | Instruction::VecLen(reg_1, reg_2)
+            | Instruction::MapRemove(reg_1, reg_2)
+            | Instruction::MapLen(reg_1, reg_2)
+            | Instruction::MapKeys(reg_1, reg_2) => vec![reg_1, reg_2],
+
+            Instruction::Add(reg_1, reg_2, reg_3)
+            | Instruction::Sub(reg_1, reg_2, reg_3)
+            | Instruction::Mul(reg_1, reg_2, reg_3)
+            | Instruction::Div(reg_1, reg_2, reg_3)
+            | Instruction::Mod(reg_1, reg_2, reg_3)
+            | Instruction::CharAt(reg_1, reg_2, reg_3)
+            | Instruction::VecGet(reg_1, reg_2, reg_3)
+            | Instruction::VecSet(reg_1, reg_2, reg_3)
+            | Instruction::MapGet(reg_1, reg_2, reg_3)
+            | Instruction::MapSet(reg_1, reg_2, reg_3)
+            | Instruction::MapContains(reg_1, reg_2, reg_3)
+            | Instruction::VectorizedAdd(reg_1, reg_2, reg_3)
+            | Instruction::VectorizedSub(reg_1, reg_2, reg_3)
+            | Instruction::VectorizedMul(reg_1, reg_2, reg_3)
+            | Instruction::VectorizedDiv(reg_1, reg_2, reg_3)
+            | Instruction::VectorizedMod(reg_1, reg_2, reg_3) => vec![reg_1, reg_2, reg_3],
+
+            Instruction::Substr(reg_1, reg_2, reg_3, reg_4) => vec![reg_1, reg_2, reg_3, reg_4],
+        }
+    }
+}
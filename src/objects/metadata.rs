@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{create_new_trace, objects::resurgence_error::ResurgenceErrorKind, ResurgenceError};
+
+/// A version number following the rules of semantic versioning (`MAJOR.MINOR.PATCH`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SemanticVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SemanticVersion {
+    /// Creates a new `SemanticVersion`
+    pub fn new(major: u64, minor: u64, patch: u64) -> SemanticVersion {
+        SemanticVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Checks if something providing this version can be used by something that requires
+    /// `required`. This is the case if the major versions match and this version is not older.
+    ///
+    /// `required` (`&SemanticVersion`): the lowest version that is accepted
+    pub fn satisfies(&self, required: &SemanticVersion) -> bool {
+        self.major == required.major && self >= required
+    }
+}
+
+impl fmt::Display for SemanticVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Describes a module, so hosts can show and check it before running its code
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModuleMetadata {
+    /// Name of the module
+    pub name: String,
+    /// Version of the module
+    pub version: SemanticVersion,
+    /// Author of the module
+    pub author: String,
+    /// Description of the module
+    pub description: String,
+    /// Lowest version of the host API the module works with
+    pub min_host_api: SemanticVersion,
+    /// Arbitrary key/value pairs for data not covered by the other fields
+    pub properties: BTreeMap<String, String>,
+}
+
+impl ModuleMetadata {
+    /// Checks if the module can run on a host providing version `host_api` of its API
+    ///
+    /// `host_api` (`&SemanticVersion`): version of the API the host provides
+    pub fn check_host_api(&self, host_api: &SemanticVersion) -> Result<(), ResurgenceError> {
+        if !host_api.satisfies(&self.min_host_api) {
+            let mut err = ResurgenceError::from(
+                ResurgenceErrorKind::INCOMPATIBLE_MODULE,
+                &format!(
+                    "Module {} {} requires host API {}, but the host provides {}",
+                    self.name, self.version, self.min_host_api, host_api
+                ),
+            );
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+}
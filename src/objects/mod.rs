@@ -3,4 +3,6 @@ pub mod constant;
 pub mod register;
 pub mod stackframe;
 pub mod codeholder;
+mod verifier;
+pub mod metadata;
 pub mod resurgence_error;
@@ -7,6 +7,7 @@
 /// * `Global`: Global scope
 /// * `Local`: Local scope
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegisterLocation {
     ConstantPool,
     Accumulator,
@@ -19,6 +20,7 @@ pub enum RegisterLocation {
 /// `u32`: Location of the virtual register in an array
 /// `RegisterLocation`: The scope of a register
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Register(pub u32, pub RegisterLocation);
 
 /// `RegisterReference`: Defines how we refer to a register in Instruction
@@ -27,6 +29,7 @@ pub struct Register(pub u32, pub RegisterLocation);
 /// * `AsIs`: Register in memory location holds a value
 /// * `Dereference`: Register in memory location holds an address to another location
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegisterReference {
     AsIs,
     Dereference,
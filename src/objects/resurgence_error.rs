@@ -22,12 +22,31 @@ pub(crate) enum ResurgenceErrorKind {
     OVERFLOW,
     /// Out of bounds when accessing a register
     REGISTER_OUT_OF_BOUNDS,
+    /// Out of bounds when jumping to an instruction
+    INSTRUCTION_OUT_OF_BOUNDS,
+    /// Out of bounds when indexing into a value (ex. `CHAR_AT`)
+    INDEX_OUT_OF_BOUNDS,
+    /// When a map does not contain a key (ex. `MAP_GET`)
+    MISSING_KEY,
     /// When imports are not resolved
     MISSING_IMPORTS,
+    /// When a module can not run on the host (ex. it requires a newer host API)
+    INCOMPATIBLE_MODULE,
     /// When a function returns an error
     FUNCTION_RETURN_ERROR,
     /// When the programmer tries to call a function that doesn't exist
     FUNCTION_DOES_NOT_EXIST,
+    /// When the code imports a function whose capability has not been granted
+    PERMISSION_DENIED,
+    /// When calls are nested deeper than the recursion limit
+    RECURSION_LIMIT,
+    /// When the interpreter runs out of fuel before the code finishes
+    OUT_OF_FUEL,
+    /// When the code uses more memory than the interpreter allows (ex. `ALLOC` of too many registers)
+    MEMORY_LIMIT,
+    /// When execution is stopped by an interrupt or because its deadline passed
+    #[allow(clippy::upper_case_acronyms)]
+    INTERRUPTED,
 
     /// When something is so messed up that you don't have the words to describe it
     I_GOOFED_UP,
@@ -76,9 +95,18 @@ impl fmt::Debug for ResurgenceError {
             ResurgenceErrorKind::MEMORY_ADDRESS_NONE => "MEMORY_ADDRESS_NONE",
             ResurgenceErrorKind::OVERFLOW => "OVERFLOW",
             ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS => "REGISTER_OUT_OF_BOUNDS",
+            ResurgenceErrorKind::INSTRUCTION_OUT_OF_BOUNDS => "INSTRUCTION_OUT_OF_BOUNDS",
+            ResurgenceErrorKind::INDEX_OUT_OF_BOUNDS => "INDEX_OUT_OF_BOUNDS",
+            ResurgenceErrorKind::MISSING_KEY => "MISSING_KEY",
             ResurgenceErrorKind::MISSING_IMPORTS => "MISSING_IMPORTS",
+            ResurgenceErrorKind::INCOMPATIBLE_MODULE => "INCOMPATIBLE_MODULE",
             ResurgenceErrorKind::FUNCTION_RETURN_ERROR => "FUNCTION_RETURN_ERROR",
             ResurgenceErrorKind::FUNCTION_DOES_NOT_EXIST => "FUNCTION_DOES_NOT_EXIST",
+            ResurgenceErrorKind::PERMISSION_DENIED => "PERMISSION_DENIED",
+            ResurgenceErrorKind::RECURSION_LIMIT => "RECURSION_LIMIT",
+            ResurgenceErrorKind::OUT_OF_FUEL => "OUT_OF_FUEL",
+            ResurgenceErrorKind::MEMORY_LIMIT => "MEMORY_LIMIT",
+            ResurgenceErrorKind::INTERRUPTED => "INTERRUPTED",
             ResurgenceErrorKind::I_GOOFED_UP => "I_GOOFED_UP"
 
         };
@@ -88,20 +116,21 @@ impl fmt::Debug for ResurgenceError {
             .field("Error Message", &self.error_message);
 
         if let Some(ctx) = &self.context {
+            let state = &ctx.state;
             debug_struct
-                .field("Max Recursion Depth", &ctx.recursion_depth)
-                .field("Depth at time of error", &ctx.call_stack.len());
+                .field("Max Recursion Depth", &state.recursion_depth)
+                .field("Depth at time of error", &state.call_stack.len());
             
             // In depth debugging
-            for (i, frame) in ctx.call_stack.iter().enumerate() {
+            for (i, frame) in state.call_stack.iter().enumerate() {
                 debug_struct 
                     .field(&format!("Register Count of {}", i), &frame.registers.len());
             }
-            for (i, constant) in ctx.constant_stack.iter().enumerate() {
+            for (i, constant) in state.constant_stack.iter().enumerate() {
                 debug_struct 
                     .field(&format!("Value of constant {}", i), &constant);
             }
-            for (i, func) in ctx.rust_and_native_fns.iter().enumerate() {
+            for (i, func) in state.rust_and_native_fns.iter().enumerate() {
                 debug_struct
                     .field(&format!("Function Name of function {}", i), &func.name)
                     .field(&format!("Is function {} a C Function?", i), &func.native);
@@ -118,16 +147,23 @@ impl fmt::Debug for ResurgenceError {
 /// Represents the interpreter state at the time of creation
 #[derive(Clone)]
 pub struct ResurgenceContext {
+    /// Stacks and functions at the time of exception; boxed so that errors stay small
+    pub(crate) state: Box<ContextState>,
+    /// Instruction at the time of exception; vector to handle recursion
+    pub(crate) instruction: Vec<Instruction>,
+    /// Instruction index at time of exception; vector to handle recursion
+    pub(crate) instruction_pointer: Vec<usize>,
+}
+
+/// The parts of the interpreter state that a `ResurgenceContext` copies
+#[derive(Clone)]
+pub(crate) struct ContextState {
     /// Call stack at the time of exception
     pub(crate) call_stack: Vec<StackFrame>,
     /// Constant stack at the time of exception
     pub(crate) constant_stack: Vec<Constant>,
     /// All registered functions and their indexes
     pub(crate) rust_and_native_fns: Vec<RustFunc>,
-    /// Instruction at the time of exception; vector to handle recursion
-    pub(crate) instruction: Vec<Instruction>,
-    /// Instruction index at time of exception; vector to handle recursion
-    pub(crate) instruction_pointer: Vec<usize>,
     /// Recursion depth 
     pub(crate) recursion_depth: usize,
 }
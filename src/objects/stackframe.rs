@@ -1,4 +1,6 @@
 use crate::constant::Constant;
+use crate::objects::resurgence_error::ResurgenceErrorKind;
+use crate::{create_new_trace, ResurgenceError};
 /// `StackFrame`: Represents a stack frame.
 #[derive(Clone)]
 pub struct StackFrame {
@@ -7,25 +9,49 @@ pub struct StackFrame {
 }
 
 impl StackFrame {
-    /// Moves a value out of a register
+    /// Moves a value out of a register, or returns an error if:
+    /// - Register is beyond bounds
+    /// - Register contains a `Option::None` instead of `Option::Some`
     /// 
     /// `index` (`usize`): index of register
-    pub fn mov_register(&mut self, index: usize) -> Constant {
-        self.registers[index].take().expect("Non-existant register!")
+    pub fn mov_register(&mut self, index: usize) -> Result<Constant, ResurgenceError> {
+        let res = self.check_register(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(self.registers[index].take().unwrap())
     }
 
-    /// Copys a value out of a register
+    /// References a value out of a register, or returns an error if:
+    /// - Register is beyond bounds
+    /// - Register contains a `Option::None` instead of `Option::Some`
     /// 
     /// `index` (`usize`): index of register
-    pub fn cpy_register(&mut self, index: usize) -> Constant {
-        self.registers[index].clone().expect("Non-existant register!")
+    pub fn ref_register(&self, index: usize) -> Result<&Constant, ResurgenceError> {
+        let res = self.check_register(index);
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(self.registers[index].as_ref().unwrap())
     }
 
-    /// References a value out of a register
-    /// 
-    /// `index` (`usize`): index of register
-    pub fn ref_register(&self, index: usize) -> &Constant {
-        self.registers[index].as_ref().expect("Non-existant register!")
+    /// Checks that a register exists and holds a value
+    fn check_register(&self, index: usize) -> Result<(), ResurgenceError> {
+        match self.registers.get(index) {
+            Some(Some(_)) => Ok(()),
+            Some(None) => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::MEMORY_ADDRESS_NONE, "Local register None!");
+                create_new_trace!(err);
+                Err(err)
+            },
+            None => {
+                let mut err = ResurgenceError::from(ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS, "Register beyond bounds!");
+                create_new_trace!(err);
+                Err(err)
+            },
+        }
     }
 }
 
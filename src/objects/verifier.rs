@@ -0,0 +1,93 @@
+use super::codeholder::CodeHolder;
+use super::instruction::Instruction;
+use super::register::{Register, RegisterLocation};
+use super::resurgence_error::{ResurgenceError, ResurgenceErrorKind};
+use crate::create_new_trace;
+
+impl CodeHolder {
+    /// Checks that the code only refers to instructions, constants and imports that exist, so
+    /// running it can not index out of bounds. The [`crate::Interpreter`] does this before it
+    /// executes any code; compilers can use it to catch bad output early.
+    pub fn verify(&self) -> Result<(), ResurgenceError> {
+        let res = self.verify_code(self.constant_pool.len());
+        if let Err(mut err) = res {
+            create_new_trace!(err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Verifies the code against a constant pool holding `constant_count` constants, which may be
+    /// stored outside of the CodeHolder
+    ///
+    /// `constant_count` (`usize`): number of constants the code can use
+    pub(crate) fn verify_code(&self, constant_count: usize) -> Result<(), ResurgenceError> {
+        let length = self.instructions.len() as u64;
+        for (index, ins) in self.instructions.iter().enumerate() {
+            let ins = match ins {
+                Some(ins) => ins,
+                None => continue,
+            };
+
+            let targets = match ins {
+                Instruction::Jump(offset) => {
+                    // a target that does not fit is out of bounds as well
+                    let target = (index as i64).checked_add(*offset).unwrap_or(-1);
+                    vec![u64::try_from(target).unwrap_or(u64::MAX)]
+                }
+                Instruction::JumpTo(target)
+                | Instruction::JumpIfTrue(_, target)
+                | Instruction::JumpIfFalse(_, target)
+                | Instruction::Call(target) => vec![*target],
+                Instruction::JumpTable(_, default, table) => {
+                    let mut targets = table.clone();
+                    targets.push(*default);
+                    targets
+                }
+                _ => vec![],
+            };
+            if let Some(target) = targets.iter().find(|target| **target >= length) {
+                let mut err = ResurgenceError::from(
+                    ResurgenceErrorKind::INSTRUCTION_OUT_OF_BOUNDS,
+                    &format!("Instruction {} refers to instruction {}, which does not exist!", index, target),
+                );
+                create_new_trace!(err);
+                return Err(err);
+            }
+
+            if let Instruction::ExtCall(import) = ins {
+                if *import >= self.imports.len() as u64 {
+                    let mut err = ResurgenceError::from(
+                        ResurgenceErrorKind::MISSING_IMPORTS,
+                        &format!("Instruction {} calls import {}, which does not exist!", index, import),
+                    );
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            }
+
+            for Register(reg_index, loc) in ins.registers() {
+                if *loc == RegisterLocation::ConstantPool && *reg_index as usize >= constant_count {
+                    let mut err = ResurgenceError::from(
+                        ResurgenceErrorKind::REGISTER_OUT_OF_BOUNDS,
+                        &format!("Instruction {} uses constant {}, which does not exist!", index, reg_index),
+                    );
+                    create_new_trace!(err);
+                    return Err(err);
+                }
+            }
+        }
+
+        for (name, position) in &self.exports {
+            if *position >= length {
+                let mut err = ResurgenceError::from(
+                    ResurgenceErrorKind::INSTRUCTION_OUT_OF_BOUNDS,
+                    &format!("Export {} points to instruction {}, which does not exist!", name, position),
+                );
+                create_new_trace!(err);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
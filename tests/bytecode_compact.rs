@@ -0,0 +1,152 @@
+use resurgence::bytecode::{
+    read_bytecode, write_bytecode, write_bytecode_version, write_bytecode_with_options, InstructionEncoding,
+    WriteOptions,
+};
+use resurgence::codegen::{self, RVMReference};
+use resurgence::CodeHolder;
+
+/// Appends a required section with the given tag to `buf`
+fn push_section(buf: &mut Vec<u8>, tag: u8, data: &[u8]) {
+    buf.push(tag);
+    buf.push(0x01);
+    buf.extend_from_slice(&(data.len() as u64).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+/// Builds version 7.8 bytecode, which uses the fixed-size encoding, with raw instructions
+fn fixed_bytecode(instructions: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x08, 0x00];
+    push_section(&mut buf, 0x01, &0u32.to_be_bytes());
+    push_section(&mut buf, 0x02, &0u64.to_be_bytes());
+    push_section(&mut buf, 0x03, &0u64.to_be_bytes());
+    push_section(&mut buf, 0x04, instructions);
+    buf
+}
+
+/// Builds a program resembling compiler output: small register indices and short jumps
+fn typical_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 4);
+    for i in 0..50 {
+        let a = codegen::generate_int_constant(&mut holder, i);
+        let b = codegen::generate_int_constant(&mut holder, i * 2);
+        let c = codegen::generate_int_constant(&mut holder, 0);
+        codegen::generate_add(&mut holder, c, a, b);
+        let d = codegen::generate_int_constant(&mut holder, i);
+        codegen::generate_stack_push(&mut holder, (d, RVMReference::AS_IS));
+        codegen::generate_jump(&mut holder, 2);
+        codegen::generate_call(&mut holder, i as u64);
+        codegen::generate_ext_call(&mut holder, 3);
+    }
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+#[test]
+fn compact_encoding_round_trips() {
+    let holder = typical_holder();
+    let compact = write_bytecode(&holder).unwrap();
+    let read = read_bytecode(&compact).unwrap();
+
+    assert_eq!(read.instructions.len(), holder.instructions.len());
+    assert_eq!(write_bytecode(&read).unwrap(), compact);
+    assert_eq!(
+        write_bytecode_version(&read, 7, 8).unwrap(),
+        write_bytecode_version(&holder, 7, 8).unwrap()
+    );
+}
+
+#[test]
+fn write_options_select_the_encoding() {
+    let holder = typical_holder();
+    let default = write_bytecode_with_options(&holder, &WriteOptions::default()).unwrap();
+    assert_eq!(default, write_bytecode(&holder).unwrap());
+
+    let options = WriteOptions {
+        encoding: InstructionEncoding::Fixed,
+        ..Default::default()
+    };
+    let fixed = write_bytecode_with_options(&holder, &options).unwrap();
+    assert_eq!(fixed, write_bytecode_version(&holder, 7, 8).unwrap());
+
+    // both layouts hold the same instructions, also with the other options
+    for encoding in [InstructionEncoding::Compact, InstructionEncoding::Fixed] {
+        let options = WriteOptions {
+            checksum: true,
+            compress: true,
+            encoding,
+            ..Default::default()
+        };
+        let read = read_bytecode(&write_bytecode_with_options(&holder, &options).unwrap()).unwrap();
+        assert_eq!(write_bytecode(&read).unwrap(), default);
+    }
+}
+
+#[test]
+fn compact_encoding_is_smaller() {
+    let holder = typical_holder();
+    let fixed = write_bytecode_version(&holder, 7, 8).unwrap();
+    let compact = write_bytecode(&holder).unwrap();
+
+    let fixed_code = code_section_len(&fixed);
+    let compact_code = code_section_len(&compact);
+    println!("fixed: {} bytes, compact: {} bytes", fixed_code, compact_code);
+    assert!(compact_code * 3 < fixed_code);
+}
+
+/// Returns the length of the code section, which is the last section written
+fn code_section_len(bytes: &[u8]) -> usize {
+    let mut pos = 9;
+    loop {
+        let len = u64::from_be_bytes(bytes[pos + 2..pos + 10].try_into().unwrap()) as usize;
+        if bytes[pos] == 0x04 {
+            return len;
+        }
+        pos += 10 + len;
+    }
+}
+
+#[test]
+fn extreme_operands_survive_conversion() {
+    let mut code = Vec::new();
+    // mov GLOBAL u32::MAX (dereferenced), LOCAL 0
+    code.push(0x06);
+    code.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0x03, 0x02]);
+    code.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x04, 0x01]);
+    // jump i64::MIN, jump -1
+    code.push(0x03);
+    code.extend_from_slice(&i64::MIN.to_be_bytes());
+    code.push(0x03);
+    code.extend_from_slice(&(-1i64).to_be_bytes());
+    // call u64::MAX
+    code.push(0x04);
+    code.extend_from_slice(&u64::MAX.to_be_bytes());
+    // alloc u32::MAX
+    code.push(0x01);
+    code.extend_from_slice(&u32::MAX.to_be_bytes());
+    // frame_alloc 300, ACCUMULATOR
+    code.push(0x15);
+    code.extend_from_slice(&300u32.to_be_bytes());
+    code.push(0x02);
+    let fixed = fixed_bytecode(&code);
+
+    let compact = write_bytecode(&read_bytecode(&fixed).unwrap()).unwrap();
+    assert!(compact.len() < fixed.len());
+    let holder = read_bytecode(&compact).unwrap();
+    assert_eq!(write_bytecode_version(&holder, 7, 8).unwrap(), fixed);
+}
+
+#[test]
+fn rejects_oversized_compact_operands() {
+    let holder = typical_holder();
+    let mut compact = write_bytecode(&holder).unwrap();
+    // replace everything after the alloc opcode with an alloc of 2^32 registers
+    let code_start = compact.len() - code_section_len(&compact);
+    compact.truncate(code_start + 1);
+    compact.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x10]);
+    let len = (compact.len() - code_start) as u64;
+    compact[code_start - 8..code_start].copy_from_slice(&len.to_be_bytes());
+
+    let err = read_bytecode(&compact).err().unwrap();
+    assert!(err.to_string().contains("32 bits"));
+}
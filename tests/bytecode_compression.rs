@@ -0,0 +1,106 @@
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{
+    read_bytecode, read_bytecode_with_options, write_bytecode, write_bytecode_with_options,
+    ReadOptions, WriteOptions, DEFAULT_MAX_DECOMPRESSED_SIZE,
+};
+use resurgence::{codegen, CodeHolder};
+
+/// Builds a program with many similar strings, like generated scripts have
+fn string_heavy_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    for i in 0..200 {
+        let string = codegen::generate_string_constant(&mut holder, format!("dialogue.chapter_one.line_{}", i));
+        let len = codegen::generate_int_constant(&mut holder, 0);
+        codegen::generate_str_len(&mut holder, len, string);
+    }
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+fn compress_options() -> WriteOptions<'static> {
+    WriteOptions {
+        compress: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn compressed_bytecode_round_trips() {
+    let holder = string_heavy_holder();
+    let bytes = write_bytecode_with_options(&holder, &compress_options()).unwrap();
+
+    let read = read_bytecode(&bytes).unwrap();
+    assert_eq!(read.constant_pool, holder.constant_pool);
+    assert_eq!(read.instructions.len(), holder.instructions.len());
+    assert_eq!(write_bytecode(&read).unwrap(), write_bytecode(&holder).unwrap());
+}
+
+#[test]
+fn compression_shrinks_string_heavy_bytecode() {
+    let holder = string_heavy_holder();
+    let raw = write_bytecode(&holder).unwrap();
+    let compressed = write_bytecode_with_options(&holder, &compress_options()).unwrap();
+    assert!(compressed.len() * 4 < raw.len(), "{} vs {} bytes", compressed.len(), raw.len());
+}
+
+#[test]
+fn integrity_covers_uncompressed_bytecode() {
+    let options = WriteOptions {
+        checksum: true,
+        compress: true,
+        ..Default::default()
+    };
+    let bytes = write_bytecode_with_options(&string_heavy_holder(), &options).unwrap();
+
+    let options = ReadOptions {
+        require_checksum: true,
+        ..Default::default()
+    };
+    assert!(read_bytecode_with_options(&bytes, &options).is_ok());
+}
+
+#[test]
+fn rejects_corrupted_compressed_body() {
+    let mut bytes = write_bytecode_with_options(&string_heavy_holder(), &compress_options()).unwrap();
+    bytes.truncate(bytes.len() / 2);
+
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn rejects_unknown_header_flags() {
+    let mut bytes = write_bytecode(&string_heavy_holder()).unwrap();
+    // the flags byte follows the magic number and version
+    bytes[8] = 0x80;
+
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert!(err.to_string().contains("header flags"));
+}
+
+#[test]
+fn rejects_bodies_over_the_decompression_limit() {
+    // a long run of the same byte compresses to almost nothing
+    let mut holder = CodeHolder::new();
+    codegen::generate_string_constant(&mut holder, "a".repeat(1 << 20));
+    let bytes = write_bytecode_with_options(&holder, &compress_options()).unwrap();
+    assert!(bytes.len() < 4096, "{} bytes", bytes.len());
+
+    let options = ReadOptions {
+        max_decompressed_size: 64 * 1024,
+        ..Default::default()
+    };
+    let err = read_bytecode_with_options(&bytes, &options).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("limit"));
+
+    // the default limit leaves room for it
+    assert_eq!(read_bytecode(&bytes).unwrap().constant_pool, holder.constant_pool);
+    let options = ReadOptions {
+        max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        ..Default::default()
+    };
+    assert!(read_bytecode_with_options(&bytes, &options).is_ok());
+}
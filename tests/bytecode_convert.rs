@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{
+    convert_bytecode, read_bytecode, write_bytecode, write_bytecode_version, BytecodeView, LATEST_VERSION,
+};
+use resurgence::{codegen, CodeHolder, Constant};
+
+fn version_of(bytes: &[u8]) -> (u16, u16) {
+    (
+        u16::from_be_bytes([bytes[4], bytes[5]]),
+        u16::from_be_bytes([bytes[6], bytes[7]]),
+    )
+}
+
+#[test]
+fn downgrades_and_upgrades_vectors() {
+    let mut holder = CodeHolder::new();
+    holder.constant_pool = vec![Constant::Vec(vec![Constant::Int(1), Constant::String("a".to_string())])];
+    codegen::generate_alloc(&mut holder, 1);
+    let latest = write_bytecode(&holder).unwrap();
+
+    let old = convert_bytecode(&latest, 7, 0).unwrap();
+    assert_eq!(version_of(&old), (7, 0));
+    assert_eq!(read_bytecode(&old).unwrap().constant_pool, holder.constant_pool);
+
+    let upgraded = convert_bytecode(&old, LATEST_VERSION.0, LATEST_VERSION.1).unwrap();
+    assert_eq!(upgraded, latest);
+}
+
+#[test]
+fn refuses_unrepresentable_constants() {
+    let mut holder = CodeHolder::new();
+    holder.constant_pool = vec![Constant::Map(BTreeMap::new())];
+    let err = write_bytecode_version(&holder, 7, 4).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert!(write_bytecode_version(&holder, 7, 5).is_ok());
+
+    holder.constant_pool = vec![Constant::Vec(vec![Constant::Boolean(true); 256])];
+    let err = write_bytecode_version(&holder, 7, 5).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+#[test]
+fn refuses_unrepresentable_instructions() {
+    let mut holder = CodeHolder::new();
+    let reg = codegen::generate_int_constant(&mut holder, 0);
+    codegen::generate_jump_to(&mut holder, 0);
+    codegen::generate_vec_new(&mut holder, reg);
+    let bytes = write_bytecode(&holder).unwrap();
+
+    let err = convert_bytecode(&bytes, 7, 3).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert!(err.to_string().contains("VecNew"));
+    assert!(convert_bytecode(&bytes, 7, 4).is_ok());
+}
+
+#[test]
+fn refuses_unknown_versions() {
+    let bytes = write_bytecode(&CodeHolder::new()).unwrap();
+    assert_eq!(convert_bytecode(&bytes, 6, 0).unwrap_err().kind(), ErrorKind::Unsupported);
+    assert_eq!(convert_bytecode(&bytes, 7, 99).unwrap_err().kind(), ErrorKind::Unsupported);
+}
+
+#[test]
+fn refuses_custom_sections_before_sections_existed() {
+    let mut holder = CodeHolder::new();
+    holder.custom_sections.insert("debug".to_string(), vec![1, 2, 3]);
+    let bytes = write_bytecode(&holder).unwrap();
+
+    let err = convert_bytecode(&bytes, 7, 6).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+    holder.custom_sections.clear();
+    assert!(write_bytecode_version(&holder, 7, 6).is_ok());
+}
+
+#[test]
+fn refuses_to_read_features_newer_than_the_version() {
+    let mut holder = CodeHolder::new();
+    let reg = codegen::generate_int_constant(&mut holder, 0);
+    codegen::generate_vec_new(&mut holder, reg);
+    let mut bytes = write_bytecode_version(&holder, 7, 4).unwrap();
+    assert!(read_bytecode(&bytes).is_ok());
+
+    // 7.3 uses the same layout, but has no vector instructions
+    bytes[7] = 3;
+    assert!(read_bytecode(&bytes).err().unwrap().to_string().contains("VecNew"));
+    let view = BytecodeView::new(&bytes).unwrap();
+    assert!(view.instructions().any(|ins| ins.is_err()));
+
+    let mut holder = CodeHolder::new();
+    holder.constant_pool = vec![Constant::Map(BTreeMap::new())];
+    let mut bytes = write_bytecode_version(&holder, 7, 5).unwrap();
+    bytes[7] = 4;
+    assert!(read_bytecode(&bytes).err().unwrap().to_string().contains("Map constant"));
+    assert!(BytecodeView::new(&bytes).is_err());
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "ed25519")]
+
+use resurgence::bytecode::integrity::{sign_bytecode, verify_bytecode, Ed25519Signer, Ed25519Verifier};
+use resurgence::bytecode::{read_bytecode_with_options, write_bytecode_with_options};
+use resurgence::bytecode::{ReadOptions, WriteOptions};
+use resurgence::{codegen, CodeHolder};
+
+fn signed_bytes(signer: &Ed25519Signer) -> Vec<u8> {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+
+    let options = WriteOptions {
+        checksum: true,
+        signer: Some(signer),
+        ..Default::default()
+    };
+    write_bytecode_with_options(&holder, &options).unwrap()
+}
+
+#[test]
+fn ed25519_signature_round_trips() {
+    let signer = Ed25519Signer::new("publisher".to_string(), &[7; 32]);
+    let mut verifier = Ed25519Verifier::new();
+    verifier
+        .add_key("publisher".to_string(), &signer.public_key())
+        .unwrap();
+
+    let options = ReadOptions {
+        verifier: Some(&verifier),
+        ..Default::default()
+    };
+    let read = read_bytecode_with_options(&signed_bytes(&signer), &options).unwrap();
+    assert_eq!(read.instructions.len(), 2);
+}
+
+#[test]
+fn ed25519_rejects_other_key() {
+    let signer = Ed25519Signer::new("publisher".to_string(), &[7; 32]);
+    let other = Ed25519Signer::new("publisher".to_string(), &[8; 32]);
+    let mut verifier = Ed25519Verifier::new();
+    verifier
+        .add_key("publisher".to_string(), &other.public_key())
+        .unwrap();
+
+    let options = ReadOptions {
+        verifier: Some(&verifier),
+        ..Default::default()
+    };
+    assert!(read_bytecode_with_options(&signed_bytes(&signer), &options).is_err());
+}
+
+#[test]
+fn ed25519_detached_signature_round_trips() {
+    let signer = Ed25519Signer::new("publisher".to_string(), &[7; 32]);
+    let mut verifier = Ed25519Verifier::new();
+    verifier
+        .add_key("publisher".to_string(), &signer.public_key())
+        .unwrap();
+
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    let mut bytes = resurgence::bytecode::write_bytecode(&holder).unwrap();
+    let signature = sign_bytecode(&bytes, &signer);
+    assert!(verify_bytecode(&bytes, &signature, &verifier).is_ok());
+
+    bytes[8] ^= 0x01;
+    assert!(verify_bytecode(&bytes, &signature, &verifier).is_err());
+}
@@ -0,0 +1,115 @@
+//! Inputs found by the fuzz targets in `fuzz/`, kept so the fixes stay fixed
+
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode, BytecodeView};
+use resurgence::{CodeHolder, Interpreter};
+
+/// Version 7.9 header without flags
+const HEADER: [u8; 9] = [0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x09, 0x00];
+
+fn section(tag: u8, flags: u8, length: u64, data: &[u8]) -> Vec<u8> {
+    let mut buf = vec![tag, flags];
+    buf.extend_from_slice(&length.to_be_bytes());
+    buf.extend_from_slice(data);
+    buf
+}
+
+/// Encodes a boolean nested in `depth` vector constants
+fn nested_vec(depth: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for _ in 0..depth {
+        buf.push(0x06);
+        buf.extend_from_slice(&1u64.to_be_bytes());
+    }
+    buf.extend_from_slice(&[0x04, 0x01]);
+    buf
+}
+
+/// CRC-32 as used by snapshots
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB88320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+#[test]
+fn huge_string_length_is_an_error() {
+    // one string constant claiming to be 2^56 bytes long
+    let mut constants = 1u32.to_be_bytes().to_vec();
+    constants.push(0x03);
+    constants.extend_from_slice(&(1u64 << 56).to_be_bytes());
+    constants.extend_from_slice(b"hello");
+
+    let mut buf = HEADER.to_vec();
+    buf.extend(section(0x01, 0x01, constants.len() as u64, &constants));
+    assert!(read_bytecode(&buf).is_err());
+    assert!(BytecodeView::new(&buf).is_err());
+}
+
+#[test]
+fn huge_section_length_is_an_error() {
+    let mut buf = HEADER.to_vec();
+    buf.extend(section(0x40, 0x00, u64::MAX, &[0; 16]));
+    assert!(read_bytecode(&buf).is_err());
+    assert!(BytecodeView::new(&buf).is_err());
+}
+
+#[test]
+fn exports_are_written_in_a_stable_order() {
+    let mut exports = 3u64.to_be_bytes().to_vec();
+    for (name, position) in [("zeta", 0u64), ("alpha", 0), ("mid", 0)] {
+        exports.extend_from_slice(&(name.len() as u64).to_be_bytes());
+        exports.extend_from_slice(name.as_bytes());
+        exports.extend_from_slice(&position.to_be_bytes());
+    }
+    let mut buf = HEADER.to_vec();
+    buf.extend(section(0x01, 0x01, 4, &0u32.to_be_bytes()));
+    buf.extend(section(0x02, 0x01, 8, &0u64.to_be_bytes()));
+    buf.extend(section(0x03, 0x01, exports.len() as u64, &exports));
+    buf.extend(section(0x04, 0x01, 1, &[0x19]));
+
+    // every read builds a new HashMap, so an unsorted writer would change the order between them
+    let written = write_bytecode(&read_bytecode(&buf).unwrap()).unwrap();
+    for _ in 0..10 {
+        assert_eq!(write_bytecode(&read_bytecode(&buf).unwrap()).unwrap(), written);
+    }
+    let view = BytecodeView::new(&written).unwrap();
+    let names: Vec<_> = view.exports().iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["alpha", "mid", "zeta"]);
+}
+
+#[test]
+fn deeply_nested_constant_is_an_error() {
+    // deep enough to overflow the stack of a decoder without a depth limit
+    let mut constants = 1u32.to_be_bytes().to_vec();
+    constants.extend(nested_vec(200_000));
+
+    let mut buf = HEADER.to_vec();
+    buf.extend(section(0x01, 0x01, constants.len() as u64, &constants));
+    assert_eq!(read_bytecode(&buf).err().unwrap().kind(), ErrorKind::InvalidData);
+    assert_eq!(BytecodeView::new(&buf).err().unwrap().kind(), ErrorKind::InvalidData);
+    assert_eq!(Interpreter::from_bytes(buf).err().unwrap().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn deeply_nested_snapshot_value_is_an_error() {
+    let mut interpreter = Interpreter::from(CodeHolder::new());
+    let mut snapshot = interpreter.snapshot().unwrap();
+
+    // replace the empty stack at the end with one holding a deeply nested value
+    snapshot.truncate(snapshot.len() - 12);
+    snapshot.extend_from_slice(&1u64.to_be_bytes());
+    snapshot.extend(nested_vec(200_000));
+    let checksum = crc32(&snapshot);
+    snapshot.extend_from_slice(&checksum.to_be_bytes());
+    assert_eq!(interpreter.restore(&snapshot).unwrap_err().kind(), ErrorKind::InvalidData);
+}
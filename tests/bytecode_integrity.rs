@@ -0,0 +1,230 @@
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{
+    read_bytecode, read_bytecode_with_options, sign_bytecode, verify_bytecode, write_bytecode,
+    write_bytecode_with_options, ReadOptions, SignatureVerifier, Signer, WriteOptions,
+};
+use resurgence::{codegen, CodeHolder, Constant};
+
+/// Toy keyed signer, the signature is a keyed sum of the data
+struct TestSigner {
+    key_id: String,
+    key: u8,
+}
+
+fn toy_signature(key: u8, data: &[u8]) -> Vec<u8> {
+    let sum = data
+        .iter()
+        .fold(key as u64, |acc, byte| acc.wrapping_mul(31).wrapping_add((*byte ^ key) as u64));
+    sum.to_be_bytes().to_vec()
+}
+
+impl Signer for TestSigner {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        toy_signature(self.key, data)
+    }
+}
+
+/// Only trusts the key "trusted", which uses the key byte 0x5A
+struct TestVerifier;
+
+impl SignatureVerifier for TestVerifier {
+    fn verify(&self, key_id: &str, data: &[u8], signature: &[u8]) -> bool {
+        key_id == "trusted" && toy_signature(0x5A, data) == signature
+    }
+}
+
+fn sample_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    holder.constant_pool.push(Constant::String("data".to_string()));
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+fn signed_bytes(key_id: &str, key: u8) -> Vec<u8> {
+    let signer = TestSigner {
+        key_id: key_id.to_string(),
+        key,
+    };
+    let options = WriteOptions {
+        checksum: true,
+        signer: Some(&signer),
+        ..Default::default()
+    };
+    write_bytecode_with_options(&sample_holder(), &options).unwrap()
+}
+
+#[test]
+fn checksum_round_trips() {
+    let options = WriteOptions {
+        checksum: true,
+        ..Default::default()
+    };
+    let bytes = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+
+    let options = ReadOptions {
+        require_checksum: true,
+        ..Default::default()
+    };
+    let read = read_bytecode_with_options(&bytes, &options).unwrap();
+    assert_eq!(read.constant_pool, sample_holder().constant_pool);
+    assert_eq!(read.instructions.len(), 2);
+
+    // readers that do not require checksums accept the file as well
+    assert!(read_bytecode(&bytes).is_ok());
+}
+
+#[test]
+fn detects_corruption() {
+    let options = WriteOptions {
+        checksum: true,
+        ..Default::default()
+    };
+    let mut bytes = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    // flip a bit inside the "data" string constant
+    let pos = bytes.windows(4).position(|w| w == b"data").unwrap();
+    bytes[pos] ^= 0x01;
+
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("checksum"));
+}
+
+#[test]
+fn requires_checksum_when_asked() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    let options = ReadOptions {
+        require_checksum: true,
+        ..Default::default()
+    };
+
+    let err = read_bytecode_with_options(&bytes, &options).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn rejects_sections_after_checksum() {
+    let options = WriteOptions {
+        checksum: true,
+        ..Default::default()
+    };
+    let mut bytes = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    bytes.extend_from_slice(&[0xF0, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn accepts_trusted_signature() {
+    let bytes = signed_bytes("trusted", 0x5A);
+    let options = ReadOptions {
+        require_checksum: true,
+        verifier: Some(&TestVerifier),
+        ..Default::default()
+    };
+
+    let read = read_bytecode_with_options(&bytes, &options).unwrap();
+    assert_eq!(read.instructions.len(), 2);
+}
+
+#[test]
+fn rejects_untrusted_signature() {
+    let options = ReadOptions {
+        verifier: Some(&TestVerifier),
+        ..Default::default()
+    };
+
+    let err = read_bytecode_with_options(&signed_bytes("unknown", 0x5A), &options).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let err = read_bytecode_with_options(&signed_bytes("trusted", 0x11), &options).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn rejects_tampered_signed_bytecode() {
+    // drop the checksum so only the signature can catch the change
+    let signer = TestSigner {
+        key_id: "trusted".to_string(),
+        key: 0x5A,
+    };
+    let options = WriteOptions {
+        signer: Some(&signer),
+        ..Default::default()
+    };
+    let mut bytes = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    let pos = bytes.windows(4).position(|w| w == b"data").unwrap();
+    bytes[pos] = b'D';
+
+    let options = ReadOptions {
+        verifier: Some(&TestVerifier),
+        ..Default::default()
+    };
+    let err = read_bytecode_with_options(&bytes, &options).err().unwrap();
+    assert!(err.to_string().contains("signature"));
+}
+
+#[test]
+fn rejects_unsigned_bytecode() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    let options = ReadOptions {
+        verifier: Some(&TestVerifier),
+        ..Default::default()
+    };
+
+    let err = read_bytecode_with_options(&bytes, &options).err().unwrap();
+    assert!(err.to_string().contains("not signed"));
+}
+
+#[test]
+fn ignores_signature_without_verifier() {
+    let read = read_bytecode(&signed_bytes("unknown", 0x11)).unwrap();
+    assert_eq!(read.instructions.len(), 2);
+}
+
+#[test]
+fn detached_signature_round_trips() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    let signer = TestSigner {
+        key_id: "trusted".to_string(),
+        key: 0x5A,
+    };
+    let signature = sign_bytecode(&bytes, &signer);
+    assert!(verify_bytecode(&bytes, &signature, &TestVerifier).is_ok());
+
+    // the bytecode itself is not changed, and still reads without a verifier
+    assert_eq!(bytes, write_bytecode(&sample_holder()).unwrap());
+    assert!(read_bytecode(&bytes).is_ok());
+}
+
+#[test]
+fn detached_signature_rejects_tampering_and_unknown_keys() {
+    let mut bytes = write_bytecode(&sample_holder()).unwrap();
+    let trusted = TestSigner {
+        key_id: "trusted".to_string(),
+        key: 0x5A,
+    };
+    let signature = sign_bytecode(&bytes, &trusted);
+
+    let untrusted = TestSigner {
+        key_id: "someone".to_string(),
+        key: 0x11,
+    };
+    let err = verify_bytecode(&bytes, &sign_bytecode(&bytes, &untrusted), &TestVerifier).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("someone"));
+
+    for truncated in [&signature[..4], &signature[..10]] {
+        let err = verify_bytecode(&bytes, truncated, &TestVerifier).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0x01;
+    assert!(verify_bytecode(&bytes, &signature, &TestVerifier).is_err());
+}
@@ -0,0 +1,78 @@
+use std::io::{self, Read};
+
+use resurgence::bytecode::{read_bytecode_from, write_bytecode, write_bytecode_to};
+use resurgence::{codegen, CodeHolder, Constant};
+
+/// Hands out a single byte per read call, like a slow network or decompression stream would
+struct OneByteReader<'a>(&'a [u8]);
+
+impl Read for OneByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+fn sample_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    let reg = codegen::generate_string_constant(&mut holder, "hello".to_string());
+    let len = codegen::generate_int_constant(&mut holder, 0);
+    holder.constant_pool.push(Constant::Vec(vec![Constant::Int(4), Constant::Double(2.5)]));
+    codegen::generate_alloc(&mut holder, 2);
+    codegen::generate_str_len(&mut holder, len, reg);
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+#[test]
+fn writer_matches_buffered_output() {
+    let holder = sample_holder();
+    let mut out = Vec::new();
+    write_bytecode_to(&holder, &mut out).unwrap();
+    assert_eq!(out, write_bytecode(&holder).unwrap());
+}
+
+#[test]
+fn reads_from_streaming_reader() {
+    let holder = sample_holder();
+    let bytes = write_bytecode(&holder).unwrap();
+
+    let read = read_bytecode_from(OneByteReader(&bytes)).unwrap();
+    assert_eq!(read.constant_pool, holder.constant_pool);
+    assert_eq!(read.instructions.len(), holder.instructions.len());
+    assert_eq!(write_bytecode(&read).unwrap(), bytes);
+}
+
+#[test]
+fn truncated_instruction_is_an_error() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    // drop the Ret instruction and the last operand of the StrLen instruction before it
+    let truncated = &bytes[..bytes.len() - 2];
+
+    let err = read_bytecode_from(truncated).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn file_round_trip() {
+    let path = std::env::temp_dir().join(format!("resurgence-io-{}.rvm", std::process::id()));
+    let holder = sample_holder();
+    resurgence::bytecode::write_bytecode_file(&holder, &path).unwrap();
+    let read = resurgence::bytecode::read_bytecode_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.constant_pool, holder.constant_pool);
+}
+
+#[test]
+fn missing_instruction_is_a_write_error() {
+    // instructions are taken out of the holder while they run
+    let mut holder = sample_holder();
+    holder.instructions[1] = None;
+
+    assert!(write_bytecode(&holder).is_err());
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use resurgence::bytecode::{
+    bytecode_to_json, code_holder_from_json, code_holder_to_json, json_to_bytecode, read_bytecode,
+    write_bytecode, write_bytecode_version,
+};
+use resurgence::codegen::{self, RVMReference};
+use resurgence::{CodeHolder, Constant, MapKey, ModuleMetadata, SemanticVersion};
+
+/// Builds a holder using every kind of data the JSON representation has to cover
+fn sample_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 2);
+    let a = codegen::generate_int_constant(&mut holder, 4);
+    let b = codegen::generate_string_constant(&mut holder, "hello".to_string());
+    codegen::generate_stack_push(&mut holder, (a, RVMReference::AS_IS));
+    codegen::generate_stack_push(&mut holder, (b, RVMReference::DEREFERENCE));
+    codegen::generate_jump(&mut holder, -2);
+    codegen::generate_ext_call(&mut holder, 0);
+    codegen::generate_return(&mut holder);
+
+    let mut map = BTreeMap::new();
+    map.insert(MapKey::Int(-1), Constant::Double(0.5));
+    map.insert(MapKey::String("list".to_string()), Constant::Vec(vec![Constant::Boolean(true)]));
+    holder.constant_pool.push(Constant::Map(map));
+
+    holder.metadata = Some(ModuleMetadata {
+        name: "sample".to_string(),
+        version: SemanticVersion::new(1, 2, 3),
+        min_host_api: SemanticVersion::new(0, 1, 0),
+        ..Default::default()
+    });
+    holder.custom_sections.insert("debug".to_string(), vec![1, 2, 3]);
+    holder
+}
+
+#[test]
+fn code_holder_round_trips() {
+    let holder = sample_holder();
+    let json = code_holder_to_json(&holder).unwrap();
+    let read = code_holder_from_json(&json).unwrap();
+
+    assert_eq!(read.constant_pool, holder.constant_pool);
+    assert_eq!(read.metadata, holder.metadata);
+    assert_eq!(read.custom_sections, holder.custom_sections);
+    assert_eq!(write_bytecode(&read).unwrap(), write_bytecode(&holder).unwrap());
+}
+
+#[test]
+fn bytecode_round_trips_through_json() {
+    let data = write_bytecode(&sample_holder()).unwrap();
+    let json = bytecode_to_json(&data).unwrap();
+    assert_eq!(json_to_bytecode(&json).unwrap(), data);
+}
+
+#[test]
+fn old_bytecode_converts_to_latest_version() {
+    let mut holder = sample_holder();
+    holder.metadata = None;
+    holder.custom_sections.clear();
+    let old = write_bytecode_version(&holder, 7, 6).unwrap();
+    let data = json_to_bytecode(&bytecode_to_json(&old).unwrap()).unwrap();
+    assert_eq!(data, write_bytecode(&read_bytecode(&old).unwrap()).unwrap());
+}
+
+#[test]
+fn output_is_stable() {
+    // exports live in a HashMap, so the JSON has to sort them to be diffable
+    let json = r#"{
+        "instructions": [],
+        "constant_pool": [],
+        "imports": ["print"],
+        "exports": {"zeta": 3, "alpha": 1, "mid": 2},
+        "metadata": null,
+        "custom_sections": {}
+    }"#;
+    let first = code_holder_to_json(&code_holder_from_json(json).unwrap()).unwrap();
+    for _ in 0..10 {
+        let again = code_holder_to_json(&code_holder_from_json(&first).unwrap()).unwrap();
+        assert_eq!(again, first);
+    }
+    let alpha = first.find("alpha").unwrap();
+    let mid = first.find("mid").unwrap();
+    assert!(alpha < mid && mid < first.find("zeta").unwrap());
+}
+
+#[test]
+fn invalid_json_is_rejected() {
+    match code_holder_from_json("{\"instructions\": 5}") {
+        Ok(_) => panic!("invalid JSON was accepted"),
+        Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidData),
+    }
+}
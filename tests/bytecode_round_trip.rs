@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode, write_bytecode_version, BytecodeView};
+use resurgence::{CodeHolder, Constant, MapKey};
+
+/// Small xorshift generator so the property tests are reproducible without extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.below(12);
+        (0..len)
+            .map(|_| match self.below(4) {
+                0 => 'é',
+                1 => '字',
+                _ => (b'a' + self.below(26) as u8) as char,
+            })
+            .collect()
+    }
+
+    fn map_key(&mut self) -> MapKey {
+        match self.below(2) {
+            0 => MapKey::Int(self.next() as i64),
+            _ => MapKey::String(self.string()),
+        }
+    }
+
+    /// Generates a random constant, nesting vectors and maps up to `depth` levels deep
+    fn constant(&mut self, depth: u32) -> Constant {
+        let variants = if depth == 0 { 4 } else { 6 };
+        match self.below(variants) {
+            0 => Constant::Int(self.next() as i64),
+            // keep doubles finite so they compare equal after the round trip
+            1 => Constant::Double((self.next() as i32) as f64 / 7.0),
+            2 => Constant::String(self.string()),
+            3 => Constant::Boolean(self.below(2) == 1),
+            4 => {
+                let len = self.below(6);
+                Constant::Vec((0..len).map(|_| self.constant(depth - 1)).collect())
+            }
+            _ => {
+                let len = self.below(6);
+                let mut map = BTreeMap::new();
+                for _ in 0..len {
+                    map.insert(self.map_key(), self.constant(depth - 1));
+                }
+                Constant::Map(map)
+            }
+        }
+    }
+}
+
+fn round_trip(pool: Vec<Constant>) -> Vec<Constant> {
+    let mut holder = CodeHolder::new();
+    holder.constant_pool = pool;
+    let bytes = write_bytecode(&holder).unwrap();
+    read_bytecode(&bytes).unwrap().constant_pool
+}
+
+/// Builds bytecode with the given version and a raw, already encoded constants table
+fn raw_bytecode(major: u16, minor: u16, constants: &[&[u8]]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88];
+    buf.extend_from_slice(&major.to_be_bytes());
+    buf.extend_from_slice(&minor.to_be_bytes());
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.extend_from_slice(constant);
+    }
+    // empty imports and exports tables
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf
+}
+
+#[test]
+fn every_variant_round_trips() {
+    let mut map = BTreeMap::new();
+    map.insert(MapKey::Int(-3), Constant::Boolean(true));
+    map.insert(MapKey::String("key".to_string()), Constant::Vec(vec![Constant::Int(1)]));
+
+    let pool = vec![
+        Constant::Int(i64::MIN),
+        Constant::Int(i64::MAX),
+        Constant::Double(-1.5),
+        Constant::String(String::new()),
+        Constant::String("hello, wörld".to_string()),
+        Constant::Boolean(false),
+        Constant::Boolean(true),
+        Constant::Vec(Vec::new()),
+        Constant::Vec(vec![Constant::Vec(vec![Constant::Int(7)]), Constant::Double(0.25)]),
+        Constant::Map(BTreeMap::new()),
+        Constant::Map(map),
+    ];
+    assert_eq!(round_trip(pool.clone()), pool);
+}
+
+#[test]
+fn address_constant_round_trips() {
+    // Address constants can not be built through the public API, so check that the bytes survive
+    let address: &[u8] = &[0x05, 0x00, 0x00, 0x00, 0x2A, 0x04];
+    let vec_of_address: &[u8] = &[
+        0x06, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x05, 0x00, 0x00, 0x00, 0x01, 0x03,
+    ];
+    let bytes = raw_bytecode(7, 6, &[address, vec_of_address]);
+
+    let holder = read_bytecode(&bytes).unwrap();
+    assert_eq!(write_bytecode_version(&holder, 7, 6).unwrap(), bytes);
+}
+
+#[test]
+fn random_constants_round_trip() {
+    let mut rng = Rng(0x9E3779B97F4A7C15);
+    for _ in 0..500 {
+        let len = rng.below(8);
+        let pool: Vec<Constant> = (0..len).map(|_| rng.constant(3)).collect();
+        assert_eq!(round_trip(pool.clone()), pool);
+    }
+}
+
+#[test]
+fn large_vector_round_trips() {
+    let pool = vec![Constant::Vec((0..1000).map(Constant::Int).collect())];
+    assert_eq!(round_trip(pool.clone()), pool);
+}
+
+#[test]
+fn reads_old_vector_layout() {
+    // 7.5 and older used a single byte for the number of elements
+    let vec_constant: &[u8] = &[0x06, 0x02, 0x04, 0x01, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x09];
+    let bytes = raw_bytecode(7, 5, &[vec_constant]);
+
+    let holder = read_bytecode(&bytes).unwrap();
+    assert_eq!(
+        holder.constant_pool,
+        vec![Constant::Vec(vec![Constant::Boolean(true), Constant::Int(9)])]
+    );
+}
+
+/// Builds a vector constant holding a single Int, nested in `depth` vectors in total
+fn nested_vec(depth: usize) -> Constant {
+    let mut constant = Constant::Int(1);
+    for _ in 0..depth {
+        constant = Constant::Vec(vec![constant]);
+    }
+    constant
+}
+
+#[test]
+fn nesting_up_to_the_depth_limit_round_trips() {
+    let pool = vec![nested_vec(256)];
+    assert_eq!(round_trip(pool.clone()), pool);
+
+    let mut holder = CodeHolder::new();
+    holder.constant_pool = vec![nested_vec(257)];
+    assert_eq!(write_bytecode(&holder).unwrap_err().kind(), ErrorKind::Unsupported);
+}
+
+#[test]
+fn deeply_nested_constants_are_rejected() {
+    let mut constant = Vec::new();
+    for _ in 0..300 {
+        constant.push(0x06);
+        constant.extend_from_slice(&1u64.to_be_bytes());
+    }
+    constant.extend_from_slice(&[0x04, 0x01]);
+    let bytes = raw_bytecode(7, 6, &[&constant]);
+
+    assert_eq!(read_bytecode(&bytes).err().unwrap().kind(), ErrorKind::InvalidData);
+    assert_eq!(BytecodeView::new(&bytes).err().unwrap().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn element_counts_beyond_the_input_are_rejected() {
+    let map = BTreeMap::from([(MapKey::Int(1), Constant::Int(1))]);
+    for (tag, constant) in [(0x06, Constant::Vec(vec![Constant::Int(1)])), (0x07, Constant::Map(map))] {
+        let mut holder = CodeHolder::new();
+        holder.constant_pool = vec![constant];
+        let mut bytes = write_bytecode(&holder).unwrap();
+
+        // claim far more elements than the constants section holds
+        let count = bytes.windows(9).position(|window| window == [tag, 0, 0, 0, 0, 0, 0, 0, 1]).unwrap() + 1;
+        bytes[count..count + 8].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(read_bytecode(&bytes).err().unwrap().kind(), ErrorKind::InvalidData);
+        assert_eq!(BytecodeView::new(&bytes).err().unwrap().kind(), ErrorKind::InvalidData);
+    }
+}
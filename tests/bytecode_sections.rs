@@ -0,0 +1,73 @@
+use std::io::ErrorKind;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{codegen, CodeHolder, Constant};
+
+/// Appends a section with the given tag and flags to `buf`
+fn push_section(buf: &mut Vec<u8>, tag: u8, flags: u8, data: &[u8]) {
+    buf.push(tag);
+    buf.push(flags);
+    buf.extend_from_slice(&(data.len() as u64).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn sample_bytecode() -> (CodeHolder, Vec<u8>) {
+    let mut holder = CodeHolder::new();
+    holder.constant_pool.push(Constant::String("data".to_string()));
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+    let bytes = write_bytecode(&holder).unwrap();
+    (holder, bytes)
+}
+
+#[test]
+fn custom_sections_round_trip() {
+    let (mut holder, _) = sample_bytecode();
+    holder.custom_sections.insert("debug-info".to_string(), vec![0xDE, 0xAD]);
+    holder.custom_sections.insert("empty".to_string(), Vec::new());
+
+    let read = read_bytecode(&write_bytecode(&holder).unwrap()).unwrap();
+    assert_eq!(read.custom_sections, holder.custom_sections);
+    assert_eq!(read.constant_pool, holder.constant_pool);
+    assert_eq!(read.instructions.len(), 2);
+}
+
+#[test]
+fn skips_unknown_optional_sections() {
+    let (holder, mut bytes) = sample_bytecode();
+    push_section(&mut bytes, 0xF0, 0x00, &[1, 2, 3, 4]);
+
+    let read = read_bytecode(&bytes).unwrap();
+    assert_eq!(read.constant_pool, holder.constant_pool);
+    assert_eq!(read.instructions.len(), 2);
+    assert!(read.custom_sections.is_empty());
+}
+
+#[test]
+fn rejects_unknown_required_sections() {
+    let (_, mut bytes) = sample_bytecode();
+    push_section(&mut bytes, 0xF0, 0x01, &[1, 2, 3, 4]);
+
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert!(err.to_string().contains("required section"));
+}
+
+#[test]
+fn rejects_sections_with_wrong_length() {
+    // an imports section that claims to be longer than its contents
+    let (_, mut bytes) = sample_bytecode();
+    push_section(&mut bytes, 0x02, 0x01, &[0, 0, 0, 0, 0, 0, 0, 0, 0xFF]);
+    let err = read_bytecode(&bytes).err().unwrap();
+    assert!(err.to_string().contains("does not match its length"));
+
+    // a section that is cut off by the end of the file
+    let (_, mut bytes) = sample_bytecode();
+    push_section(&mut bytes, 0xF0, 0x00, &[1, 2, 3, 4]);
+    bytes.truncate(bytes.len() - 2);
+    assert!(read_bytecode(&bytes).is_err());
+
+    // an instruction that runs past the end of the code section
+    let (_, mut bytes) = sample_bytecode();
+    push_section(&mut bytes, 0x04, 0x01, &[0x01, 0x80]);
+    assert_eq!(read_bytecode(&bytes).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+}
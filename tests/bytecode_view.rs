@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind};
+use std::sync::Mutex;
+
+use resurgence::bytecode::{
+    read_bytecode, write_bytecode, write_bytecode_version, write_bytecode_with_options,
+    BytecodeView, ReadOptions, SignatureVerifier, Signer, WriteOptions,
+};
+use resurgence::{codegen, CodeHolder, Constant, ExecutionEngine, Interpreter, MapKey, ResurgenceState};
+
+fn sample_holder() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    let string = codegen::generate_string_constant(&mut holder, "hello, wörld".to_string());
+    let len = codegen::generate_int_constant(&mut holder, 0);
+    let mut map = BTreeMap::new();
+    map.insert(MapKey::String("key".to_string()), Constant::Vec(vec![Constant::Double(0.5)]));
+    holder.constant_pool.push(Constant::Map(map));
+    codegen::generate_alloc(&mut holder, 2);
+    codegen::generate_str_len(&mut holder, len, string);
+    codegen::generate_jump(&mut holder, -2);
+    codegen::generate_return(&mut holder);
+    holder.custom_sections.insert("debug".to_string(), vec![1, 2, 3]);
+    holder
+}
+
+/// Checks that a view of `bytes` decodes to the same code as `read_bytecode`
+fn assert_matches_reader(bytes: &[u8]) {
+    let view = BytecodeView::new(bytes).unwrap();
+    let holder = read_bytecode(bytes).unwrap();
+
+    assert_eq!(view.constant_count(), holder.constant_pool.len());
+    for (index, constant) in holder.constant_pool.iter().enumerate() {
+        assert_eq!(&view.constant(index).unwrap(), constant);
+    }
+    let instructions: Vec<_> = view.instructions().collect::<Result<_, _>>().unwrap();
+    assert_eq!(instructions.len(), holder.instructions.len());
+
+    let converted = view.to_code_holder().unwrap();
+    assert_eq!(write_bytecode(&converted).unwrap(), write_bytecode(&holder).unwrap());
+}
+
+#[test]
+fn view_matches_reader() {
+    let holder = sample_holder();
+    assert_matches_reader(&write_bytecode(&holder).unwrap());
+    // fixed-size operands
+    assert_matches_reader(&write_bytecode_version(&holder, 7, 8).unwrap());
+
+    // no sections
+    let mut flat = CodeHolder::new();
+    flat.constant_pool = holder.constant_pool.clone();
+    codegen::generate_return(&mut flat);
+    assert_matches_reader(&write_bytecode_version(&flat, 7, 6).unwrap());
+}
+
+#[test]
+fn strings_are_borrowed_from_the_buffer() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    let view = BytecodeView::new(&bytes).unwrap();
+
+    let string = view.string_constant(0).unwrap();
+    assert_eq!(string, "hello, wörld");
+    let range = bytes.as_ptr_range();
+    assert!(range.contains(&string.as_ptr()));
+
+    assert_eq!(view.string_constant(1), None);
+    assert_eq!(view.string_constant(10), None);
+    assert_eq!(view.custom_section("debug"), Some(&[1u8, 2, 3][..]));
+}
+
+#[test]
+fn rejects_invalid_bytecode() {
+    let bytes = write_bytecode(&sample_holder()).unwrap();
+    let pos = bytes.windows(5).position(|w| w == b"hello").unwrap();
+
+    // a string constant cut off by the end of the bytecode
+    let err = BytecodeView::new(&bytes[..pos + 2]).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // a string constant that is not UTF-8
+    let mut invalid = bytes.clone();
+    invalid[pos] = 0xFF;
+    let err = BytecodeView::new(&invalid).err().unwrap();
+    assert!(err.to_string().contains("UTF-8"));
+
+    let options = WriteOptions {
+        compress: true,
+        ..Default::default()
+    };
+    let compressed = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    let err = BytecodeView::new(&compressed).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+}
+
+static RECORDED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record(state: &mut ResurgenceState) -> Result<(), io::Error> {
+    RECORDED.lock().unwrap().push(state.get_string()?);
+    Ok(())
+}
+
+/// Version 7.6 bytecode that pushes constant 1 on the stack and calls the "record" import
+fn record_program() -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    // constants: an int and a string
+    buf.extend_from_slice(&2u32.to_be_bytes());
+    buf.push(0x01);
+    buf.extend_from_slice(&7i64.to_be_bytes());
+    buf.push(0x03);
+    buf.extend_from_slice(&9u64.to_be_bytes());
+    buf.extend_from_slice(b"from view");
+    // imports
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    // exports
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    // stack_push CONST 1 AS_IS, ext_call 0
+    buf.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01]);
+    buf.push(0x05);
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf
+}
+
+#[test]
+fn interpreter_runs_from_bytes() {
+    let mut it = Interpreter::from_bytes(record_program()).unwrap();
+    it.register_function(record, "record".to_string());
+    it.execute_instruction(0).unwrap();
+
+    let mut it = Interpreter::from(read_bytecode(&record_program()).unwrap());
+    it.register_function(record, "record".to_string());
+    it.execute_instruction(0).unwrap();
+
+    assert_eq!(*RECORDED.lock().unwrap(), vec!["from view", "from view"]);
+}
+
+/// Toy signer whose signature is the length of the data
+struct LengthSigner;
+
+impl Signer for LengthSigner {
+    fn key_id(&self) -> &str {
+        "length"
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        (data.len() as u64).to_be_bytes().to_vec()
+    }
+}
+
+impl SignatureVerifier for LengthSigner {
+    fn verify(&self, key_id: &str, data: &[u8], signature: &[u8]) -> bool {
+        key_id == "length" && signature == self.sign(data)
+    }
+}
+
+#[test]
+fn view_validates_checksums() {
+    let options = WriteOptions {
+        checksum: true,
+        ..Default::default()
+    };
+    let mut bytes = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    assert_matches_reader(&bytes);
+
+    let require = ReadOptions {
+        require_checksum: true,
+        ..Default::default()
+    };
+    assert!(BytecodeView::new_with_options(&bytes, &require).is_ok());
+    let unchecked = write_bytecode(&sample_holder()).unwrap();
+    let err = BytecodeView::new_with_options(&unchecked, &require).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    // checksums that are present are validated even when they are not required
+    let pos = bytes.windows(5).position(|w| w == b"hello").unwrap();
+    bytes[pos] ^= 0x01;
+    let err = BytecodeView::new(&bytes).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(Interpreter::from_bytes(bytes).is_err());
+}
+
+#[test]
+fn from_bytes_verifies_signatures() {
+    let options = WriteOptions {
+        checksum: true,
+        signer: Some(&LengthSigner),
+        ..Default::default()
+    };
+    let signed = write_bytecode_with_options(&sample_holder(), &options).unwrap();
+    let verify = ReadOptions {
+        verifier: Some(&LengthSigner),
+        ..Default::default()
+    };
+    assert!(BytecodeView::new_with_options(&signed, &verify).is_ok());
+    assert!(Interpreter::from_bytes_with_options(signed.clone(), &verify).is_ok());
+
+    // a signature made over other data is rejected
+    let mut forged = write_bytecode_with_options(&CodeHolder::new(), &options).unwrap();
+    let signature_len = 8;
+    let len = forged.len();
+    forged[len - signature_len..].copy_from_slice(&(signed.len() as u64).to_be_bytes());
+    let err = Interpreter::from_bytes_with_options(forged, &verify).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let unsigned = write_bytecode(&sample_holder()).unwrap();
+    let err = Interpreter::from_bytes_with_options(unsigned, &verify).err().unwrap();
+    assert!(err.to_string().contains("not signed"));
+}
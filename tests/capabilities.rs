@@ -0,0 +1,109 @@
+use std::io::Error;
+
+use resurgence::bytecode::read_bytecode;
+use resurgence::{CodeHolder, ExecutionEngine, ImportAudit, Interpreter, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const RET: u8 = 0x19;
+
+/// Builds version 7.6 bytecode that calls every import once in order
+fn program(imports: &[&str]) -> CodeHolder {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&0u32.to_be_bytes());
+    buf.extend_from_slice(&(imports.len() as u64).to_be_bytes());
+    for import in imports {
+        buf.extend_from_slice(&(import.len() as u64).to_be_bytes());
+        buf.extend_from_slice(import.as_bytes());
+    }
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    for index in 0..imports.len() as u64 {
+        buf.push(EXT_CALL);
+        buf.extend_from_slice(&index.to_be_bytes());
+    }
+    buf.push(RET);
+    read_bytecode(&buf).unwrap()
+}
+
+fn noop(_: &mut ResurgenceState) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Registers `print` without a capability and `read_file` in the "fs" capability
+fn interpreter(imports: &[&str]) -> Interpreter {
+    let mut interpreter = Interpreter::from(program(imports));
+    interpreter.register_function(noop, String::from("print"));
+    interpreter.register_function_with_capability(noop, String::from("read_file"), String::from("fs"));
+    interpreter
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) -> String {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => {
+            let message = format!("{:?}", err);
+            assert!(message.contains(kind), "{}", message);
+            message
+        }
+    }
+}
+
+#[test]
+fn functions_without_capability_need_no_grant() {
+    let mut interpreter = interpreter(&["print"]);
+    interpreter.resolve_imports().unwrap();
+    interpreter.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn import_without_grant_is_denied() {
+    let mut interpreter = interpreter(&["print", "read_file"]);
+    let message = assert_error(interpreter.resolve_imports(), "PERMISSION_DENIED");
+    assert!(message.contains("read_file"), "{}", message);
+    assert!(message.contains("capability fs"), "{}", message);
+}
+
+#[test]
+fn granted_import_can_be_retried() {
+    let mut interpreter = interpreter(&["print", "read_file"]);
+    assert!(interpreter.resolve_imports().is_err());
+
+    interpreter.grant_capability(String::from("fs"));
+    assert!(interpreter.has_capability("fs"));
+    interpreter.resolve_imports().unwrap();
+    interpreter.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn revoked_capability_is_denied() {
+    let mut interpreter = interpreter(&["read_file"]);
+    interpreter.grant_capability(String::from("fs"));
+    interpreter.revoke_capability("fs");
+    assert!(!interpreter.has_capability("fs"));
+    assert_error(interpreter.resolve_imports(), "PERMISSION_DENIED");
+}
+
+#[test]
+fn missing_function_is_still_reported() {
+    let mut interpreter = interpreter(&["connect"]);
+    assert_error(interpreter.resolve_imports(), "MISSING_IMPORTS");
+}
+
+#[test]
+fn imports_can_be_audited() {
+    let holder = program(&["print", "read_file", "connect"]);
+    assert_eq!(holder.imports(), ["print", "read_file", "connect"]);
+
+    let mut interpreter = interpreter(&["print", "read_file", "connect"]);
+    let audit = interpreter.audit_imports();
+    assert_eq!(
+        audit,
+        vec![
+            ImportAudit { name: String::from("print"), registered: true, capability: None, allowed: true },
+            ImportAudit { name: String::from("read_file"), registered: true, capability: Some(String::from("fs")), allowed: false },
+            ImportAudit { name: String::from("connect"), registered: false, capability: None, allowed: false },
+        ]
+    );
+
+    interpreter.grant_capability(String::from("fs"));
+    assert!(interpreter.audit_imports()[1].allowed);
+}
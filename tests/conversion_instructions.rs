@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+use std::io::Error;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const FRAME_ALLOC: u8 = 0x15;
+const RET: u8 = 0x19;
+const TO_INT: u8 = 0x1E;
+const TO_DOUBLE: u8 = 0x1F;
+const TO_STRING: u8 = 0x20;
+const TO_BOOL: u8 = 0x21;
+const TYPE_OF: u8 = 0x22;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` on this thread
+    static RECORDED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = state.get_value_as_string()?;
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<String> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+fn int(value: i64) -> Vec<u8> {
+    let mut buf = vec![0x01];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn double(value: f64) -> Vec<u8> {
+    let mut buf = vec![0x02];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut buf = vec![0x03];
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value.as_bytes());
+    buf
+}
+
+fn boolean(value: bool) -> Vec<u8> {
+    vec![0x04, value as u8]
+}
+
+/// Encodes a register operand
+fn reg(index: u32, location: u8) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(location);
+    buf
+}
+
+/// Builds version 7.6 bytecode that converts `constant` with the instruction `op`, and passes the
+/// result and its type tag to `record`
+fn conversion(op: u8, constant: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&1u32.to_be_bytes());
+    buf.extend_from_slice(constant);
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+
+    buf.push(FRAME_ALLOC);
+    buf.extend(2u32.to_be_bytes());
+    buf.push(GLOBAL);
+    buf.push(op);
+    buf.extend(reg(0, GLOBAL));
+    buf.extend(reg(0, CONSTANT));
+    buf.push(TYPE_OF);
+    buf.extend(reg(1, GLOBAL));
+    buf.extend(reg(0, GLOBAL));
+    for register in 0..2 {
+        buf.push(STACK_PUSH);
+        buf.extend(reg(register, GLOBAL));
+        buf.push(AS_IS);
+        buf.push(EXT_CALL);
+        buf.extend(0u64.to_be_bytes());
+    }
+    buf.push(RET);
+    buf
+}
+
+/// Converts `constant` with the instruction `op`, and returns the result and its type tag
+fn convert(op: u8, constant: &[u8]) -> Result<(String, i64), ResurgenceError> {
+    let mut interpreter = Interpreter::from(read_bytecode(&conversion(op, constant)).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    let res = interpreter.execute_instruction(0);
+    let recorded = take_recorded();
+    res.map(|()| (recorded[0].clone(), recorded[1].parse().unwrap()))
+}
+
+fn assert_converts(op: u8, constant: &[u8], value: &str, type_tag: i64) {
+    assert_eq!(convert(op, constant).unwrap(), (String::from(value), type_tag));
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+#[test]
+fn to_int_converts_numbers_and_strings() {
+    assert_converts(TO_INT, &int(-7), "-7", 1);
+    assert_converts(TO_INT, &double(5.9), "5", 1);
+    assert_converts(TO_INT, &double(-5.9), "-5", 1);
+    assert_converts(TO_INT, &string("42"), "42", 1);
+}
+
+#[test]
+fn to_int_rejects_bad_values() {
+    assert_error(convert(TO_INT, &string("4.2")), "INVALID_OPERATION");
+    assert_error(convert(TO_INT, &string("forty two")), "INVALID_OPERATION");
+    assert_error(convert(TO_INT, &double(f64::NAN)), "INVALID_OPERATION");
+    assert_error(convert(TO_INT, &boolean(true)), "INVALID_OPERATION");
+    assert_error(convert(TO_INT, &double(1e19)), "OVERFLOW");
+    assert_error(convert(TO_INT, &double(-1e19)), "OVERFLOW");
+}
+
+#[test]
+fn to_double_converts_numbers_and_strings() {
+    assert_converts(TO_DOUBLE, &int(3), "3", 2);
+    assert_converts(TO_DOUBLE, &int(1 << 53), "9007199254740992", 2);
+    assert_converts(TO_DOUBLE, &int(i64::MIN), &(i64::MIN as f64).to_string(), 2);
+    assert_converts(TO_DOUBLE, &double(2.5), "2.5", 2);
+    assert_converts(TO_DOUBLE, &string("-0.25"), "-0.25", 2);
+}
+
+#[test]
+fn to_double_rejects_bad_values() {
+    assert_error(convert(TO_DOUBLE, &string("two")), "INVALID_OPERATION");
+    assert_error(convert(TO_DOUBLE, &boolean(false)), "INVALID_OPERATION");
+}
+
+#[test]
+fn to_double_rejects_integers_it_can_not_hold_exactly() {
+    assert_error(convert(TO_DOUBLE, &int((1 << 53) + 1)), "OVERFLOW");
+    assert_error(convert(TO_DOUBLE, &int(i64::MAX)), "OVERFLOW");
+    assert_error(convert(TO_DOUBLE, &int(-(1 << 53) - 1)), "OVERFLOW");
+}
+
+#[test]
+fn to_string_converts_scalars() {
+    assert_converts(TO_STRING, &int(12), "12", 3);
+    assert_converts(TO_STRING, &double(0.5), "0.5", 3);
+    assert_converts(TO_STRING, &boolean(true), "true", 3);
+    assert_converts(TO_STRING, &string("same"), "same", 3);
+}
+
+#[test]
+fn to_bool_uses_truthiness() {
+    assert_converts(TO_BOOL, &int(0), "false", 4);
+    assert_converts(TO_BOOL, &int(-1), "true", 4);
+    assert_converts(TO_BOOL, &double(0.0), "false", 4);
+    assert_converts(TO_BOOL, &double(f64::NAN), "false", 4);
+    assert_converts(TO_BOOL, &string(""), "false", 4);
+    assert_converts(TO_BOOL, &string("false"), "true", 4);
+    assert_converts(TO_BOOL, &boolean(true), "true", 4);
+}
+
+#[test]
+fn conversions_round_trip_through_the_writer() {
+    for op in [TO_INT, TO_DOUBLE, TO_STRING, TO_BOOL] {
+        let original = read_bytecode(&conversion(op, &int(1))).unwrap();
+        let written = write_bytecode(&original).unwrap();
+        let read_back = read_bytecode(&written).unwrap();
+
+        assert_eq!(format!("{:?}", read_back.instructions), format!("{:?}", original.instructions));
+        assert_eq!(write_bytecode(&read_back).unwrap(), written);
+    }
+}
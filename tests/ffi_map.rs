@@ -0,0 +1,122 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use resurgence::ffi::*;
+
+/// Reads and frees a string returned by the C API
+fn take_string(value: *mut c_char) -> String {
+    let string = unsafe { CStr::from_ptr(value) }.to_str().unwrap().to_owned();
+    unsafe { rvm_string_free(value) };
+    string
+}
+
+/// A key read back through the C API
+#[derive(Debug, PartialEq)]
+enum Key {
+    Int(i64),
+    String(String),
+}
+
+/// Lists the keys of a map the way C code would, without knowing them beforehand
+unsafe fn keys(map: *mut RVMMap) -> Vec<Key> {
+    let mut len = 0;
+    assert_eq!(rvm_map_len(map, &mut len), 0);
+
+    let mut keys = Vec::new();
+    for index in 0..len {
+        let mut is_integer = 0;
+        assert_eq!(rvm_map_key_is_integer(map, index, &mut is_integer), 0);
+        if is_integer == 1 {
+            let mut key = 0;
+            assert_eq!(rvm_map_get_key_integer(map, index, &mut key), 0);
+            keys.push(Key::Int(key));
+        } else {
+            let mut key = ptr::null_mut();
+            assert_eq!(rvm_map_get_key_string(map, index, &mut key), 0);
+            keys.push(Key::String(take_string(key)));
+        }
+    }
+    keys
+}
+
+#[test]
+fn maps_hold_values_under_string_and_integer_keys() {
+    unsafe {
+        let map = rvm_map_new();
+        let name = CString::new("name").unwrap();
+        let health = CString::new("health").unwrap();
+        let value = CString::new("hero").unwrap();
+        assert_eq!(rvm_map_set_string(map, name.as_ptr(), value.as_ptr()), 0);
+        assert_eq!(rvm_map_set_integer(map, health.as_ptr(), 100), 0);
+        assert_eq!(rvm_map_set_bool_at(map, 3, 1), 0);
+        assert_eq!(rvm_map_set_float_at(map, -2, 0.5), 0);
+
+        let mut string = ptr::null_mut();
+        assert_eq!(rvm_map_get_string(map, name.as_ptr(), &mut string), 0);
+        assert_eq!(take_string(string), "hero");
+        let mut int = 0;
+        assert_eq!(rvm_map_get_integer(map, health.as_ptr(), &mut int), 0);
+        assert_eq!(int, 100);
+        let mut boolean = 0;
+        assert_eq!(rvm_map_get_bool_at(map, 3, &mut boolean), 0);
+        assert_eq!(boolean, 1);
+        let mut float = 0.0;
+        assert_eq!(rvm_map_get_float_at(map, -2, &mut float), 0);
+        assert_eq!(float, 0.5);
+
+        // a value of another type, or a missing key, is an error
+        assert_eq!(rvm_map_get_integer(map, name.as_ptr(), &mut int), 1);
+        assert_eq!(rvm_map_get_integer_at(map, 4, &mut int), 1);
+
+        let mut contains = 0;
+        assert_eq!(rvm_map_contains_at(map, 3, &mut contains), 0);
+        assert_eq!(contains, 1);
+        assert_eq!(rvm_map_remove_at(map, 3), 0);
+        assert_eq!(rvm_map_contains_at(map, 3, &mut contains), 0);
+        assert_eq!(contains, 0);
+
+        rvm_map_destroy(map);
+    }
+}
+
+#[test]
+fn keys_can_be_listed_by_position() {
+    unsafe {
+        let map = rvm_map_new();
+        for key in ["zeta", "alpha"] {
+            let key = CString::new(key).unwrap();
+            assert_eq!(rvm_map_set_bool(map, key.as_ptr(), 1), 0);
+        }
+        for key in [7, -1] {
+            assert_eq!(rvm_map_set_integer_at(map, key, key), 0);
+        }
+
+        assert_eq!(
+            keys(map),
+            vec![Key::Int(-1), Key::Int(7), Key::String(String::from("alpha")), Key::String(String::from("zeta"))]
+        );
+        rvm_map_destroy(map);
+    }
+}
+
+#[test]
+fn listing_keys_checks_the_position_and_key_type() {
+    unsafe {
+        let map = rvm_map_new();
+        let key = CString::new("only").unwrap();
+        assert_eq!(rvm_map_set_integer(map, key.as_ptr(), 1), 0);
+
+        let mut is_integer = 0;
+        let mut int_key = 0;
+        let mut string_key = ptr::null_mut();
+        assert_eq!(rvm_map_key_is_integer(map, 1, &mut is_integer), 1);
+        assert_eq!(rvm_map_get_key_string(map, 1, &mut string_key), 1);
+        assert_eq!(rvm_map_get_key_integer(map, 0, &mut int_key), 1);
+        assert_eq!(rvm_map_get_key_string(map, u64::MAX, &mut string_key), 1);
+        assert_eq!(rvm_map_key_is_integer(ptr::null_mut(), 0, &mut is_integer), 1);
+        assert_eq!(rvm_map_get_key_integer(map, 0, ptr::null_mut()), 1);
+
+        rvm_map_destroy(map);
+    }
+}
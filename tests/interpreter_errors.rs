@@ -0,0 +1,143 @@
+use std::io::Error;
+
+use resurgence::bytecode::read_bytecode;
+use resurgence::{Constant, ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const ALLOC: u8 = 0x01;
+const EXT_CALL: u8 = 0x05;
+const CPY: u8 = 0x07;
+const REF: u8 = 0x08;
+const STACK_PUSH: u8 = 0x09;
+const FRAME_ALLOC: u8 = 0x15;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const LOCAL: u8 = 0x04;
+
+const AS_IS: u8 = 0x01;
+const DEREFERENCE: u8 = 0x02;
+
+/// Encodes a register operand with its reference type
+fn reg(index: u32, location: u8, reference: u8) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(location);
+    buf.push(reference);
+    buf
+}
+
+/// A host function registered under a name
+type Function<'a> = (&'a str, fn(&mut ResurgenceState) -> Result<(), Error>);
+
+/// Builds version 7.6 bytecode with int constants, imports and the given instructions, and runs it
+fn run(constants: &[i64], imports: &[&str], code: &[u8], functions: &[Function]) -> Result<(), ResurgenceError> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.push(0x01);
+        buf.extend_from_slice(&constant.to_be_bytes());
+    }
+    buf.extend_from_slice(&(imports.len() as u64).to_be_bytes());
+    for import in imports {
+        buf.extend_from_slice(&(import.len() as u64).to_be_bytes());
+        buf.extend_from_slice(import.as_bytes());
+    }
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+
+    let mut interpreter = Interpreter::from(read_bytecode(&buf).unwrap());
+    for (name, function) in functions {
+        interpreter.register_function(*function, name.to_string());
+    }
+    interpreter.execute_instruction(0)
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+#[test]
+fn local_register_without_stack_frame_is_an_error() {
+    let mut code = vec![STACK_PUSH];
+    code.extend(reg(0, LOCAL, AS_IS));
+
+    assert_error(run(&[], &[], &code, &[]), "INVALID_OPERATION");
+}
+
+#[test]
+fn frame_alloc_without_stack_frame_is_an_error() {
+    let mut code = vec![FRAME_ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(LOCAL);
+
+    assert_error(run(&[], &[], &code, &[]), "INVALID_OPERATION");
+}
+
+#[test]
+fn empty_local_register_is_an_error() {
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(STACK_PUSH);
+    code.extend(reg(0, LOCAL, AS_IS));
+
+    assert_error(run(&[], &[], &code, &[]), "MEMORY_ADDRESS_NONE");
+}
+
+#[test]
+fn local_register_beyond_bounds_is_an_error() {
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(CPY);
+    code.extend(reg(5, LOCAL, AS_IS));
+    code.extend(reg(0, CONSTANT, AS_IS));
+
+    assert_error(run(&[1], &[], &code, &[]), "REGISTER_OUT_OF_BOUNDS");
+}
+
+#[test]
+fn dereferencing_a_non_address_is_an_error() {
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(CPY);
+    code.extend(reg(0, LOCAL, AS_IS));
+    code.extend(reg(0, CONSTANT, AS_IS));
+    code.push(STACK_PUSH);
+    code.extend(reg(0, LOCAL, DEREFERENCE));
+
+    assert_error(run(&[1], &[], &code, &[]), "INVALID_OPERATION");
+}
+
+#[test]
+fn dangling_address_is_an_error() {
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(REF);
+    code.extend(reg(0, LOCAL, AS_IS));
+    code.extend(reg(3, GLOBAL, AS_IS));
+    code.push(STACK_PUSH);
+    code.extend(reg(0, LOCAL, DEREFERENCE));
+
+    assert_error(run(&[], &[], &code, &[]), "REGISTER_OUT_OF_BOUNDS");
+}
+
+fn needs_argument(state: &mut ResurgenceState) -> Result<(), Error> {
+    state.get_i64()?;
+    Ok(())
+}
+
+#[test]
+fn missing_host_function_argument_is_an_error() {
+    let mut code = vec![EXT_CALL];
+    code.extend(0u64.to_be_bytes());
+
+    let res = run(&[], &["needs_argument"], &code, &[("needs_argument", needs_argument)]);
+    assert_error(res, "FUNCTION_RETURN_ERROR");
+}
+
+#[test]
+fn remainder_overflow_is_an_error() {
+    let res = Constant::Int(i64::MIN).modlo(&Constant::Int(-1));
+    assert!(format!("{:?}", res.err().unwrap()).contains("OVERFLOW"));
+}
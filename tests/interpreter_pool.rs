@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use resurgence::bytecode::read_bytecode;
+use resurgence::codegen;
+use resurgence::{CodeHolder, Interpreter, InterpreterPool, PoolResult, PoolTask, ResurgenceError};
+
+const ALLOC: u8 = 0x01;
+const JUMP: u8 = 0x03;
+const RET: u8 = 0x19;
+
+/// Builds version 7.6 bytecode exporting `finish` at 0, which returns after allocating a frame,
+/// and `spin` at 2, which never returns
+fn exported_program() -> CodeHolder {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&0u32.to_be_bytes());
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(&2u64.to_be_bytes());
+    for (name, index) in [("finish", 0u64), ("spin", 2)] {
+        buf.extend_from_slice(&(name.len() as u64).to_be_bytes());
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(&index.to_be_bytes());
+    }
+    buf.push(ALLOC);
+    buf.extend_from_slice(&1u32.to_be_bytes());
+    buf.push(RET);
+    buf.push(JUMP);
+    buf.extend_from_slice(&0i64.to_be_bytes());
+    read_bytecode(&buf).unwrap()
+}
+
+/// Code that never finishes on its own
+fn infinite_loop() -> Arc<CodeHolder> {
+    let mut holder = CodeHolder::new();
+    codegen::generate_jump(&mut holder, 0);
+    Arc::new(holder)
+}
+
+/// Code that finishes after two instructions
+fn finishes() -> Arc<CodeHolder> {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+    Arc::new(holder)
+}
+
+fn assert_error(res: &Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+fn task_with_fuel(code: &Arc<CodeHolder>, fuel: u64) -> PoolTask {
+    let mut task = PoolTask::from_instruction(Interpreter::from_shared(Arc::clone(code)), 0);
+    task.set_fuel(fuel);
+    task
+}
+
+#[test]
+fn pool_has_at_least_one_worker() {
+    assert_eq!(InterpreterPool::new(0).workers(), 1);
+    assert!(InterpreterPool::default().workers() >= 1);
+}
+
+#[test]
+fn empty_pool_run_returns_nothing() {
+    assert!(InterpreterPool::new(4).run(Vec::new()).is_empty());
+}
+
+#[test]
+fn results_keep_the_order_of_the_tasks() {
+    let finishes = finishes();
+    let loops = infinite_loop();
+    let tasks = (0..50)
+        .map(|index| match index % 3 {
+            0 => task_with_fuel(&loops, 1000),
+            _ => task_with_fuel(&finishes, 1000),
+        })
+        .collect();
+
+    let results = InterpreterPool::new(4).run(tasks);
+    assert_eq!(results.len(), 50);
+    for (index, PoolResult { result, .. }) in results.iter().enumerate() {
+        match index % 3 {
+            0 => assert_error(result, "OUT_OF_FUEL"),
+            _ => assert!(result.is_ok(), "{:?}", result),
+        }
+    }
+}
+
+#[test]
+fn every_task_has_its_own_fuel() {
+    let finishes = finishes();
+    let tasks = (10..20).map(|fuel| task_with_fuel(&finishes, fuel)).collect();
+
+    let results = InterpreterPool::new(3).run(tasks);
+    for (fuel, result) in (10..20).zip(results) {
+        assert!(result.result.is_ok());
+        assert_eq!(result.interpreter.remaining_fuel(), Some(fuel - 2));
+    }
+}
+
+#[test]
+fn tasks_without_fuel_keep_the_fuel_of_their_interpreter() {
+    let mut interpreter = Interpreter::from_shared(infinite_loop());
+    interpreter.set_fuel(Some(100));
+
+    let results = InterpreterPool::new(1).run(vec![PoolTask::from_instruction(interpreter, 0)]);
+    assert_error(&results[0].result, "OUT_OF_FUEL");
+}
+
+#[test]
+fn tasks_can_run_exported_functions() {
+    let code = Arc::new(exported_program());
+    let mut spin = PoolTask::from_function(Interpreter::from_shared(Arc::clone(&code)), "spin");
+    spin.set_fuel(1000);
+    let tasks = vec![
+        PoolTask::from_function(Interpreter::from_shared(Arc::clone(&code)), "finish"),
+        spin,
+        PoolTask::from_function(Interpreter::from_shared(Arc::clone(&code)), "missing"),
+    ];
+
+    let results = InterpreterPool::new(2).run(tasks);
+    assert!(results[0].result.is_ok());
+    assert_error(&results[1].result, "OUT_OF_FUEL");
+    assert_error(&results[2].result, "FUNCTION_DOES_NOT_EXIST");
+}
@@ -0,0 +1,127 @@
+use std::io::Error;
+
+use resurgence::bytecode::read_bytecode;
+use resurgence::codegen::{self, RVMLocation, RVMReference};
+use resurgence::{CodeHolder, ExecutionEngine, Interpreter, MemoryLimits, ResurgenceError, ResurgenceState};
+
+const ALLOC: u8 = 0x01;
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const RET: u8 = 0x19;
+
+const LOCAL: u8 = 0x04;
+const AS_IS: u8 = 0x01;
+
+/// Builds version 7.6 bytecode without constants, with the given imports and instructions
+fn program(imports: &[&str], code: &[u8]) -> CodeHolder {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&0u32.to_be_bytes());
+    buf.extend_from_slice(&(imports.len() as u64).to_be_bytes());
+    for import in imports {
+        buf.extend_from_slice(&(import.len() as u64).to_be_bytes());
+        buf.extend_from_slice(import.as_bytes());
+    }
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+    read_bytecode(&buf).unwrap()
+}
+
+/// Limits that only let the code below run once without a reset
+fn tight_limits() -> MemoryLimits {
+    MemoryLimits {
+        max_registers: 2,
+        max_globals: 1,
+        max_stack: 1,
+        ..MemoryLimits::default()
+    }
+}
+
+/// Allocates a stack frame and a global register and pushes a value, without cleaning up
+fn leaves_state_behind() -> Interpreter {
+    let mut holder = CodeHolder::new();
+    let value = codegen::generate_int_constant(&mut holder, 1);
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_frame_alloc(&mut holder, 1, RVMLocation::GLOBAL);
+    codegen::generate_stack_push(&mut holder, (value, RVMReference::AS_IS));
+    codegen::generate_return(&mut holder);
+
+    let mut interpreter = Interpreter::from(holder);
+    interpreter.set_memory_limits(tight_limits());
+    interpreter
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) -> String {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => {
+            let message = format!("{:?}", err);
+            assert!(message.contains(kind), "{}", message);
+            message
+        }
+    }
+}
+
+#[test]
+fn state_persists_without_reset() {
+    let mut interpreter = leaves_state_behind();
+    interpreter.execute_instruction(0).unwrap();
+    assert_error(interpreter.execute_instruction(0), "MEMORY_LIMIT");
+}
+
+#[test]
+fn reset_clears_state() {
+    let mut interpreter = leaves_state_behind();
+    for _ in 0..3 {
+        interpreter.execute_instruction(0).unwrap();
+        interpreter.reset();
+    }
+}
+
+#[test]
+fn code_is_intact_after_an_error() {
+    // the second instruction fails because the register is empty
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(STACK_PUSH);
+    code.extend(0u32.to_be_bytes());
+    code.extend([LOCAL, AS_IS]);
+
+    let mut interpreter = Interpreter::from(program(&[], &code));
+    for _ in 0..3 {
+        let message = assert_error(interpreter.execute_instruction(0), "MEMORY_ADDRESS_NONE");
+        assert!(!message.contains("is missing"), "{}", message);
+        interpreter.reset();
+    }
+}
+
+#[test]
+fn code_is_intact_after_running_out_of_fuel() {
+    let mut interpreter = leaves_state_behind();
+    interpreter.set_fuel(Some(2));
+    assert_error(interpreter.execute_instruction(0), "OUT_OF_FUEL");
+
+    interpreter.reset();
+    interpreter.set_fuel(None);
+    interpreter.execute_instruction(0).unwrap();
+}
+
+fn push_one(state: &mut ResurgenceState) -> Result<(), Error> {
+    state.push_i64(1);
+    Ok(())
+}
+
+#[test]
+fn reset_keeps_functions_and_imports() {
+    let mut code = vec![EXT_CALL];
+    code.extend(0u64.to_be_bytes());
+    code.push(RET);
+
+    let mut interpreter = Interpreter::from(program(&["push_one"], &code));
+    interpreter.register_function(push_one, String::from("push_one"));
+    interpreter.resolve_imports().unwrap();
+    interpreter.set_memory_limits(tight_limits());
+    for _ in 0..3 {
+        interpreter.execute_instruction(0).unwrap();
+        interpreter.reset();
+    }
+}
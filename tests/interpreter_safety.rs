@@ -0,0 +1,120 @@
+use resurgence::codegen;
+use resurgence::{CodeHolder, ExecutionEngine, Interpreter, ResurgenceError};
+
+/// Runs the code from its first instruction
+fn run(holder: CodeHolder, fuel: Option<u64>) -> (Interpreter, Result<(), ResurgenceError>) {
+    let mut interpreter = Interpreter::from(holder);
+    interpreter.set_fuel(fuel);
+    let res = interpreter.execute_instruction(0);
+    (interpreter, res)
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+#[test]
+fn fuel_stops_infinite_loops() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_jump(&mut holder, 0);
+
+    let (interpreter, res) = run(holder, Some(1000));
+    assert_error(res, "OUT_OF_FUEL");
+    assert_eq!(interpreter.remaining_fuel(), Some(0));
+}
+
+#[test]
+fn finished_code_keeps_remaining_fuel() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+
+    let (interpreter, res) = run(holder, Some(10));
+    assert!(res.is_ok());
+    assert_eq!(interpreter.remaining_fuel(), Some(8));
+}
+
+#[test]
+fn recursion_is_limited() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_call(&mut holder, 0);
+    codegen::generate_return(&mut holder);
+
+    // calls do not use the native stack, so the default limit is safe on a small test thread
+    let (_, res) = run(holder, None);
+    assert_error(res, "RECURSION_LIMIT");
+}
+
+#[test]
+fn calls_return_to_the_caller() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_call(&mut holder, 3);
+    codegen::generate_call(&mut holder, 4);
+    codegen::generate_return(&mut holder);
+    codegen::generate_return(&mut holder);
+    codegen::generate_alloc(&mut holder, 1);
+
+    // Call 3, Ret, Call 4, Alloc and running off the end, then the final Ret
+    let (interpreter, res) = run(holder, Some(10));
+    assert!(res.is_ok());
+    assert_eq!(interpreter.remaining_fuel(), Some(5));
+}
+
+#[test]
+fn writing_to_a_constant_is_an_error() {
+    let mut holder = CodeHolder::new();
+    let dst = codegen::generate_int_constant(&mut holder, 0);
+    let a = codegen::generate_int_constant(&mut holder, 1);
+    let b = codegen::generate_int_constant(&mut holder, 2);
+    codegen::generate_add(&mut holder, dst, a, b);
+    codegen::generate_return(&mut holder);
+
+    let (_, res) = run(holder, None);
+    assert_error(res, "INVALID_OPERATION");
+}
+
+#[test]
+fn jumps_out_of_the_code_are_rejected() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_jump(&mut holder, -2);
+    assert_error(holder.verify(), "INSTRUCTION_OUT_OF_BOUNDS");
+
+    // the interpreter verifies the code before running it, every time until it passes
+    let (mut interpreter, res) = run(holder, None);
+    assert_error(res, "INSTRUCTION_OUT_OF_BOUNDS");
+    assert_error(interpreter.execute_instruction(0), "INSTRUCTION_OUT_OF_BOUNDS");
+    assert_error(interpreter.resolve_imports(), "INSTRUCTION_OUT_OF_BOUNDS");
+}
+
+#[test]
+fn calls_out_of_the_code_are_rejected() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_call(&mut holder, 5);
+    codegen::generate_return(&mut holder);
+    assert_error(holder.verify(), "INSTRUCTION_OUT_OF_BOUNDS");
+}
+
+#[test]
+fn missing_imports_are_rejected() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_ext_call(&mut holder, 0);
+    assert_error(holder.verify(), "MISSING_IMPORTS");
+}
+
+#[test]
+fn valid_code_verifies() {
+    let mut holder = CodeHolder::new();
+    let dst = codegen::generate_string_constant(&mut holder, String::new());
+    let src = codegen::generate_int_constant(&mut holder, 4);
+    let index = codegen::generate_int_constant(&mut holder, 1);
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_to_string(&mut holder, dst, src);
+    codegen::generate_jump_table(&mut holder, index, 4, vec![0, 3]);
+    codegen::generate_jump(&mut holder, -3);
+    codegen::generate_return(&mut holder);
+    assert!(holder.verify().is_ok());
+}
@@ -0,0 +1,108 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use resurgence::codegen;
+use resurgence::{CodeHolder, ExecutionEngine, InterruptHandle, Interpreter, ResurgenceError};
+
+/// Fuel for code that should be stopped by an interrupt, so a broken interrupt fails instead of
+/// hanging the tests
+const SAFETY_FUEL: u64 = 500_000_000;
+
+/// Code that never finishes on its own
+fn infinite_loop() -> Interpreter {
+    let mut holder = CodeHolder::new();
+    codegen::generate_jump(&mut holder, 0);
+    let mut interpreter = Interpreter::from(holder);
+    interpreter.set_fuel(Some(SAFETY_FUEL));
+    interpreter
+}
+
+/// Code that finishes right away
+fn finishes() -> Interpreter {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+    Interpreter::from(holder)
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+#[test]
+fn handle_can_be_shared_between_threads() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<InterruptHandle>();
+}
+
+#[test]
+fn interrupt_from_another_thread_stops_the_code() {
+    let mut interpreter = infinite_loop();
+    let handle = interpreter.interrupt_handle();
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        handle.interrupt();
+    });
+
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+    interrupter.join().unwrap();
+    assert!(interpreter.remaining_fuel().unwrap() > 0);
+}
+
+#[test]
+fn interrupt_only_stops_execution_once() {
+    let mut interpreter = finishes();
+    let handle = interpreter.interrupt_handle();
+    handle.clone().interrupt();
+    assert!(handle.is_interrupted());
+
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+    assert!(!handle.is_interrupted());
+    interpreter.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn cleared_interrupt_does_not_stop_the_code() {
+    let mut interpreter = finishes();
+    let handle = interpreter.interrupt_handle();
+    handle.interrupt();
+    handle.clear();
+
+    interpreter.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn deadline_stops_the_code() {
+    let mut interpreter = infinite_loop();
+    let start = Instant::now();
+    interpreter.set_deadline(Some(start + Duration::from_millis(20)));
+
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert!(interpreter.remaining_fuel().unwrap() > 0);
+}
+
+#[test]
+fn deadline_can_be_removed() {
+    let mut interpreter = finishes();
+    interpreter.set_deadline(Some(Instant::now()));
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+
+    interpreter.set_deadline(None);
+    assert_eq!(interpreter.deadline(), None);
+    interpreter.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn interrupted_code_can_run_again() {
+    let mut interpreter = infinite_loop();
+    interpreter.interrupt_handle().interrupt();
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+
+    // the code is left intact, so running it again only stops once the fuel runs out
+    interpreter.set_fuel(Some(1000));
+    assert_error(interpreter.execute_instruction(0), "OUT_OF_FUEL");
+}
@@ -0,0 +1,218 @@
+use std::cell::RefCell;
+use std::io::Error;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const RET: u8 = 0x19;
+const JUMP_TO: u8 = 0x1A;
+const JUMP_IF_TRUE: u8 = 0x1B;
+const JUMP_IF_FALSE: u8 = 0x1C;
+const JUMP_TABLE: u8 = 0x1D;
+
+const CONSTANT: u8 = 0x01;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` on this thread
+    static RECORDED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = state.get_value_as_string()?;
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<String> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+fn int(value: i64) -> Vec<u8> {
+    let mut buf = vec![0x01];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn boolean(value: bool) -> Vec<u8> {
+    vec![0x04, value as u8]
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut buf = vec![0x03];
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value.as_bytes());
+    buf
+}
+
+/// Encodes a constant pool register operand
+fn constant(index: u32) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(CONSTANT);
+    buf
+}
+
+/// Encodes instructions that pass the constant `index` to `record`
+fn record_constant(index: u32) -> Vec<u8> {
+    let mut buf = vec![STACK_PUSH];
+    buf.extend(constant(index));
+    buf.push(AS_IS);
+    buf.push(EXT_CALL);
+    buf.extend(0u64.to_be_bytes());
+    buf
+}
+
+/// Builds version 7.6 bytecode with the given constants, `record` as its only import, and the
+/// given instructions
+fn program(constants: &[Vec<u8>], code: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.extend_from_slice(constant);
+    }
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+    buf
+}
+
+fn run(bytecode: &[u8]) -> Result<Vec<String>, ResurgenceError> {
+    let mut interpreter = Interpreter::from(read_bytecode(bytecode).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    let res = interpreter.execute_instruction(0);
+    let recorded = take_recorded();
+    res.map(|()| recorded)
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+/// Builds a program that jumps with `jump`, which is followed by recording "skipped" and then
+/// "reached" at instruction 3
+fn jumps_over_skipped(constants: &[Vec<u8>], jump: &[u8]) -> Vec<u8> {
+    let mut constants = constants.to_vec();
+    let skipped = constants.len() as u32;
+    constants.push(string("skipped"));
+    constants.push(string("reached"));
+
+    let mut code = jump.to_vec();
+    code.extend(record_constant(skipped));
+    code.extend(record_constant(skipped + 1));
+    code.push(RET);
+    program(&constants, &code)
+}
+
+fn conditional_jump(op: u8, condition: u32, target: u64) -> Vec<u8> {
+    let mut jump = vec![op];
+    jump.extend(constant(condition));
+    jump.extend(target.to_be_bytes());
+    jump
+}
+
+fn jump_table(index: u32, default: u64, table: &[u64]) -> Vec<u8> {
+    let mut jump = vec![JUMP_TABLE];
+    jump.extend(constant(index));
+    jump.extend(default.to_be_bytes());
+    jump.extend((table.len() as u64).to_be_bytes());
+    for target in table {
+        jump.extend(target.to_be_bytes());
+    }
+    jump
+}
+
+#[test]
+fn jump_to_skips_to_an_absolute_instruction() {
+    let mut jump = vec![JUMP_TO];
+    jump.extend(3u64.to_be_bytes());
+
+    assert_eq!(run(&jumps_over_skipped(&[], &jump)).unwrap(), ["reached"]);
+}
+
+#[test]
+fn conditional_jumps_follow_the_condition() {
+    let constants = [boolean(true), boolean(false)];
+    let jump_if_true = |condition| jumps_over_skipped(&constants, &conditional_jump(JUMP_IF_TRUE, condition, 3));
+    let jump_if_false = |condition| jumps_over_skipped(&constants, &conditional_jump(JUMP_IF_FALSE, condition, 3));
+
+    assert_eq!(run(&jump_if_true(0)).unwrap(), ["reached"]);
+    assert_eq!(run(&jump_if_true(1)).unwrap(), ["skipped", "reached"]);
+    assert_eq!(run(&jump_if_false(1)).unwrap(), ["reached"]);
+    assert_eq!(run(&jump_if_false(0)).unwrap(), ["skipped", "reached"]);
+}
+
+#[test]
+fn conditional_jump_on_a_non_boolean_is_an_error() {
+    let bytecode = jumps_over_skipped(&[int(1)], &conditional_jump(JUMP_IF_TRUE, 0, 3));
+    assert_error(run(&bytecode), "INVALID_OPERATION");
+}
+
+#[test]
+fn jump_table_jumps_to_the_indexed_instruction() {
+    // 0: table, 1: record "first", 4: record "second", 7: record "default", each followed by a return
+    let constants = [int(0), int(1), int(2), int(-1), string("first"), string("second"), string("default")];
+    let program_for = |index| {
+        let mut code = jump_table(index, 7, &[1, 4]);
+        code.extend(record_constant(4));
+        code.push(RET);
+        code.extend(record_constant(5));
+        code.push(RET);
+        code.extend(record_constant(6));
+        code.push(RET);
+        program(&constants, &code)
+    };
+
+    assert_eq!(run(&program_for(0)).unwrap(), ["first"]);
+    assert_eq!(run(&program_for(1)).unwrap(), ["second"]);
+    assert_eq!(run(&program_for(2)).unwrap(), ["default"]);
+    assert_eq!(run(&program_for(3)).unwrap(), ["default"]);
+}
+
+#[test]
+fn jump_table_on_a_non_integer_is_an_error() {
+    let bytecode = jumps_over_skipped(&[boolean(true)], &jump_table(0, 3, &[3]));
+    assert_error(run(&bytecode), "INVALID_OPERATION");
+}
+
+#[test]
+fn jumps_beyond_the_code_are_rejected() {
+    let mut jump_to = vec![JUMP_TO];
+    jump_to.extend(100u64.to_be_bytes());
+    let jumps = [
+        jump_to,
+        conditional_jump(JUMP_IF_FALSE, 0, 100),
+        jump_table(1, 3, &[100]),
+        jump_table(1, 100, &[3]),
+    ];
+
+    for jump in jumps {
+        let bytecode = jumps_over_skipped(&[boolean(true), int(0)], &jump);
+        assert_error(run(&bytecode), "INSTRUCTION_OUT_OF_BOUNDS");
+    }
+}
+
+#[test]
+fn jumps_round_trip_through_the_writer() {
+    let mut jump_to = vec![JUMP_TO];
+    jump_to.extend(3u64.to_be_bytes());
+    let mut code = jump_to;
+    code.extend(conditional_jump(JUMP_IF_TRUE, 0, 3));
+    code.extend(conditional_jump(JUMP_IF_FALSE, 0, 0));
+    code.extend(jump_table(1, 2, &[0, 1, 4]));
+    code.push(RET);
+
+    let original = read_bytecode(&program(&[boolean(true), int(0)], &code)).unwrap();
+    let written = write_bytecode(&original).unwrap();
+    let read_back = read_bytecode(&written).unwrap();
+
+    assert_eq!(format!("{:?}", read_back.instructions), format!("{:?}", original.instructions));
+    assert_eq!(write_bytecode(&read_back).unwrap(), written);
+}
@@ -0,0 +1,285 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::Error;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{Constant, ExecutionEngine, Interpreter, MapKey, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const FRAME_ALLOC: u8 = 0x15;
+const RET: u8 = 0x19;
+const MAP_NEW: u8 = 0x2C;
+const MAP_GET: u8 = 0x2D;
+const MAP_SET: u8 = 0x2E;
+const MAP_REMOVE: u8 = 0x2F;
+const MAP_CONTAINS: u8 = 0x30;
+const MAP_LEN: u8 = 0x31;
+const MAP_KEYS: u8 = 0x32;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record`, `record_vec` and `record_map` on this thread
+    static RECORDED: RefCell<Vec<Constant>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = Constant::String(state.get_value_as_string()?);
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+fn record_vec(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = Constant::Vec(state.get_vec()?);
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+fn record_map(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = Constant::Map(state.get_map()?);
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<Constant> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+fn int(value: i64) -> Vec<u8> {
+    let mut buf = vec![0x01];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn double(value: f64) -> Vec<u8> {
+    let mut buf = vec![0x02];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut buf = vec![0x03];
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value.as_bytes());
+    buf
+}
+
+/// Encodes a map constant of encoded keys and values
+fn map(entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut buf = vec![0x07];
+    buf.extend((entries.len() as u64).to_be_bytes());
+    for (key, value) in entries {
+        buf.extend(key);
+        buf.extend(value);
+    }
+    buf
+}
+
+/// Encodes an instruction with register operands
+fn ins(op: u8, registers: &[(u32, u8)]) -> Vec<u8> {
+    let mut buf = vec![op];
+    for (index, location) in registers {
+        buf.extend(index.to_be_bytes());
+        buf.push(*location);
+    }
+    buf
+}
+
+/// Import indexes of the recording functions
+const RECORD: u64 = 0;
+const RECORD_VEC: u64 = 1;
+const RECORD_MAP: u64 = 2;
+
+/// Encodes instructions that pass the global register `index` to the import `import`
+fn record_global(index: u32, import: u64) -> Vec<u8> {
+    let mut buf = ins(STACK_PUSH, &[(index, GLOBAL)]);
+    buf.push(AS_IS);
+    buf.push(EXT_CALL);
+    buf.extend(import.to_be_bytes());
+    buf
+}
+
+/// Builds version 7.6 bytecode with the given constants, the recording functions as its imports,
+/// and two global registers allocated before the given instructions
+fn program(constants: &[Vec<u8>], code: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.extend_from_slice(constant);
+    }
+    buf.extend_from_slice(&3u64.to_be_bytes());
+    for import in ["record", "record_vec", "record_map"] {
+        buf.extend_from_slice(&(import.len() as u64).to_be_bytes());
+        buf.extend_from_slice(import.as_bytes());
+    }
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.push(FRAME_ALLOC);
+    buf.extend(2u32.to_be_bytes());
+    buf.push(GLOBAL);
+    buf.extend_from_slice(code);
+    buf.push(RET);
+    buf
+}
+
+fn run(bytecode: &[u8]) -> Result<Vec<Constant>, ResurgenceError> {
+    let mut interpreter = Interpreter::from(read_bytecode(bytecode).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    interpreter.register_function(record_vec, String::from("record_vec"));
+    interpreter.register_function(record_map, String::from("record_map"));
+    let res = interpreter.execute_instruction(0);
+    let recorded = take_recorded();
+    res.map(|()| recorded)
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+fn text(value: &str) -> Constant {
+    Constant::String(String::from(value))
+}
+
+/// Constants used by the programs below
+fn constants() -> Vec<Vec<u8>> {
+    vec![string("name"), int(7), string("hero"), int(100), double(1.5), string("missing")]
+}
+
+/// Instructions that build the map `{7: 100, "name": "hero"}` in the global register 0
+fn build_map() -> Vec<u8> {
+    let mut code = ins(MAP_NEW, &[(0, GLOBAL)]);
+    code.extend(ins(MAP_SET, &[(0, GLOBAL), (0, CONSTANT), (2, CONSTANT)]));
+    code.extend(ins(MAP_SET, &[(0, GLOBAL), (1, CONSTANT), (3, CONSTANT)]));
+    code
+}
+
+#[test]
+fn maps_can_be_built_and_read() {
+    let mut code = build_map();
+    code.extend(ins(MAP_GET, &[(1, GLOBAL), (0, GLOBAL), (0, CONSTANT)]));
+    code.extend(record_global(1, RECORD));
+    code.extend(ins(MAP_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(record_global(1, RECORD));
+    code.extend(ins(MAP_CONTAINS, &[(1, GLOBAL), (0, GLOBAL), (1, CONSTANT)]));
+    code.extend(record_global(1, RECORD));
+    code.extend(ins(MAP_KEYS, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(record_global(1, RECORD_VEC));
+    code.extend(ins(MAP_REMOVE, &[(0, GLOBAL), (1, CONSTANT)]));
+    // removing a key that is not there does nothing
+    code.extend(ins(MAP_REMOVE, &[(0, GLOBAL), (5, CONSTANT)]));
+    code.extend(ins(MAP_CONTAINS, &[(1, GLOBAL), (0, GLOBAL), (1, CONSTANT)]));
+    code.extend(record_global(1, RECORD));
+    code.extend(record_global(0, RECORD_MAP));
+
+    let recorded = run(&program(&constants(), &code)).unwrap();
+    let remaining = BTreeMap::from([(MapKey::String(String::from("name")), text("hero"))]);
+    assert_eq!(
+        recorded,
+        vec![
+            text("hero"),
+            text("2"),
+            text("true"),
+            Constant::Vec(vec![Constant::Int(7), text("name")]),
+            text("false"),
+            Constant::Map(remaining),
+        ]
+    );
+}
+
+#[test]
+fn map_set_replaces_values() {
+    let mut code = build_map();
+    code.extend(ins(MAP_SET, &[(0, GLOBAL), (1, CONSTANT), (4, CONSTANT)]));
+    code.extend(ins(MAP_GET, &[(1, GLOBAL), (0, GLOBAL), (1, CONSTANT)]));
+    code.extend(record_global(1, RECORD));
+    code.extend(ins(MAP_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(record_global(1, RECORD));
+
+    assert_eq!(run(&program(&constants(), &code)).unwrap(), vec![text("1.5"), text("2")]);
+}
+
+#[test]
+fn map_get_reads_map_constants() {
+    let mut constants = constants();
+    constants.push(map(&[(int(-3), string("minus three")), (string("nested"), map(&[]))]));
+    constants.push(int(-3));
+    let mut code = ins(MAP_GET, &[(0, GLOBAL), (6, CONSTANT), (7, CONSTANT)]);
+    code.extend(record_global(0, RECORD));
+    assert_eq!(run(&program(&constants, &code)).unwrap(), vec![text("minus three")]);
+
+    let missing = ins(MAP_GET, &[(0, GLOBAL), (6, CONSTANT), (1, CONSTANT)]);
+    assert_error(run(&program(&constants, &missing)), "MISSING_KEY");
+}
+
+#[test]
+fn missing_keys_are_errors() {
+    let mut code = build_map();
+    code.extend(ins(MAP_GET, &[(1, GLOBAL), (0, GLOBAL), (5, CONSTANT)]));
+    assert_error(run(&program(&constants(), &code)), "MISSING_KEY");
+}
+
+#[test]
+fn keys_of_the_wrong_type_are_errors() {
+    let wrong_keys = [
+        ins(MAP_SET, &[(0, GLOBAL), (4, CONSTANT), (3, CONSTANT)]),
+        ins(MAP_GET, &[(1, GLOBAL), (0, GLOBAL), (4, CONSTANT)]),
+        ins(MAP_CONTAINS, &[(1, GLOBAL), (0, GLOBAL), (4, CONSTANT)]),
+        ins(MAP_REMOVE, &[(0, GLOBAL), (4, CONSTANT)]),
+    ];
+    for instruction in wrong_keys {
+        let mut code = build_map();
+        code.extend(instruction);
+        assert_error(run(&program(&constants(), &code)), "INVALID_OPERATION");
+    }
+}
+
+#[test]
+fn map_instructions_on_other_types_are_errors() {
+    let not_maps = [
+        ins(MAP_LEN, &[(1, GLOBAL), (2, CONSTANT)]),
+        ins(MAP_KEYS, &[(1, GLOBAL), (3, CONSTANT)]),
+        ins(MAP_GET, &[(1, GLOBAL), (2, CONSTANT), (0, CONSTANT)]),
+        ins(MAP_CONTAINS, &[(1, GLOBAL), (2, CONSTANT), (0, CONSTANT)]),
+        ins(MAP_SET, &[(1, GLOBAL), (0, CONSTANT), (2, CONSTANT)]),
+        ins(MAP_REMOVE, &[(1, GLOBAL), (0, CONSTANT)]),
+    ];
+    for instruction in not_maps {
+        let mut code = build_map();
+        code.extend(ins(MAP_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+        code.extend(instruction);
+        assert_error(run(&program(&constants(), &code)), "INVALID_OPERATION");
+    }
+}
+
+#[test]
+fn map_constants_with_wrong_key_types_are_rejected() {
+    let bad_key = program(&[map(&[(double(1.0), int(1))])], &[]);
+    let err = read_bytecode(&bad_key).err().expect("a double map key should not be readable");
+    assert!(err.to_string().contains("map key"), "{}", err);
+}
+
+#[test]
+fn map_instructions_round_trip_through_the_writer() {
+    let mut constants = constants();
+    constants.push(map(&[(int(1), map(&[(string("inner"), double(0.25))])), (string("list"), int(2))]));
+    let mut code = build_map();
+    code.extend(ins(MAP_GET, &[(1, GLOBAL), (6, CONSTANT), (1, CONSTANT)]));
+    code.extend(ins(MAP_REMOVE, &[(0, GLOBAL), (1, CONSTANT)]));
+    code.extend(ins(MAP_CONTAINS, &[(1, GLOBAL), (0, GLOBAL), (0, CONSTANT)]));
+    code.extend(ins(MAP_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(ins(MAP_KEYS, &[(1, GLOBAL), (0, GLOBAL)]));
+
+    let original = read_bytecode(&program(&constants, &code)).unwrap();
+    let written = write_bytecode(&original).unwrap();
+    let read_back = read_bytecode(&written).unwrap();
+
+    assert_eq!(read_back.constant_pool, original.constant_pool);
+    assert_eq!(format!("{:?}", read_back.instructions), format!("{:?}", original.instructions));
+    assert_eq!(write_bytecode(&read_back).unwrap(), written);
+}
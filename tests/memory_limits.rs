@@ -0,0 +1,177 @@
+use resurgence::bytecode::read_bytecode;
+use resurgence::codegen::{self, RVMLocation, RVMReference};
+use resurgence::{CodeHolder, Constant, ExecutionEngine, Interpreter, MemoryLimits, ResurgenceError};
+
+const ALLOC: u8 = 0x01;
+const JUMP: u8 = 0x03;
+const CPY: u8 = 0x07;
+const ADD: u8 = 0x0B;
+const VEC_NEW: u8 = 0x26;
+const VEC_GET: u8 = 0x27;
+const VEC_PUSH: u8 = 0x29;
+
+const CONSTANT: u8 = 0x01;
+const LOCAL: u8 = 0x04;
+const AS_IS: u8 = 0x01;
+
+/// Limits small enough for the tests to reach quickly
+fn small_limits() -> MemoryLimits {
+    MemoryLimits {
+        max_registers: 100,
+        max_globals: 100,
+        max_stack: 100,
+        max_string_length: 100,
+        max_vector_size: 100,
+    }
+}
+
+fn run(holder: CodeHolder, limits: MemoryLimits) -> Result<(), ResurgenceError> {
+    let mut interpreter = Interpreter::from(holder);
+    interpreter.set_memory_limits(limits);
+    interpreter.set_fuel(Some(100_000));
+    interpreter.execute_instruction(0)
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+/// Encodes a local register operand
+fn local(index: u32) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(LOCAL);
+    buf
+}
+
+/// Builds version 7.6 bytecode with a single string constant and the given instructions
+fn string_program(constant: &str, code: &[u8]) -> CodeHolder {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&1u32.to_be_bytes());
+    buf.push(0x03);
+    buf.extend_from_slice(&(constant.len() as u64).to_be_bytes());
+    buf.extend_from_slice(constant.as_bytes());
+    // empty imports and exports tables
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+    read_bytecode(&buf).unwrap()
+}
+
+#[test]
+fn default_limits_reject_huge_alloc() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, u32::MAX);
+
+    assert_error(run(holder, MemoryLimits::default()), "MEMORY_LIMIT");
+}
+
+#[test]
+fn limits_can_be_changed() {
+    let mut interpreter = Interpreter::from(CodeHolder::new());
+    assert_eq!(interpreter.memory_limits(), MemoryLimits::default());
+    interpreter.set_memory_limits(small_limits());
+    assert_eq!(interpreter.memory_limits(), small_limits());
+}
+
+#[test]
+fn global_registers_are_limited() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_frame_alloc(&mut holder, 10, RVMLocation::GLOBAL);
+    codegen::generate_jump(&mut holder, -1);
+
+    assert_error(run(holder, small_limits()), "MEMORY_LIMIT");
+}
+
+#[test]
+fn empty_stack_frames_are_limited() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 0);
+    codegen::generate_jump(&mut holder, -1);
+
+    assert_error(run(holder, small_limits()), "MEMORY_LIMIT");
+}
+
+#[test]
+fn freed_registers_can_be_allocated_again() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 50);
+    codegen::generate_frame_alloc(&mut holder, 40, RVMLocation::LOCAL);
+    codegen::generate_frame_free(&mut holder, 40, RVMLocation::LOCAL);
+    codegen::generate_free(&mut holder, 1);
+    codegen::generate_alloc(&mut holder, 90);
+    codegen::generate_return(&mut holder);
+
+    assert!(run(holder, small_limits()).is_ok());
+}
+
+#[test]
+fn stack_depth_is_limited() {
+    let mut holder = CodeHolder::new();
+    let value = codegen::generate_int_constant(&mut holder, 1);
+    codegen::generate_stack_push(&mut holder, (value, RVMReference::AS_IS));
+    codegen::generate_jump(&mut holder, -1);
+
+    assert_error(run(holder, small_limits()), "MEMORY_LIMIT");
+}
+
+#[test]
+fn string_length_is_limited() {
+    // copy the string into a register, then keep appending it to itself
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(CPY);
+    code.extend(local(0));
+    code.push(AS_IS);
+    code.extend(0u32.to_be_bytes());
+    code.extend([CONSTANT, AS_IS]);
+    code.push(ADD);
+    code.extend(local(0));
+    code.extend(local(0));
+    code.extend(local(0));
+    code.push(JUMP);
+    code.extend((-1i64).to_be_bytes());
+
+    assert_error(run(string_program("ab", &code), small_limits()), "MEMORY_LIMIT");
+}
+
+#[test]
+fn nested_vectors_are_limited() {
+    // pushing a vector into itself doubles the amount of nested elements every time
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(VEC_NEW);
+    code.extend(local(0));
+    code.push(VEC_PUSH);
+    code.extend(local(0));
+    code.extend(local(0));
+    code.push(JUMP);
+    code.extend((-1i64).to_be_bytes());
+
+    let mut interpreter = Interpreter::from(string_program("", &code));
+    interpreter.set_memory_limits(small_limits());
+    interpreter.set_fuel(Some(1000));
+    assert_error(interpreter.execute_instruction(0), "MEMORY_LIMIT");
+    // the doubling is stopped long before the fuel runs out
+    assert!(interpreter.remaining_fuel().unwrap() > 950);
+}
+
+#[test]
+fn copied_elements_are_limited() {
+    // the constant pool is not limited, so an element copied out of it has to be checked
+    let mut code = vec![ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(VEC_GET);
+    code.extend(local(0));
+    code.extend(0u32.to_be_bytes());
+    code.push(CONSTANT);
+    code.extend(1u32.to_be_bytes());
+    code.push(CONSTANT);
+
+    let mut holder = string_program("", &code);
+    holder.constant_pool[0] = Constant::Vec(vec![Constant::String("a".repeat(200))]);
+    holder.constant_pool.push(Constant::Int(0));
+    assert_error(run(holder, small_limits()), "MEMORY_LIMIT");
+}
@@ -0,0 +1,60 @@
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{codegen, CodeHolder, Interpreter, ModuleMetadata, SemanticVersion};
+
+fn module(min_host_api: SemanticVersion) -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    codegen::generate_return(&mut holder);
+
+    let mut metadata = ModuleMetadata {
+        name: "better-crafting".to_string(),
+        version: SemanticVersion::new(1, 4, 2),
+        author: "someone".to_string(),
+        description: "Adds more recipes".to_string(),
+        min_host_api,
+        ..Default::default()
+    };
+    metadata.properties.insert("license".to_string(), "MIT".to_string());
+    holder.metadata = Some(metadata);
+    holder
+}
+
+#[test]
+fn metadata_round_trips() {
+    let holder = module(SemanticVersion::new(2, 1, 0));
+    let read = read_bytecode(&write_bytecode(&holder).unwrap()).unwrap();
+    assert_eq!(read.metadata, holder.metadata);
+
+    let read = read_bytecode(&write_bytecode(&CodeHolder::new()).unwrap()).unwrap();
+    assert_eq!(read.metadata, None);
+}
+
+#[test]
+fn version_compatibility() {
+    let required = SemanticVersion::new(2, 1, 0);
+    assert!(SemanticVersion::new(2, 1, 0).satisfies(&required));
+    assert!(SemanticVersion::new(2, 3, 1).satisfies(&required));
+    assert!(!SemanticVersion::new(2, 0, 9).satisfies(&required));
+    assert!(!SemanticVersion::new(3, 0, 0).satisfies(&required));
+    assert_eq!(required.to_string(), "2.1.0");
+}
+
+#[test]
+fn host_checks_module_before_loading() {
+    let holder = module(SemanticVersion::new(2, 1, 0));
+    assert!(holder.check_host_api(&SemanticVersion::new(2, 4, 0)).is_ok());
+    assert!(holder.check_host_api(&SemanticVersion::new(1, 9, 0)).is_err());
+
+    // modules without metadata run everywhere
+    assert!(CodeHolder::new().check_host_api(&SemanticVersion::new(0, 0, 1)).is_ok());
+}
+
+#[test]
+fn resolve_imports_rejects_incompatible_modules() {
+    let mut it = Interpreter::from(module(SemanticVersion::new(2, 1, 0)));
+    it.set_host_api_version(SemanticVersion::new(2, 0, 0));
+    let err = it.resolve_imports().err().unwrap();
+    assert!(format!("{:?}", err).contains("INCOMPATIBLE_MODULE"));
+
+    it.set_host_api_version(SemanticVersion::new(2, 2, 0));
+    assert!(it.resolve_imports().is_ok());
+}
@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::io::Error;
+
+use resurgence::bytecode::read_bytecode;
+use resurgence::{ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const MOV: u8 = 0x06;
+const CPY: u8 = 0x07;
+const STACK_PUSH: u8 = 0x09;
+const FRAME_ALLOC: u8 = 0x15;
+const STACK_MOV: u8 = 0x17;
+const RET: u8 = 0x19;
+
+const CONSTANT: u8 = 0x01;
+const ACCUMULATOR: u8 = 0x02;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` on this thread
+    static RECORDED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = state.get_value_as_string()?;
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<String> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+/// Encodes a register operand
+fn reg(index: u32, location: u8) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(location);
+    buf
+}
+
+/// Builds version 7.6 bytecode with the string constant "kept", `record` as its only import,
+/// and the given instructions
+fn program(code: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&1u32.to_be_bytes());
+    buf.push(0x03);
+    buf.extend_from_slice(&4u64.to_be_bytes());
+    buf.extend_from_slice(b"kept");
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+    buf
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+/// Copies "kept" into the global register 0, moves it to `dst`, which has to fail with `kind`,
+/// and then records what is left in the global register 0
+fn mov_fails(dst: Vec<u8>, kind: &str) -> Vec<String> {
+    // 0: allocate, 1: copy, 2: move, 3: record the source, 5: return
+    let mut code = vec![FRAME_ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(GLOBAL);
+    code.push(CPY);
+    code.extend(reg(0, GLOBAL));
+    code.push(AS_IS);
+    code.extend(reg(0, CONSTANT));
+    code.push(AS_IS);
+    code.push(MOV);
+    code.extend(dst);
+    code.push(AS_IS);
+    code.extend(reg(0, GLOBAL));
+    code.push(AS_IS);
+    code.push(STACK_PUSH);
+    code.extend(reg(0, GLOBAL));
+    code.push(AS_IS);
+    code.push(EXT_CALL);
+    code.extend(0u64.to_be_bytes());
+    code.push(RET);
+
+    let mut interpreter = Interpreter::from(read_bytecode(&program(&code)).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    assert_error(interpreter.execute_instruction(0), kind);
+    assert!(interpreter.execute_instruction(3).is_ok());
+    take_recorded()
+}
+
+/// Pushes "kept" onto the stack, moves it to `dst`, which has to fail with `kind`, and then
+/// records what is left on the stack
+fn stack_mov_fails(dst: Vec<u8>, kind: &str) -> Vec<String> {
+    // 0: allocate, 1: push, 2: move, 3: record the top of the stack, 4: return
+    let mut code = vec![FRAME_ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(GLOBAL);
+    code.push(STACK_PUSH);
+    code.extend(reg(0, CONSTANT));
+    code.push(AS_IS);
+    code.push(STACK_MOV);
+    code.extend(dst);
+    code.push(AS_IS);
+    code.push(EXT_CALL);
+    code.extend(0u64.to_be_bytes());
+    code.push(RET);
+
+    let mut interpreter = Interpreter::from(read_bytecode(&program(&code)).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    assert_error(interpreter.execute_instruction(0), kind);
+    assert!(interpreter.execute_instruction(3).is_ok());
+    take_recorded()
+}
+
+#[test]
+fn failed_mov_keeps_the_source() {
+    assert_eq!(mov_fails(reg(5, GLOBAL), "REGISTER_OUT_OF_BOUNDS"), ["kept"]);
+    assert_eq!(mov_fails(reg(0, ACCUMULATOR), "INVALID_OPERATION"), ["kept"]);
+}
+
+#[test]
+fn failed_stack_mov_keeps_the_value_on_the_stack() {
+    assert_eq!(stack_mov_fails(reg(5, GLOBAL), "REGISTER_OUT_OF_BOUNDS"), ["kept"]);
+    assert_eq!(stack_mov_fails(reg(0, ACCUMULATOR), "INVALID_OPERATION"), ["kept"]);
+}
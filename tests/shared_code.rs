@@ -0,0 +1,146 @@
+use std::io::Error;
+use std::sync::Arc;
+use std::thread;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::codegen::{self, RVMLocation, RVMReference};
+use resurgence::{CodeHolder, ExecutionEngine, Interpreter, MemoryLimits, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const RET: u8 = 0x19;
+
+/// Limits that only let the code below run once per interpreter
+fn tight_limits() -> MemoryLimits {
+    MemoryLimits {
+        max_registers: 2,
+        max_globals: 1,
+        max_stack: 1,
+        ..MemoryLimits::default()
+    }
+}
+
+/// Allocates a stack frame and a global register and pushes a value, without cleaning up
+fn leaves_state_behind() -> CodeHolder {
+    let mut holder = CodeHolder::new();
+    let value = codegen::generate_int_constant(&mut holder, 1);
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_frame_alloc(&mut holder, 1, RVMLocation::GLOBAL);
+    codegen::generate_stack_push(&mut holder, (value, RVMReference::AS_IS));
+    codegen::generate_return(&mut holder);
+    holder
+}
+
+/// Builds version 7.6 bytecode that calls `push_one` and returns
+fn calls_push_one() -> CodeHolder {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&0u32.to_be_bytes());
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&8u64.to_be_bytes());
+    buf.extend_from_slice(b"push_one");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.push(EXT_CALL);
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.push(RET);
+    read_bytecode(&buf).unwrap()
+}
+
+fn push_one(state: &mut ResurgenceState) -> Result<(), Error> {
+    state.push_i64(1);
+    Ok(())
+}
+
+fn assert_error(res: Result<(), ResurgenceError>, kind: &str) {
+    match res {
+        Ok(()) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+#[test]
+fn interpreters_share_one_copy_of_the_code() {
+    let code = Arc::new(leaves_state_behind());
+    let interpreters: Vec<Interpreter> = (0..10).map(|_| Interpreter::from_shared(Arc::clone(&code))).collect();
+
+    assert_eq!(Arc::strong_count(&code), 11);
+    for interpreter in &interpreters {
+        assert!(Arc::ptr_eq(interpreter.code().holder(), &code));
+    }
+}
+
+#[test]
+fn interpreters_have_their_own_state() {
+    let code = Arc::new(leaves_state_behind());
+    for _ in 0..3 {
+        let mut interpreter = Interpreter::from_shared(Arc::clone(&code));
+        interpreter.set_memory_limits(tight_limits());
+        interpreter.execute_instruction(0).unwrap();
+        assert_error(interpreter.execute_instruction(0), "MEMORY_LIMIT");
+    }
+}
+
+#[test]
+fn interpreters_can_run_on_many_threads() {
+    let code = Arc::new(leaves_state_behind());
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let code = Arc::clone(&code);
+            thread::spawn(move || {
+                let mut interpreter = Interpreter::from_shared(code);
+                interpreter.execute_instruction(0)
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn imports_are_resolved_per_interpreter() {
+    let code = Arc::new(calls_push_one());
+    let mut registered = Interpreter::from_shared(Arc::clone(&code));
+    registered.register_function(push_one, String::from("push_one"));
+    let mut unregistered = Interpreter::from_shared(Arc::clone(&code));
+
+    registered.execute_instruction(0).unwrap();
+    assert_error(unregistered.execute_instruction(0), "MISSING_IMPORTS");
+}
+
+#[test]
+fn shared_interpreters_keep_functions_and_limits() {
+    let mut first = Interpreter::from(calls_push_one());
+    first.register_function(push_one, String::from("push_one"));
+    first.set_memory_limits(tight_limits());
+    first.execute_instruction(0).unwrap();
+
+    let mut second = first.share_code();
+    assert!(first.code().ptr_eq(&second.code()));
+    assert_eq!(second.memory_limits(), tight_limits());
+    second.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn lazily_loaded_code_can_be_shared() {
+    let bytes = write_bytecode(&leaves_state_behind()).unwrap();
+    let mut first = Interpreter::from_bytes(bytes).unwrap();
+    let mut second = first.share_code();
+
+    first.execute_instruction(0).unwrap();
+    second.execute_instruction(0).unwrap();
+}
+
+#[test]
+fn lazily_loaded_code_can_be_passed_to_other_interpreters() {
+    let bytes = write_bytecode(&leaves_state_behind()).unwrap();
+    let first = Interpreter::from_bytes(bytes).unwrap();
+    // the constants live in the bytecode buffer, so the handle has to carry them
+    assert!(first.code().holder().constant_pool.is_empty());
+
+    let code = first.code();
+    let mut interpreters: Vec<Interpreter> = (0..3).map(|_| Interpreter::from_shared(code.clone())).collect();
+    for interpreter in &mut interpreters {
+        assert!(interpreter.code().ptr_eq(&code));
+        interpreter.execute_instruction(0).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::io::Error;
+use std::sync::Arc;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::codegen;
+use resurgence::{CodeHolder, ExecutionEngine, Interpreter, MemoryLimits, ResurgenceError, ResurgenceState};
+
+const JUMP: u8 = 0x03;
+const EXT_CALL: u8 = 0x05;
+const CPY: u8 = 0x07;
+const STACK_PUSH: u8 = 0x09;
+const ADD: u8 = 0x0B;
+const FRAME_ALLOC: u8 = 0x15;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` on this thread
+    static RECORDED: RefCell<Vec<i64>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = state.get_i64()?;
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<i64> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+/// Encodes a register operand
+fn reg(index: u32, location: u8) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(location);
+    buf
+}
+
+/// Builds version 7.6 bytecode that counts up from `start` in a global register forever,
+/// passing every value to `record`
+fn counter_bytecode(start: i64) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&2u32.to_be_bytes());
+    for constant in [start, 1] {
+        buf.push(0x01);
+        buf.extend_from_slice(&constant.to_be_bytes());
+    }
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+
+    buf.push(FRAME_ALLOC);
+    buf.extend(2u32.to_be_bytes());
+    buf.push(GLOBAL);
+    buf.push(CPY);
+    buf.extend(reg(0, GLOBAL));
+    buf.push(AS_IS);
+    buf.extend(reg(0, CONSTANT));
+    buf.push(AS_IS);
+    // loop: add one, then record a copy of the value, as pushing it moves it out of the register
+    buf.push(ADD);
+    buf.extend(reg(0, GLOBAL));
+    buf.extend(reg(0, GLOBAL));
+    buf.extend(reg(1, CONSTANT));
+    buf.push(CPY);
+    buf.extend(reg(1, GLOBAL));
+    buf.push(AS_IS);
+    buf.extend(reg(0, GLOBAL));
+    buf.push(AS_IS);
+    buf.push(STACK_PUSH);
+    buf.extend(reg(1, GLOBAL));
+    buf.push(AS_IS);
+    buf.push(EXT_CALL);
+    buf.extend(0u64.to_be_bytes());
+    buf.push(JUMP);
+    buf.extend((-4i64).to_be_bytes());
+    buf
+}
+
+fn counter(start: i64) -> Arc<CodeHolder> {
+    Arc::new(read_bytecode(&counter_bytecode(start)).unwrap())
+}
+
+fn interpreter(code: &Arc<CodeHolder>) -> Interpreter {
+    let mut interpreter = Interpreter::from_shared(Arc::clone(code));
+    interpreter.register_function(record, String::from("record"));
+    interpreter
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+/// Runs the counter with `fuel` without stopping, and returns what it recorded
+fn uninterrupted(code: &Arc<CodeHolder>, fuel: u64) -> Vec<i64> {
+    let mut interpreter = interpreter(code);
+    interpreter.set_fuel(Some(fuel));
+    assert_error(interpreter.execute_instruction(0), "OUT_OF_FUEL");
+    take_recorded()
+}
+
+#[test]
+fn stopped_code_can_be_resumed() {
+    let code = counter(10);
+    let expected = uninterrupted(&code, 40);
+
+    let mut interpreter = interpreter(&code);
+    interpreter.set_fuel(Some(20));
+    assert_error(interpreter.execute_instruction(0), "OUT_OF_FUEL");
+    assert!(interpreter.is_suspended());
+    interpreter.set_fuel(Some(20));
+    assert_error(interpreter.resume(), "OUT_OF_FUEL");
+
+    assert_eq!(take_recorded(), expected);
+}
+
+#[test]
+fn interrupted_code_can_be_resumed() {
+    let code = counter(0);
+    let expected = uninterrupted(&code, 30);
+
+    let mut interpreter = interpreter(&code);
+    interpreter.set_fuel(Some(30));
+    interpreter.interrupt_handle().interrupt();
+    assert_error(interpreter.execute_instruction(0), "INTERRUPTED");
+    assert_error(interpreter.resume(), "OUT_OF_FUEL");
+
+    assert_eq!(take_recorded(), expected);
+}
+
+#[test]
+fn nothing_to_resume_is_an_error() {
+    let mut holder = CodeHolder::new();
+    codegen::generate_alloc(&mut holder, 1);
+    codegen::generate_return(&mut holder);
+    let mut interpreter = Interpreter::from(holder);
+
+    assert_error(interpreter.resume(), "INVALID_OPERATION");
+    interpreter.execute_instruction(0).unwrap();
+    assert!(!interpreter.is_suspended());
+    assert_error(interpreter.resume(), "INVALID_OPERATION");
+}
+
+#[test]
+fn snapshot_continues_in_another_interpreter() {
+    let code = counter(100);
+    let expected = uninterrupted(&code, 40);
+
+    let mut first = interpreter(&code);
+    first.set_fuel(Some(20));
+    assert_error(first.execute_instruction(0), "OUT_OF_FUEL");
+    let mut recorded = take_recorded();
+    let snapshot = first.snapshot().unwrap();
+
+    // the first interpreter keeps running, so the snapshot has to be independent of it
+    first.set_fuel(Some(100));
+    assert_error(first.resume(), "OUT_OF_FUEL");
+    take_recorded();
+
+    let mut second = interpreter(&code);
+    second.restore(&snapshot).unwrap();
+    assert!(second.is_suspended());
+    second.set_fuel(Some(20));
+    assert_error(second.resume(), "OUT_OF_FUEL");
+    recorded.extend(take_recorded());
+
+    assert_eq!(recorded, expected);
+}
+
+#[test]
+fn snapshot_can_be_restored_onto_lazily_loaded_code() {
+    let bytecode = write_bytecode(&counter(5)).unwrap();
+    let mut eager = interpreter(&Arc::new(read_bytecode(&bytecode).unwrap()));
+    eager.set_fuel(Some(20));
+    assert_error(eager.execute_instruction(0), "OUT_OF_FUEL");
+    let expected = take_recorded().last().unwrap() + 1;
+
+    let mut lazy = Interpreter::from_bytes(bytecode).unwrap();
+    lazy.register_function(record, String::from("record"));
+    lazy.restore(&eager.snapshot().unwrap()).unwrap();
+    lazy.set_fuel(Some(4));
+    assert_error(lazy.resume(), "OUT_OF_FUEL");
+    assert_eq!(take_recorded(), vec![expected]);
+}
+
+#[test]
+fn snapshot_of_different_code_is_rejected() {
+    let mut first = interpreter(&counter(0));
+    first.set_fuel(Some(10));
+    assert_error(first.execute_instruction(0), "OUT_OF_FUEL");
+    take_recorded();
+
+    let mut second = interpreter(&counter(1));
+    let err = second.restore(&first.snapshot().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("different code"), "{}", err);
+    assert!(!second.is_suspended());
+}
+
+#[test]
+fn corrupted_snapshot_is_rejected() {
+    let code = counter(0);
+    let mut first = interpreter(&code);
+    first.set_fuel(Some(10));
+    assert_error(first.execute_instruction(0), "OUT_OF_FUEL");
+    take_recorded();
+    let mut snapshot = first.snapshot().unwrap();
+    let middle = snapshot.len() / 2;
+    snapshot[middle] ^= 0xFF;
+
+    let err = interpreter(&code).restore(&snapshot).unwrap_err();
+    assert!(err.to_string().contains("checksum"), "{}", err);
+    assert!(interpreter(&code).restore(&snapshot[..3]).is_err());
+    assert!(interpreter(&code).restore(b"not a snapshot").is_err());
+}
+
+#[test]
+fn restored_state_is_checked_against_the_limits() {
+    let code = counter(0);
+    let mut first = interpreter(&code);
+    first.set_fuel(Some(10));
+    assert_error(first.execute_instruction(0), "OUT_OF_FUEL");
+    take_recorded();
+
+    let mut second = interpreter(&code);
+    second.set_memory_limits(MemoryLimits {
+        max_globals: 0,
+        ..MemoryLimits::default()
+    });
+    let err = second.restore(&first.snapshot().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("memory limits"), "{}", err);
+    assert!(!second.is_suspended());
+}
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::io::Error;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const JUMP: u8 = 0x03;
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const ADD: u8 = 0x0B;
+const GREATER: u8 = 0x11;
+const LESS: u8 = 0x12;
+const FRAME_ALLOC: u8 = 0x15;
+const RET: u8 = 0x19;
+const STR_LEN: u8 = 0x23;
+const SUBSTR: u8 = 0x24;
+const CHAR_AT: u8 = 0x25;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` on this thread
+    static RECORDED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = state.get_value_as_string()?;
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<String> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+fn int(value: i64) -> Vec<u8> {
+    let mut buf = vec![0x01];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut buf = vec![0x03];
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value.as_bytes());
+    buf
+}
+
+/// Encodes a register operand
+fn reg(index: u32, location: u8) -> Vec<u8> {
+    let mut buf = index.to_be_bytes().to_vec();
+    buf.push(location);
+    buf
+}
+
+/// Encodes an instruction taking the global register 0 as its destination, followed by the
+/// given constants
+fn to_global(op: u8, constants: &[u32]) -> Vec<u8> {
+    let mut buf = vec![op];
+    buf.extend(reg(0, GLOBAL));
+    for constant in constants {
+        buf.extend(reg(*constant, CONSTANT));
+    }
+    buf
+}
+
+/// Builds version 7.6 bytecode with the given constants, `record` as its only import, and the
+/// given instructions
+fn program(constants: &[Vec<u8>], code: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.extend_from_slice(constant);
+    }
+    buf.extend_from_slice(&1u64.to_be_bytes());
+    buf.extend_from_slice(&6u64.to_be_bytes());
+    buf.extend_from_slice(b"record");
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.extend_from_slice(code);
+    buf
+}
+
+/// Builds bytecode that runs `instruction`, which stores its result in the global register 0,
+/// and passes the result to `record`
+fn storing_program(constants: &[Vec<u8>], instruction: &[u8]) -> Vec<u8> {
+    let mut code = vec![FRAME_ALLOC];
+    code.extend(1u32.to_be_bytes());
+    code.push(GLOBAL);
+    code.extend_from_slice(instruction);
+    code.push(STACK_PUSH);
+    code.extend(reg(0, GLOBAL));
+    code.push(AS_IS);
+    code.push(EXT_CALL);
+    code.extend(0u64.to_be_bytes());
+    code.push(RET);
+    program(constants, &code)
+}
+
+fn run(bytecode: &[u8]) -> Result<Vec<String>, ResurgenceError> {
+    let mut interpreter = Interpreter::from(read_bytecode(bytecode).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    let res = interpreter.execute_instruction(0);
+    let recorded = take_recorded();
+    res.map(|()| recorded)
+}
+
+/// Runs `instruction` on the constants and returns the string form of its result
+fn result_of(constants: &[Vec<u8>], instruction: &[u8]) -> Result<String, ResurgenceError> {
+    run(&storing_program(constants, instruction)).map(|recorded| recorded[0].clone())
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+/// Returns true if the comparison `op` holds for the two strings
+fn compare(op: u8, left: &str, right: &str) -> bool {
+    // 0: compare, 1: skip the record if it did not hold, 2: record, 4: return
+    let mut code = vec![op];
+    code.extend(reg(0, CONSTANT));
+    code.extend(reg(1, CONSTANT));
+    code.push(JUMP);
+    code.extend(3i64.to_be_bytes());
+    code.push(STACK_PUSH);
+    code.extend(reg(2, CONSTANT));
+    code.push(AS_IS);
+    code.push(EXT_CALL);
+    code.extend(0u64.to_be_bytes());
+    code.push(RET);
+
+    let recorded = run(&program(&[string(left), string(right), string("holds")], &code)).unwrap();
+    !recorded.is_empty()
+}
+
+#[test]
+fn add_concatenates_strings() {
+    let constants = [string("héllo, "), string("wörld")];
+    assert_eq!(result_of(&constants, &to_global(ADD, &[0, 1])).unwrap(), "héllo, wörld");
+}
+
+#[test]
+fn add_of_a_string_and_a_number_is_an_error() {
+    let constants = [string("one"), int(1)];
+    assert_error(result_of(&constants, &to_global(ADD, &[0, 1])), "INVALID_OPERATION");
+}
+
+#[test]
+fn str_len_counts_characters() {
+    assert_eq!(result_of(&[string("héllo")], &to_global(STR_LEN, &[0])).unwrap(), "5");
+    assert_eq!(result_of(&[string("")], &to_global(STR_LEN, &[0])).unwrap(), "0");
+    assert_error(result_of(&[int(5)], &to_global(STR_LEN, &[0])), "INVALID_OPERATION");
+}
+
+#[test]
+fn substr_takes_characters() {
+    let constants = [string("héllo wörld"), int(1), int(4), int(6), int(5), int(0)];
+    assert_eq!(result_of(&constants, &to_global(SUBSTR, &[0, 1, 2])).unwrap(), "éllo");
+    assert_eq!(result_of(&constants, &to_global(SUBSTR, &[0, 3, 4])).unwrap(), "wörld");
+    assert_eq!(result_of(&constants, &to_global(SUBSTR, &[0, 5, 5])).unwrap(), "");
+}
+
+#[test]
+fn substr_out_of_range_is_an_error() {
+    let constants = [string("héllo"), int(3), int(-1), int(1)];
+    assert_error(result_of(&constants, &to_global(SUBSTR, &[0, 1, 1])), "INDEX_OUT_OF_BOUNDS");
+    assert_error(result_of(&constants, &to_global(SUBSTR, &[0, 2, 3])), "INDEX_OUT_OF_BOUNDS");
+    assert_error(result_of(&constants, &to_global(SUBSTR, &[0, 3, 2])), "INDEX_OUT_OF_BOUNDS");
+    assert_error(result_of(&constants, &to_global(SUBSTR, &[3, 3, 3])), "INVALID_OPERATION");
+}
+
+#[test]
+fn char_at_indexes_characters() {
+    let constants = [string("héllo"), int(1), int(4), int(5), int(-1)];
+    assert_eq!(result_of(&constants, &to_global(CHAR_AT, &[0, 1])).unwrap(), "é");
+    assert_eq!(result_of(&constants, &to_global(CHAR_AT, &[0, 2])).unwrap(), "o");
+    assert_error(result_of(&constants, &to_global(CHAR_AT, &[0, 3])), "INDEX_OUT_OF_BOUNDS");
+    assert_error(result_of(&constants, &to_global(CHAR_AT, &[0, 4])), "INDEX_OUT_OF_BOUNDS");
+}
+
+#[test]
+fn strings_compare_by_code_point() {
+    assert!(compare(LESS, "apple", "banana"));
+    assert!(!compare(LESS, "banana", "apple"));
+    assert!(compare(LESS, "Zebra", "apple"));
+    assert!(compare(GREATER, "é", "z"));
+    assert!(!compare(GREATER, "same", "same"));
+}
+
+#[test]
+fn string_instructions_round_trip_through_the_writer() {
+    let mut code = to_global(STR_LEN, &[0]);
+    code.extend(to_global(SUBSTR, &[0, 1, 1]));
+    code.extend(to_global(CHAR_AT, &[0, 1]));
+
+    let original = read_bytecode(&storing_program(&[string("text"), int(1)], &code)).unwrap();
+    let written = write_bytecode(&original).unwrap();
+    let read_back = read_bytecode(&written).unwrap();
+
+    assert_eq!(format!("{:?}", read_back.instructions), format!("{:?}", original.instructions));
+    assert_eq!(write_bytecode(&read_back).unwrap(), written);
+}
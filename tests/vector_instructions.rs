@@ -0,0 +1,227 @@
+use std::cell::RefCell;
+use std::io::Error;
+
+use resurgence::bytecode::{read_bytecode, write_bytecode};
+use resurgence::{Constant, ExecutionEngine, Interpreter, ResurgenceError, ResurgenceState};
+
+const EXT_CALL: u8 = 0x05;
+const STACK_PUSH: u8 = 0x09;
+const FRAME_ALLOC: u8 = 0x15;
+const RET: u8 = 0x19;
+const VEC_NEW: u8 = 0x26;
+const VEC_GET: u8 = 0x27;
+const VEC_SET: u8 = 0x28;
+const VEC_PUSH: u8 = 0x29;
+const VEC_POP: u8 = 0x2A;
+const VEC_LEN: u8 = 0x2B;
+
+const CONSTANT: u8 = 0x01;
+const GLOBAL: u8 = 0x03;
+const AS_IS: u8 = 0x01;
+
+thread_local! {
+    /// Values passed to `record` and `record_vec` on this thread
+    static RECORDED: RefCell<Vec<Constant>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = Constant::String(state.get_value_as_string()?);
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+fn record_vec(state: &mut ResurgenceState) -> Result<(), Error> {
+    let value = Constant::Vec(state.get_vec()?);
+    RECORDED.with(|recorded| recorded.borrow_mut().push(value));
+    Ok(())
+}
+
+/// Returns and clears the values recorded so far
+fn take_recorded() -> Vec<Constant> {
+    RECORDED.with(|recorded| recorded.take())
+}
+
+fn int(value: i64) -> Vec<u8> {
+    let mut buf = vec![0x01];
+    buf.extend(value.to_be_bytes());
+    buf
+}
+
+fn string(value: &str) -> Vec<u8> {
+    let mut buf = vec![0x03];
+    buf.extend((value.len() as u64).to_be_bytes());
+    buf.extend(value.as_bytes());
+    buf
+}
+
+/// Encodes a vector constant of encoded elements
+fn vector(elements: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = vec![0x06];
+    buf.extend((elements.len() as u64).to_be_bytes());
+    for element in elements {
+        buf.extend(element);
+    }
+    buf
+}
+
+/// Encodes an instruction with register operands
+fn ins(op: u8, registers: &[(u32, u8)]) -> Vec<u8> {
+    let mut buf = vec![op];
+    for (index, location) in registers {
+        buf.extend(index.to_be_bytes());
+        buf.push(*location);
+    }
+    buf
+}
+
+/// Encodes instructions that pass the global register `index` to the import `import`
+fn record_global(index: u32, import: u64) -> Vec<u8> {
+    let mut buf = ins(STACK_PUSH, &[(index, GLOBAL)]);
+    buf.push(AS_IS);
+    buf.push(EXT_CALL);
+    buf.extend(import.to_be_bytes());
+    buf
+}
+
+/// Builds version 7.6 bytecode with the given constants, `record` and `record_vec` as its
+/// imports, and two global registers allocated before the given instructions
+fn program(constants: &[Vec<u8>], code: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x52, 0x56, 0x4D, 0x88, 0x00, 0x07, 0x00, 0x06];
+    buf.extend_from_slice(&(constants.len() as u32).to_be_bytes());
+    for constant in constants {
+        buf.extend_from_slice(constant);
+    }
+    buf.extend_from_slice(&2u64.to_be_bytes());
+    for import in ["record", "record_vec"] {
+        buf.extend_from_slice(&(import.len() as u64).to_be_bytes());
+        buf.extend_from_slice(import.as_bytes());
+    }
+    buf.extend_from_slice(&0u64.to_be_bytes());
+    buf.push(FRAME_ALLOC);
+    buf.extend(2u32.to_be_bytes());
+    buf.push(GLOBAL);
+    buf.extend_from_slice(code);
+    buf.push(RET);
+    buf
+}
+
+fn run(bytecode: &[u8]) -> Result<Vec<Constant>, ResurgenceError> {
+    let mut interpreter = Interpreter::from(read_bytecode(bytecode).unwrap());
+    interpreter.register_function(record, String::from("record"));
+    interpreter.register_function(record_vec, String::from("record_vec"));
+    let res = interpreter.execute_instruction(0);
+    let recorded = take_recorded();
+    res.map(|()| recorded)
+}
+
+fn assert_error<T>(res: Result<T, ResurgenceError>, kind: &str) {
+    match res {
+        Ok(_) => panic!("expected a {} error", kind),
+        Err(err) => assert!(format!("{:?}", err).contains(kind), "{:?}", err),
+    }
+}
+
+fn text(value: &str) -> Constant {
+    Constant::String(String::from(value))
+}
+
+/// Constants used by the programs below
+fn constants() -> Vec<Vec<u8>> {
+    vec![int(0), int(1), int(2), int(-1), string("two"), int(10)]
+}
+
+/// Instructions that build the vector `[0, "two", 2]` in the global register 0
+fn build_vector() -> Vec<u8> {
+    let mut code = ins(VEC_NEW, &[(0, GLOBAL)]);
+    for element in [0, 4, 2] {
+        code.extend(ins(VEC_PUSH, &[(0, GLOBAL), (element, CONSTANT)]));
+    }
+    code
+}
+
+#[test]
+fn vectors_can_be_built_and_read() {
+    let mut code = build_vector();
+    code.extend(ins(VEC_SET, &[(0, GLOBAL), (0, CONSTANT), (5, CONSTANT)]));
+    code.extend(ins(VEC_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(record_global(1, 0));
+    code.extend(ins(VEC_GET, &[(1, GLOBAL), (0, GLOBAL), (1, CONSTANT)]));
+    code.extend(record_global(1, 0));
+    code.extend(ins(VEC_POP, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(record_global(1, 0));
+    code.extend(record_global(0, 1));
+
+    let recorded = run(&program(&constants(), &code)).unwrap();
+    assert_eq!(
+        recorded,
+        vec![text("3"), text("two"), text("2"), Constant::Vec(vec![Constant::Int(10), text("two")])]
+    );
+}
+
+#[test]
+fn vec_get_reads_vector_constants() {
+    let mut constants = constants();
+    constants.push(vector(&[int(7), string("eight")]));
+    let mut code = ins(VEC_GET, &[(0, GLOBAL), (6, CONSTANT), (1, CONSTANT)]);
+    code.extend(record_global(0, 0));
+
+    assert_eq!(run(&program(&constants, &code)).unwrap(), vec![text("eight")]);
+}
+
+#[test]
+fn indexes_out_of_range_are_errors() {
+    let out_of_range = [
+        ins(VEC_GET, &[(1, GLOBAL), (0, GLOBAL), (3, CONSTANT)]),
+        ins(VEC_SET, &[(0, GLOBAL), (3, CONSTANT), (0, CONSTANT)]),
+        ins(VEC_SET, &[(0, GLOBAL), (5, CONSTANT), (0, CONSTANT)]),
+    ];
+    for instruction in out_of_range {
+        let mut code = build_vector();
+        code.extend(instruction);
+        assert_error(run(&program(&constants(), &code)), "INDEX_OUT_OF_BOUNDS");
+    }
+
+    let mut pop_past_empty = ins(VEC_NEW, &[(0, GLOBAL)]);
+    pop_past_empty.extend(ins(VEC_POP, &[(1, GLOBAL), (0, GLOBAL)]));
+    assert_error(run(&program(&constants(), &pop_past_empty)), "INDEX_OUT_OF_BOUNDS");
+}
+
+#[test]
+fn vector_instructions_on_other_types_are_errors() {
+    let not_vectors = [
+        ins(VEC_LEN, &[(1, GLOBAL), (4, CONSTANT)]),
+        ins(VEC_GET, &[(1, GLOBAL), (4, CONSTANT), (0, CONSTANT)]),
+        ins(VEC_PUSH, &[(1, GLOBAL), (0, CONSTANT)]),
+        ins(VEC_POP, &[(0, GLOBAL), (1, GLOBAL)]),
+    ];
+    for instruction in not_vectors {
+        let mut code = ins(VEC_NEW, &[(0, GLOBAL)]);
+        code.extend(ins(VEC_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+        code.extend(instruction);
+        assert_error(run(&program(&constants(), &code)), "INVALID_OPERATION");
+    }
+
+    // the index has to be an integer
+    let mut code = build_vector();
+    code.extend(ins(VEC_GET, &[(1, GLOBAL), (0, GLOBAL), (4, CONSTANT)]));
+    assert_error(run(&program(&constants(), &code)), "INVALID_OPERATION");
+}
+
+#[test]
+fn vector_instructions_round_trip_through_the_writer() {
+    let mut constants = constants();
+    constants.push(vector(&[int(1), vector(&[string("nested")])]));
+    let mut code = build_vector();
+    code.extend(ins(VEC_SET, &[(0, GLOBAL), (0, CONSTANT), (6, CONSTANT)]));
+    code.extend(ins(VEC_GET, &[(1, GLOBAL), (0, GLOBAL), (1, CONSTANT)]));
+    code.extend(ins(VEC_POP, &[(1, GLOBAL), (0, GLOBAL)]));
+    code.extend(ins(VEC_LEN, &[(1, GLOBAL), (0, GLOBAL)]));
+
+    let original = read_bytecode(&program(&constants, &code)).unwrap();
+    let written = write_bytecode(&original).unwrap();
+    let read_back = read_bytecode(&written).unwrap();
+
+    assert_eq!(read_back.constant_pool, original.constant_pool);
+    assert_eq!(format!("{:?}", read_back.instructions), format!("{:?}", original.instructions));
+    assert_eq!(write_bytecode(&read_back).unwrap(), written);
+}